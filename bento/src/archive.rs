@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::{BentoError, CompilationResult, create_parent_dir};
+
+const MAGIC: &[u8; 4] = b"BNTA";
+// Version 2 entries carry the `CompilationResult::to_bytes` header.
+const VERSION: u32 = 2;
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8;
+
+/// Many `CompilationResult`s stored in one file, so large projects don't pay for a file per
+/// shader.
+///
+/// The file starts with a magic tag and format version, followed by an index of name to
+/// offset/length and the concatenated bincode-encoded results. Results are only decoded when
+/// requested through `get`.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderArchive {
+    index: BTreeMap<String, (usize, usize)>,
+    data: Vec<u8>,
+}
+
+impl ShaderArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `result` under `name`, replacing any result already stored under it.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        result: &CompilationResult,
+    ) -> Result<(), BentoError> {
+        let bytes = result.to_bytes()?;
+        let offset = self.data.len();
+        self.data.extend_from_slice(&bytes);
+        self.index.insert(name.into(), (offset, bytes.len()));
+        Ok(())
+    }
+
+    /// Decodes the result stored under `name`. Entries that fail to decode read as missing.
+    pub fn get(&self, name: &str) -> Option<CompilationResult> {
+        let (offset, len) = *self.index.get(name)?;
+        CompilationResult::from_bytes(&self.data[offset..offset + len]).ok()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    /// Names of the stored results, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Encodes the archive, dropping bytes left behind by replaced entries.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BentoError> {
+        let mut index = BTreeMap::new();
+        let mut data = Vec::new();
+        for (name, (offset, len)) in &self.index {
+            index.insert(name.as_str(), (data.len() as u64, *len as u64));
+            data.extend_from_slice(&self.data[*offset..*offset + *len]);
+        }
+
+        let index = bincode::serialize(&index)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + index.len() + data.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&index);
+        bytes.extend_from_slice(&data);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BentoError> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(BentoError::InvalidInput(
+                "Not a shader archive: missing archive header".into(),
+            ));
+        }
+
+        let version = u32::from_le_bytes(bytes[4..8].try_into().expect("4-byte version"));
+        if version != VERSION {
+            return Err(BentoError::InvalidInput(format!(
+                "Unsupported shader archive version {version}, expected {VERSION}"
+            )));
+        }
+
+        let index_len = u64::from_le_bytes(bytes[8..16].try_into().expect("8-byte length"));
+        let body = &bytes[HEADER_LEN..];
+        let index_len = usize::try_from(index_len)
+            .ok()
+            .filter(|len| *len <= body.len())
+            .ok_or_else(|| BentoError::InvalidInput("Truncated shader archive index".into()))?;
+
+        let entries: BTreeMap<String, (u64, u64)> = bincode::deserialize(&body[..index_len])?;
+        let data = body[index_len..].to_vec();
+
+        let mut index = BTreeMap::new();
+        for (name, (offset, len)) in entries {
+            let in_bounds = offset
+                .checked_add(len)
+                .is_some_and(|end| end <= data.len() as u64);
+            if !in_bounds {
+                return Err(BentoError::InvalidInput(format!(
+                    "Shader archive entry `{name}` points outside the archive"
+                )));
+            }
+            index.insert(name, (offset as usize, len as usize));
+        }
+
+        Ok(Self { index, data })
+    }
+
+    pub fn save_to_disk(&self, path: &str) -> Result<(), BentoError> {
+        let path = Path::new(path);
+        create_parent_dir(path)?;
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn load_from_disk(path: &str) -> Result<Self, BentoError> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+}
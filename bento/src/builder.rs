@@ -1,16 +1,19 @@
 use std::{
     collections::{HashMap, HashSet, hash_map::Entry},
+    fmt,
     ptr::NonNull,
+    sync::Arc,
 };
 
 use dashi::{
-    BindTable, BindTableInfo, BindTableLayout, BindTableLayoutInfo, BindTableVariableType,
-    BufferInfo, BufferUsage, BufferView, ComputePipeline, ComputePipelineInfo,
-    ComputePipelineLayout, ComputePipelineLayoutInfo, Context, Format, GraphicsPipeline,
-    GraphicsPipelineDetails, GraphicsPipelineInfo, GraphicsPipelineLayout,
-    GraphicsPipelineLayoutInfo, Handle, ImageInfo, ImageView, IndexedBindingInfo, IndexedResource,
-    MemoryVisibility, PipelineShaderInfo, SampleCount, SamplerInfo, ShaderInfo,
-    ShaderPrimitiveType, ShaderResource, ShaderType, VertexDescriptionInfo, VertexEntryInfo,
+    AttachmentDescription, BindTable, BindTableInfo, BindTableLayout, BindTableLayoutInfo,
+    BindTableVariableType, Buffer, BufferInfo, BufferUsage, BufferView, ComputePipeline,
+    ComputePipelineInfo, ComputePipelineLayout, ComputePipelineLayoutInfo, Context, Format,
+    GraphicsPipeline, GraphicsPipelineDetails, GraphicsPipelineInfo, GraphicsPipelineLayout,
+    GraphicsPipelineLayoutInfo, Handle, Image, ImageInfo, ImageView, IndexedBindingInfo,
+    IndexedResource, MemoryVisibility, PipelineShaderInfo, RenderPass, RenderPassInfo, SampleCount,
+    Sampler, SamplerInfo, ShaderInfo, ShaderResource, ShaderType, SubpassDescription, Topology,
+    VertexDescriptionInfo, VertexEntryInfo, Viewport,
 };
 
 pub use crate::PipelineBuildError;
@@ -53,7 +56,9 @@ fn config_dynamic_type(config: &BindTableVariable) -> Option<BindTableVariableTy
             .iter()
             .find_map(|resource| dynamic_type_from_resource(&resource.resource)),
         BindTableVariable::Dynamic { var_type, .. } => Some(*var_type),
-        BindTableVariable::Empty { .. } => None,
+        BindTableVariable::Empty { .. }
+        | BindTableVariable::BufferRange { .. }
+        | BindTableVariable::BufferArray { .. } => None,
     }
 }
 
@@ -95,9 +100,187 @@ fn merge_variable_type(
     }
 }
 
+/// Conditions the pipeline builders and pipelines recover from without failing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineWarning {
+    /// `update_table` was called with a key that has no table binding.
+    UnknownTableKey { key: String },
+    /// `update_table` was called with a slot past the end of the binding; the update was dropped.
+    SlotOutOfRange { key: String, slot: u32, size: u32 },
+    /// A binding configured without resources was filled with default resources.
+    DefaultedResources { name: String, count: u32 },
+    /// A binding configured with a size of zero was given a default size.
+    ClampedCount {
+        name: String,
+        requested: u32,
+        count: u32,
+    },
+}
+
+#[derive(Clone, Default)]
+struct WarningSink(Option<Arc<dyn Fn(PipelineWarning)>>);
+
+impl WarningSink {
+    fn emit(&self, warning: PipelineWarning) {
+        if let Some(sink) = &self.0 {
+            sink(warning);
+        }
+    }
+
+    // Report the recoverable conditions involved in turning `config` into `size` resources.
+    fn emit_config(&self, name: &str, config: &BindTableVariable, size: u32) {
+        let requested = match config {
+            BindTableVariable::Empty { size } | BindTableVariable::Dynamic { size, .. } => *size,
+            BindTableVariable::Binding { .. }
+            | BindTableVariable::BufferRange { .. }
+            | BindTableVariable::BufferArray { .. }
+            | BindTableVariable::WithResources { .. } => return,
+        };
+
+        if requested != size {
+            self.emit(PipelineWarning::ClampedCount {
+                name: name.to_string(),
+                requested,
+                count: size,
+            });
+        }
+
+        self.emit(PipelineWarning::DefaultedResources {
+            name: name.to_string(),
+            count: size,
+        });
+    }
+}
+
+impl fmt::Debug for WarningSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WarningSink").field(&self.0.is_some()).finish()
+    }
+}
+
+// Debug builds track which contexts are alive, so updates through a PSO or CSO whose context
+// has been retired panic with a clear message instead of dereferencing freed memory. Contexts are
+// keyed by address, and every registration starts a new generation, so a context created where a
+// retired one lived doesn't revive that one's pipelines.
+#[cfg(debug_assertions)]
+mod context_epochs {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, OnceLock};
+
+    static EPOCHS: OnceLock<Mutex<HashMap<usize, u64>>> = OnceLock::new();
+    static NEXT_EPOCH: AtomicU64 = AtomicU64::new(1);
+
+    fn epochs() -> std::sync::MutexGuard<'static, HashMap<usize, u64>> {
+        EPOCHS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn register(ctx: *const dashi::Context) -> u64 {
+        let epoch = NEXT_EPOCH.fetch_add(1, Ordering::Relaxed);
+        epochs().insert(ctx as usize, epoch);
+        epoch
+    }
+
+    pub(super) fn current(ctx: *const dashi::Context) -> u64 {
+        *epochs()
+            .entry(ctx as usize)
+            .or_insert_with(|| NEXT_EPOCH.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub(super) fn retire(ctx: *const dashi::Context) {
+        epochs().remove(&(ctx as usize));
+    }
+
+    pub(super) fn is_live(ctx: *const dashi::Context, epoch: u64) -> bool {
+        epochs().get(&(ctx as usize)) == Some(&epoch)
+    }
+}
+
+#[cfg(debug_assertions)]
+fn context_epoch(ctx: &Context) -> u64 {
+    context_epochs::current(ctx)
+}
+
+#[cfg(not(debug_assertions))]
+fn context_epoch(_ctx: &Context) -> u64 {
+    0
+}
+
+/// Starts a new generation for `ctx`. Call it right after creating a context: pipelines built on
+/// an earlier context that lived at the same address then count as retired even if nobody called
+/// `retire_context` for it. Release builds skip the bookkeeping.
+pub fn register_context(ctx: &Context) {
+    #[cfg(debug_assertions)]
+    context_epochs::register(ctx);
+    #[cfg(not(debug_assertions))]
+    let _ = ctx;
+}
+
+/// Marks `ctx` as torn down. Call it before destroying a context that built pipelines; in debug
+/// builds any later table update through those pipelines panics instead of touching freed
+/// memory. Release builds skip the bookkeeping.
+pub fn retire_context(ctx: &Context) {
+    #[cfg(debug_assertions)]
+    context_epochs::retire(ctx);
+    #[cfg(not(debug_assertions))]
+    let _ = ctx;
+}
+
+fn assert_context_live(ctx: NonNull<Context>, epoch: u64) {
+    #[cfg(debug_assertions)]
+    assert!(
+        context_epochs::is_live(ctx.as_ptr(), epoch),
+        "pipeline table update after its dashi Context was retired"
+    );
+    #[cfg(not(debug_assertions))]
+    let _ = (ctx, epoch);
+}
+
+fn update_table_target(
+    ctx: &mut Context,
+    table_bindings: &HashMap<String, TableBinding>,
+    warnings: &WarningSink,
+    key: &str,
+    resources: &[IndexedResource],
+) {
+    let Some(binding_info) = table_bindings.get(key).copied() else {
+        warnings.emit(PipelineWarning::UnknownTableKey {
+            key: key.to_string(),
+        });
+        return;
+    };
+
+    if let Some(resource) = resources
+        .iter()
+        .find(|resource| resource.slot >= binding_info.size)
+    {
+        warnings.emit(PipelineWarning::SlotOutOfRange {
+            key: key.to_string(),
+            slot: resource.slot,
+            size: binding_info.size,
+        });
+        return;
+    }
+
+    let bindings = [IndexedBindingInfo {
+        resources,
+        binding: binding_info.binding,
+    }];
+
+    let _ = ctx.update_bind_table(&dashi::BindTableUpdateInfo {
+        table: binding_info.table,
+        bindings: &bindings,
+    });
+}
+
 struct DefaultResources {
     uniform: Option<ShaderResource>,
+    dynamic_uniform: Option<ShaderResource>,
     storage: Option<ShaderResource>,
+    dynamic_storage: Option<ShaderResource>,
     sampled_image: Option<ShaderResource>,
     image: Option<ShaderResource>,
     sampler: Option<ShaderResource>,
@@ -108,7 +291,9 @@ impl Default for DefaultResources {
     fn default() -> Self {
         Self {
             uniform: None,
+            dynamic_uniform: None,
             storage: None,
+            dynamic_storage: None,
             sampled_image: None,
             image: None,
             sampler: None,
@@ -118,46 +303,54 @@ impl Default for DefaultResources {
 }
 
 impl DefaultResources {
-    fn make_uniform(
+    // Buffers are only handed out to the exact binding type they were created for, so the
+    // usage flags always match the descriptor they end up in.
+    fn make_buffer(
         ctx: &mut dashi::Context,
         name: &str,
+        var_type: BindTableVariableType,
     ) -> Result<ShaderResource, PipelineBuildError> {
-        let buffer = ctx
-            .make_buffer(&BufferInfo {
-                debug_name: "bento_default_uniform",
-                byte_size: 256,
-                visibility: MemoryVisibility::CpuAndGpu,
-                usage: BufferUsage::UNIFORM,
-                initial_data: None,
-            })
-            .map_err(|source| PipelineBuildError::DefaultResourceCreateFailed {
-                name: name.to_string(),
-                resource_type: "uniform buffer",
-                source,
-            })?;
-
-        Ok(ShaderResource::Buffer(BufferView::new(buffer)))
-    }
+        let (debug_name, usage, resource_type) = match var_type {
+            BindTableVariableType::Uniform => {
+                ("bento_default_uniform", BufferUsage::UNIFORM, "uniform buffer")
+            }
+            BindTableVariableType::DynamicUniform => (
+                "bento_default_dynamic_uniform",
+                BufferUsage::UNIFORM,
+                "dynamic uniform buffer",
+            ),
+            BindTableVariableType::Storage => {
+                ("bento_default_storage", BufferUsage::STORAGE, "storage buffer")
+            }
+            BindTableVariableType::DynamicStorage => (
+                "bento_default_dynamic_storage",
+                BufferUsage::STORAGE,
+                "dynamic storage buffer",
+            ),
+            _ => unreachable!("default buffers are only made for buffer bindings"),
+        };
 
-    fn make_storage(
-        ctx: &mut dashi::Context,
-        name: &str,
-    ) -> Result<ShaderResource, PipelineBuildError> {
         let buffer = ctx
             .make_buffer(&BufferInfo {
-                debug_name: "bento_default_storage",
+                debug_name,
                 byte_size: 256,
                 visibility: MemoryVisibility::CpuAndGpu,
-                usage: BufferUsage::STORAGE,
+                usage,
                 initial_data: None,
             })
             .map_err(|source| PipelineBuildError::DefaultResourceCreateFailed {
                 name: name.to_string(),
-                resource_type: "storage buffer",
+                resource_type,
                 source,
             })?;
 
-        Ok(ShaderResource::StorageBuffer(BufferView::new(buffer)))
+        let view = BufferView::new(buffer);
+        Ok(match var_type {
+            BindTableVariableType::Uniform | BindTableVariableType::DynamicUniform => {
+                ShaderResource::Buffer(view)
+            }
+            _ => ShaderResource::StorageBuffer(view),
+        })
     }
 
     fn make_sampled_image(
@@ -248,20 +441,34 @@ impl DefaultResources {
         name: &str,
     ) -> Result<ShaderResource, PipelineBuildError> {
         match var_type {
-            BindTableVariableType::Uniform | BindTableVariableType::DynamicUniform => {
+            BindTableVariableType::Uniform => {
                 if self.uniform.is_none() {
-                    self.uniform = Some(Self::make_uniform(ctx, name)?);
+                    self.uniform = Some(Self::make_buffer(ctx, name, var_type)?);
                 }
 
                 Ok(self.uniform.clone().expect("uniform default"))
             }
-            BindTableVariableType::Storage | BindTableVariableType::DynamicStorage => {
+            BindTableVariableType::DynamicUniform => {
+                if self.dynamic_uniform.is_none() {
+                    self.dynamic_uniform = Some(Self::make_buffer(ctx, name, var_type)?);
+                }
+
+                Ok(self.dynamic_uniform.clone().expect("dynamic uniform default"))
+            }
+            BindTableVariableType::Storage => {
                 if self.storage.is_none() {
-                    self.storage = Some(Self::make_storage(ctx, name)?);
+                    self.storage = Some(Self::make_buffer(ctx, name, var_type)?);
                 }
 
                 Ok(self.storage.clone().expect("storage default"))
             }
+            BindTableVariableType::DynamicStorage => {
+                if self.dynamic_storage.is_none() {
+                    self.dynamic_storage = Some(Self::make_buffer(ctx, name, var_type)?);
+                }
+
+                Ok(self.dynamic_storage.clone().expect("dynamic storage default"))
+            }
             BindTableVariableType::SampledImage => {
                 if self.sampled_image.is_none() {
                     self.sampled_image = Some(Self::make_sampled_image(ctx, name)?);
@@ -294,6 +501,76 @@ impl DefaultResources {
     }
 }
 
+/// GPU objects a builder created for a pipeline, destroyed together with it.
+#[derive(Debug, Clone, Default)]
+struct OwnedObjects {
+    bind_tables: Vec<Handle<BindTable>>,
+    bind_table_layouts: Vec<Handle<BindTableLayout>>,
+    buffers: Vec<Handle<Buffer>>,
+    images: Vec<Handle<Image>>,
+    samplers: Vec<Handle<Sampler>>,
+}
+
+impl OwnedObjects {
+    fn take_defaults(&mut self, defaults: DefaultResources) {
+        let DefaultResources {
+            uniform,
+            dynamic_uniform,
+            storage,
+            dynamic_storage,
+            sampled_image,
+            image,
+            sampler,
+            storage_image,
+        } = defaults;
+
+        for resource in [
+            uniform,
+            dynamic_uniform,
+            storage,
+            dynamic_storage,
+            sampled_image,
+            image,
+            sampler,
+            storage_image,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            match resource {
+                ShaderResource::Buffer(view)
+                | ShaderResource::ConstBuffer(view)
+                | ShaderResource::StorageBuffer(view) => self.buffers.push(view.handle),
+                ShaderResource::SampledImage(view, sampler) => {
+                    self.images.push(view.img);
+                    self.samplers.push(sampler);
+                }
+                ShaderResource::Image(view) => self.images.push(view.img),
+                ShaderResource::Sampler(sampler) => self.samplers.push(sampler),
+                ShaderResource::Dynamic(_) | ShaderResource::DynamicStorage(_) => {}
+            }
+        }
+    }
+
+    fn destroy(self, ctx: &mut Context) {
+        for table in self.bind_tables {
+            ctx.destroy_bind_table(table);
+        }
+        for layout in self.bind_table_layouts {
+            ctx.destroy_bind_table_layout(layout);
+        }
+        for buffer in self.buffers {
+            ctx.destroy_buffer(buffer);
+        }
+        for image in self.images {
+            ctx.destroy_image(image);
+        }
+        for sampler in self.samplers {
+            ctx.destroy_sampler(sampler);
+        }
+    }
+}
+
 fn default_resources_for_variable(
     defaults: &mut DefaultResources,
     ctx: &mut dashi::Context,
@@ -372,11 +649,53 @@ fn resources_from_config(
             Ok((resources.clone(), expected_count))
         }
         BindTableVariable::Binding { resource } => Ok((
-            vec![IndexedResource {
-                resource: resource.clone(),
-                slot: 0,
-            }],
-            1,
+            (0..expected_count)
+                .map(|slot| IndexedResource {
+                    resource: resource.clone(),
+                    slot,
+                })
+                .collect(),
+            expected_count,
+        )),
+        BindTableVariable::BufferRange { view, offset, size } => {
+            // The range is checked against the view's byte size; a zero-sized whole-buffer view
+            // has no length to check against, so it's rejected rather than trusted.
+            let fits = offset
+                .checked_add(*size)
+                .is_some_and(|end| end <= view.size);
+            if *size == 0 || !fits {
+                return Err(PipelineBuildError::BufferRangeOutOfBounds {
+                    name: name.to_string(),
+                    offset: *offset,
+                    size: *size,
+                    available: view.size,
+                });
+            }
+
+            let ranged = BufferView {
+                handle: view.handle,
+                offset: view.offset + offset,
+                size: *size,
+            };
+            let resource = match var.var_type {
+                BindTableVariableType::Storage | BindTableVariableType::DynamicStorage => {
+                    ShaderResource::StorageBuffer(ranged)
+                }
+                _ => ShaderResource::Buffer(ranged),
+            };
+
+            Ok((vec![IndexedResource { resource, slot: 0 }], 1))
+        }
+        BindTableVariable::BufferArray { views } => Ok((
+            views
+                .iter()
+                .zip(0..)
+                .map(|(view, slot)| IndexedResource {
+                    resource: ShaderResource::StorageBuffer(*view),
+                    slot,
+                })
+                .collect(),
+            expected_count,
         )),
     }
 }
@@ -395,10 +714,25 @@ fn resolve_binding_count(
         }],
     })?;
     let count = match config {
-        BindTableVariable::Binding { resource: _ } => 1,
+        // A single resource bound to a fixed-size array fills every element, so the layout
+        // keeps the array length the shader declares.
+        BindTableVariable::Binding { resource: _ } => var.count.max(1),
+        BindTableVariable::BufferRange { .. } => 1,
         BindTableVariable::Empty { size } => *size,
         BindTableVariable::Dynamic { size, .. } => *size,
         BindTableVariable::WithResources { resources } => resources.len() as u32,
+        BindTableVariable::BufferArray { views } => {
+            let provided = views.len() as u32;
+            // Runtime-sized arrays reflect a count of zero and take any number of views.
+            if var.count != 0 && var.count != provided {
+                return Err(PipelineBuildError::InvalidResourceCount {
+                    name: name.to_string(),
+                    expected: var.count,
+                    provided,
+                });
+            }
+            provided
+        }
     };
 
     Ok(if count == 0 { 256 } else { count })
@@ -410,10 +744,57 @@ pub struct PSO {
     pub handle: Handle<GraphicsPipeline>,
     pub bind_table: [Option<Handle<BindTable>>; 4],
     pub ctx: NonNull<Context>,
+    ctx_epoch: u64,
     table_bindings: HashMap<String, TableBinding>,
+    attachment_formats: Vec<Format>,
+    samples: SampleCount,
+    depth: Option<(Format, SampleCount)>,
+    warnings: WarningSink,
+    owned: OwnedObjects,
 }
 
 impl PSO {
+    /// Color attachment formats the pipeline was built against, in output location order.
+    pub fn attachment_formats(&self) -> &[Format] {
+        &self.attachment_formats
+    }
+
+    /// Creates a single-subpass render pass whose color and depth attachments match the formats
+    /// and sample counts this pipeline was built against.
+    ///
+    /// The render pass is owned by the caller and is not destroyed with the PSO.
+    pub fn make_compatible_render_pass(
+        &self,
+        ctx: &mut Context,
+        viewport: Viewport,
+    ) -> Result<Handle<RenderPass>, PipelineBuildError> {
+        let colors: Vec<AttachmentDescription> = self
+            .attachment_formats
+            .iter()
+            .map(|format| AttachmentDescription {
+                format: *format,
+                samples: self.samples,
+                ..Default::default()
+            })
+            .collect();
+        let depth = self.depth.map(|(format, samples)| AttachmentDescription {
+            format,
+            samples,
+            ..Default::default()
+        });
+
+        ctx.make_render_pass(&RenderPassInfo {
+            viewport,
+            subpasses: &[SubpassDescription {
+                color_attachments: &colors,
+                depth_stencil_attachment: depth.as_ref(),
+                subpass_dependencies: &[],
+            }],
+            debug_name: "bento_compatible_render_pass",
+        })
+        .map_err(|source| PipelineBuildError::RenderPassCreateFailed { source })
+    }
+
     pub fn table_binding(&self, key: &str) -> Option<BindTableUpdateTarget> {
         self.table_bindings.get(key).copied().map(|binding| {
             BindTableUpdateTarget {
@@ -429,35 +810,28 @@ impl PSO {
     }
 
     pub fn update_table_slice(&mut self, key: &str, resources: &[IndexedResource]) {
-        let Some(binding_info) = self.table_bindings.get(key).copied() else {
-            return;
-        };
-
-        if resources
-            .iter()
-            .any(|resource| resource.slot >= binding_info.size)
-        {
-            return;
-        }
-
-        let bindings = [IndexedBindingInfo {
-            resources,
-            binding: binding_info.binding,
-        }];
-
         // Safety: The PSO stores a NonNull pointer to the context it was
         // created with. Callers are responsible for ensuring the context
         // remains valid for the lifetime of the PSO.
+        assert_context_live(self.ctx, self.ctx_epoch);
         let ctx = unsafe { self.ctx.as_mut() };
-        let _ = ctx.update_bind_table(&dashi::BindTableUpdateInfo {
-            table: binding_info.table,
-            bindings: &bindings,
-        });
+        update_table_target(ctx, &self.table_bindings, &self.warnings, key, resources);
     }
 
     pub fn tables(&self) -> [Option<Handle<BindTable>>; 4] {
         self.bind_table
     }
+
+    /// Destroys the pipeline, its layout, and every bind table, bind table layout and default
+    /// resource the builder created for it.
+    ///
+    /// Tables assigned to `bind_table` after building are owned by the caller and are not
+    /// destroyed.
+    pub fn destroy(self, ctx: &mut Context) {
+        ctx.destroy_graphics_pipeline(self.handle);
+        ctx.destroy_graphics_pipeline_layout(self.layout);
+        self.owned.destroy(ctx);
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -478,7 +852,9 @@ pub struct BindTableUpdateTarget {
 pub enum BindTableVariable {
     Empty { size: u32 },
     Binding { resource: ShaderResource },
+    BufferRange { view: BufferView, offset: u64, size: u64 },
     WithResources { resources: Vec<IndexedResource> },
+    BufferArray { views: Vec<BufferView> },
     Dynamic { size: u32, var_type: BindTableVariableType },
 }
 
@@ -547,14 +923,37 @@ pub struct AttachmentDesc {
     pub format: Format,
 }
 
+/// Formats inferred from `fragment`'s outputs in location order, with explicit overrides applied
+/// by output location.
+fn resolve_attachment_formats(
+    fragment: &CompilationResult,
+    overrides: &HashMap<u32, Format>,
+    srgb_outputs: bool,
+) -> Vec<Format> {
+    fragment
+        .inferred_attachments()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (location, inferred))| {
+            match overrides.get(&location.unwrap_or(idx as u32)).copied() {
+                Some(format) => format,
+                None if srgb_outputs && inferred == Format::RGBA8 => Format::RGBA8_SRGB,
+                None => inferred,
+            }
+        })
+        .collect()
+}
+
 pub struct PSOBuilder {
     vertex: Option<CompilationResult>,
     fragment: Option<CompilationResult>,
     depth: Option<AttachmentDesc>,
     table_variables: HashMap<String, BindTableVariable>,
     attachment_formats: HashMap<u32, Format>,
+    srgb_outputs: bool,
     details: GraphicsPipelineDetails,
     debug_names: BuilderDebugNames,
+    warnings: WarningSink,
 }
 
 impl PSOBuilder {
@@ -564,9 +963,20 @@ impl PSOBuilder {
             fragment: None,
             table_variables: HashMap::new(),
             attachment_formats: HashMap::new(),
+            srgb_outputs: false,
             details: GraphicsPipelineDetails::default(),
             depth: None,
             debug_names: BuilderDebugNames::graphics_default(),
+            warnings: WarningSink::default(),
+        }
+    }
+
+    /// Registers a callback for conditions the builder and the built pipeline recover from
+    /// silently, such as table updates that are dropped. Defaults to ignoring them.
+    pub fn on_warning(self, sink: impl Fn(PipelineWarning) + 'static) -> Self {
+        Self {
+            warnings: WarningSink(Some(Arc::new(sink))),
+            ..self
         }
     }
 
@@ -586,6 +996,7 @@ impl PSOBuilder {
                                 optimization: OptimizationLevel::None,
                                 debug_symbols: true,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -602,6 +1013,7 @@ impl PSOBuilder {
                                 optimization: OptimizationLevel::Performance,
                                 debug_symbols: false,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -648,6 +1060,7 @@ impl PSOBuilder {
                                 optimization: OptimizationLevel::None,
                                 debug_symbols: true,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -664,6 +1077,7 @@ impl PSOBuilder {
                                 optimization: OptimizationLevel::Performance,
                                 debug_symbols: false,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -734,6 +1148,39 @@ impl PSOBuilder {
         }
     }
 
+    /// Binds `size` bytes of `view` starting `offset` bytes into it, for sub-allocated buffers.
+    /// The range is bound as a uniform or storage buffer to match the shader and must fit in
+    /// the view, whose `size` must be the buffer's byte size; this is checked when building.
+    pub fn add_buffer_range(self, key: &str, view: BufferView, offset: u64, size: u64) -> Self {
+        let mut table_variables = self.table_variables;
+        table_variables.insert(
+            key.to_string(),
+            BindTableVariable::BufferRange { view, offset, size },
+        );
+
+        Self {
+            table_variables,
+            ..self
+        }
+    }
+
+    /// Binds `views` as storage buffers in slots `0..views.len()`. The length must match the
+    /// array the shader declares; this is checked when building.
+    pub fn add_buffer_array(self, key: &str, views: &[BufferView]) -> Self {
+        let mut table_variables = self.table_variables;
+        table_variables.insert(
+            key.to_string(),
+            BindTableVariable::BufferArray {
+                views: views.to_vec(),
+            },
+        );
+
+        Self {
+            table_variables,
+            ..self
+        }
+    }
+
     pub fn add_dynamic_variable(self, key: &str, size: u32, var_type: DynamicVariableType) -> Self {
         let mut table_variables = self.table_variables;
         table_variables.insert(
@@ -761,6 +1208,14 @@ impl PSOBuilder {
         Self { details, ..self }
     }
 
+    /// Sets the primitive topology in the pipeline details. Defaults to a triangle list.
+    pub fn topology(self, topology: Topology) -> Self {
+        let mut details = self.details;
+        details.topology = topology;
+
+        Self { details, ..self }
+    }
+
     pub fn set_attachment_format(self, slot: u32, format: Format) -> Self {
         let mut attachment_formats = self.attachment_formats;
         attachment_formats.insert(slot, format);
@@ -771,20 +1226,50 @@ impl PSOBuilder {
         }
     }
 
+    /// Selects the sRGB variant when inferring color attachment formats from fragment outputs,
+    /// so writes are gamma-encoded. Formats set with `set_attachment_format` are left untouched.
+    pub fn srgb_outputs(self, enabled: bool) -> Self {
+        Self {
+            srgb_outputs: enabled,
+            ..self
+        }
+    }
+
+    /// Color attachment formats `build` would give the pipeline, in output location order,
+    /// without creating any GPU objects. Empty until a fragment shader is set.
+    pub fn preview_attachments(&self) -> Vec<Format> {
+        self.fragment
+            .as_ref()
+            .map(|fragment| {
+                resolve_attachment_formats(fragment, &self.attachment_formats, self.srgb_outputs)
+            })
+            .unwrap_or_default()
+    }
+
     pub fn build(self, ctx: &mut dashi::Context) -> Result<PSO, BentoError> {
         let PSOBuilder {
             vertex,
             fragment,
             table_variables,
             attachment_formats,
+            srgb_outputs,
             details,
             depth,
             debug_names,
+            warnings,
         } = self;
 
         let vertex = vertex.ok_or(PipelineBuildError::MissingShader { stage: "vertex" })?;
         let fragment = fragment.ok_or(PipelineBuildError::MissingShader { stage: "fragment" })?;
 
+        // Patch lists only make sense when tessellation stages consume them.
+        if matches!(details.topology, Topology::PatchList) {
+            return Err(PipelineBuildError::MissingTessellationStage {
+                topology: details.topology,
+            }
+            .into());
+        }
+
         let mut missing_bindings = Vec::new();
         let mut seen = HashSet::new();
         for var in vertex.variables.iter().chain(fragment.variables.iter()) {
@@ -804,11 +1289,38 @@ impl PSOBuilder {
             .into());
         }
 
+        let vertex_entries: Vec<VertexEntryInfo> = vertex
+            .metadata
+            .vertex
+            .as_ref()
+            .map(|layout| {
+                layout
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        let format = entry.format.ok_or(
+                            PipelineBuildError::UnsupportedVertexAttribute {
+                                location: entry.location,
+                                primitive: entry.primitive,
+                            },
+                        )?;
+                        Ok(VertexEntryInfo {
+                            format,
+                            location: entry.location,
+                            offset: entry.offset,
+                        })
+                    })
+                    .collect::<Result<_, PipelineBuildError>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
         // Build bind table layouts and tables.
         let mut bt_layouts: [Option<Handle<BindTableLayout>>; 4] = [None; 4];
         let mut bind_tables: [Option<Handle<BindTable>>; 4] = [None; 4];
         let mut table_bindings = HashMap::new();
         let mut defaults = DefaultResources::default();
+        let mut owned = OwnedObjects::default();
 
         for set in 0..4u32 {
             let mut merged_vars: HashMap<u32, (dashi::BindTableVariable, dashi::ShaderType)> =
@@ -912,6 +1424,7 @@ impl PSOBuilder {
             if (set as usize) < bt_layouts.len() {
                 bt_layouts[set as usize] = Some(layout);
             }
+            owned.bind_table_layouts.push(layout);
 
             // Create bind table with any provided resources.
             let mut pending_bindings = Vec::new();
@@ -935,6 +1448,7 @@ impl PSOBuilder {
                             resource,
                             expected_count,
                         )?;
+                        warnings.emit_config(&var.name, resource, size);
                         resources.push(initial_resources);
                         let resource_index = resources.len() - 1;
 
@@ -962,6 +1476,7 @@ impl PSOBuilder {
                         set,
                     })
                     .map_err(|source| PipelineBuildError::BindTableCreateFailed { set, source })?;
+                owned.bind_tables.push(table);
                 for (name, binding, size) in pending_names {
                     table_bindings.insert(
                         name,
@@ -991,23 +1506,6 @@ impl PSOBuilder {
             },
         ];
 
-        let vertex_entries: Vec<VertexEntryInfo> = vertex
-            .metadata
-            .vertex
-            .as_ref()
-            .map(|layout| {
-                layout
-                    .entries
-                    .iter()
-                    .map(|entry| VertexEntryInfo {
-                        format: entry.format,
-                        location: entry.location,
-                        offset: entry.offset,
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
         let vertex_info = VertexDescriptionInfo {
             entries: vertex_entries.as_slice(),
             stride: vertex
@@ -1038,28 +1536,7 @@ impl PSOBuilder {
                 source,
             })?;
 
-        let mut idx = 0;
-        let attachments: Vec<Format> = fragment
-            .metadata
-            .outputs
-            .iter()
-            .map(|iv| {
-                if let Some(format) = attachment_formats.get(&idx).copied() {
-                    idx += 1;
-                    return format;
-                }
-
-                idx += 1;
-                let fmt = iv.format.unwrap_or(ShaderPrimitiveType::Vec4);
-                match fmt {
-                    ShaderPrimitiveType::Vec2 => Format::RGB8,
-                    ShaderPrimitiveType::Vec3 => Format::RGB8,
-                    ShaderPrimitiveType::Vec4 => Format::RGBA8,
-                    ShaderPrimitiveType::IVec4 => Format::RGBA8,
-                    ShaderPrimitiveType::UVec4 => Format::RGBA8,
-                }
-            })
-            .collect();
+        let attachments = resolve_attachment_formats(&fragment, &attachment_formats, srgb_outputs);
 
         let samples = attachments.iter().map(|_| sample_count).collect();
 
@@ -1076,7 +1553,7 @@ impl PSOBuilder {
         let pipeline = ctx
             .make_graphics_pipeline(&GraphicsPipelineInfo {
                 layout,
-                attachment_formats: attachments,
+                attachment_formats: attachments.clone(),
                 depth_format,
                 subpass_samples: dashi::SubpassSampleInfo {
                     color_samples: samples,
@@ -1090,12 +1567,20 @@ impl PSOBuilder {
                 source,
             })?;
 
+        owned.take_defaults(defaults);
+
         Ok(PSO {
             layout,
             handle: pipeline,
             bind_table: bind_tables,
             table_bindings,
+            attachment_formats: attachments,
+            samples: sample_count,
+            depth: depth_format.zip(depth_sample),
+            ctx_epoch: context_epoch(ctx),
             ctx: NonNull::from(ctx),
+            warnings,
+            owned,
         })
     }
 }
@@ -1109,7 +1594,13 @@ pub struct CSO {
     pub handle: Handle<ComputePipeline>,
     pub bind_table: [Option<Handle<BindTable>>; 4],
     pub ctx: NonNull<Context>,
+    ctx_epoch: u64,
+    shader: CompilationResult,
+    bt_layouts: [Option<Handle<BindTableLayout>>; 4],
+    pipeline_name: String,
     table_bindings: HashMap<String, TableBinding>,
+    warnings: WarningSink,
+    owned: OwnedObjects,
 }
 
 impl CSO {
@@ -1128,37 +1619,86 @@ impl CSO {
     }
 
     pub fn update_table_slice(&mut self, key: &str, resources: &[IndexedResource]) {
-        let Some(binding_info) = self.table_bindings.get(key).copied() else {
-            return;
-        };
+        assert_context_live(self.ctx, self.ctx_epoch);
+        let ctx = unsafe { self.ctx.as_mut() };
+        update_table_target(ctx, &self.table_bindings, &self.warnings, key, resources);
+    }
 
-        if resources
-            .iter()
-            .any(|resource| resource.slot >= binding_info.size)
-        {
-            return;
+    pub fn tables(&self) -> [Option<Handle<BindTable>>; 4] {
+        self.bind_table
+    }
+
+    /// Swaps in `new`'s SPIR-V after a hot reload, keeping the bind tables, their layouts and
+    /// table bindings.
+    ///
+    /// `new` must have the same interface as the shader the pipeline was built from (see
+    /// `CompilationResult::interface_eq`). The pipeline layout carries the shader module, so it
+    /// is recreated along with the pipeline; both old objects are destroyed immediately, so the
+    /// caller must make sure no submitted work still uses them.
+    pub fn reload_spirv(
+        &mut self,
+        ctx: &mut Context,
+        new: &CompilationResult,
+    ) -> Result<(), PipelineBuildError> {
+        if !self.shader.interface_eq(new) {
+            return Err(PipelineBuildError::InterfaceMismatch {
+                pipeline: "compute",
+            });
         }
 
-        let bindings = [IndexedBindingInfo {
-            resources,
-            binding: binding_info.binding,
-        }];
+        let shader_info = PipelineShaderInfo {
+            stage: new.stage,
+            spirv: &new.spirv,
+            specialization: &[],
+        };
+        let layout = ctx
+            .make_compute_pipeline_layout(&ComputePipelineLayoutInfo {
+                bt_layouts: self.bt_layouts,
+                shader: &shader_info,
+            })
+            .map_err(|source| PipelineBuildError::PipelineLayoutCreateFailed {
+                pipeline: "compute",
+                source,
+            })?;
 
-        let ctx = unsafe { self.ctx.as_mut() };
-        let _ = ctx.update_bind_table(&dashi::BindTableUpdateInfo {
-            table: binding_info.table,
-            bindings: &bindings,
-        });
+        let pipeline = match ctx.make_compute_pipeline(&ComputePipelineInfo {
+            debug_name: self.pipeline_name.as_str(),
+            layout,
+        }) {
+            Ok(pipeline) => pipeline,
+            Err(source) => {
+                ctx.destroy_compute_pipeline_layout(layout);
+                return Err(PipelineBuildError::PipelineCreateFailed {
+                    pipeline: "compute",
+                    source,
+                });
+            }
+        };
+
+        ctx.destroy_compute_pipeline(self.handle);
+        ctx.destroy_compute_pipeline_layout(self.layout);
+        self.handle = pipeline;
+        self.layout = layout;
+        self.shader = new.clone();
+        Ok(())
     }
 
-    pub fn tables(&self) -> [Option<Handle<BindTable>>; 4] {
-        self.bind_table
+    /// Destroys the pipeline, its layout, and every bind table, bind table layout and default
+    /// resource the builder created for it.
+    ///
+    /// Tables assigned to `bind_table` after building are owned by the caller and are not
+    /// destroyed.
+    pub fn destroy(self, ctx: &mut Context) {
+        ctx.destroy_compute_pipeline(self.handle);
+        ctx.destroy_compute_pipeline_layout(self.layout);
+        self.owned.destroy(ctx);
     }
 }
 pub struct CSOBuilder {
     shader: Option<CompilationResult>,
     table_variables: HashMap<String, BindTableVariable>,
     debug_names: BuilderDebugNames,
+    warnings: WarningSink,
 }
 
 impl CSOBuilder {
@@ -1167,6 +1707,16 @@ impl CSOBuilder {
             shader: None,
             table_variables: HashMap::new(),
             debug_names: BuilderDebugNames::compute_default(),
+            warnings: WarningSink::default(),
+        }
+    }
+
+    /// Registers a callback for conditions the builder and the built pipeline recover from
+    /// silently, such as table updates that are dropped. Defaults to ignoring them.
+    pub fn on_warning(self, sink: impl Fn(PipelineWarning) + 'static) -> Self {
+        Self {
+            warnings: WarningSink(Some(Arc::new(sink))),
+            ..self
         }
     }
 
@@ -1186,6 +1736,7 @@ impl CSOBuilder {
                                 optimization: OptimizationLevel::None,
                                 debug_symbols: true,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -1202,6 +1753,7 @@ impl CSOBuilder {
                                 optimization: OptimizationLevel::Performance,
                                 debug_symbols: false,
                                 defines: HashMap::new(),
+                                ..Default::default()
                             },
                         )
                         .unwrap(),
@@ -1268,6 +1820,39 @@ impl CSOBuilder {
         }
     }
 
+    /// Binds `size` bytes of `view` starting `offset` bytes into it, for sub-allocated buffers.
+    /// The range is bound as a uniform or storage buffer to match the shader and must fit in
+    /// the view, whose `size` must be the buffer's byte size; this is checked when building.
+    pub fn add_buffer_range(self, key: &str, view: BufferView, offset: u64, size: u64) -> Self {
+        let mut table_variables = self.table_variables;
+        table_variables.insert(
+            key.to_string(),
+            BindTableVariable::BufferRange { view, offset, size },
+        );
+
+        Self {
+            table_variables,
+            ..self
+        }
+    }
+
+    /// Binds `views` as storage buffers in slots `0..views.len()`. The length must match the
+    /// array the shader declares; this is checked when building.
+    pub fn add_buffer_array(self, key: &str, views: &[BufferView]) -> Self {
+        let mut table_variables = self.table_variables;
+        table_variables.insert(
+            key.to_string(),
+            BindTableVariable::BufferArray {
+                views: views.to_vec(),
+            },
+        );
+
+        Self {
+            table_variables,
+            ..self
+        }
+    }
+
     pub fn add_dynamic_variable(self, key: &str, size: u32, var_type: DynamicVariableType) -> Self {
         let mut table_variables = self.table_variables;
         table_variables.insert(
@@ -1297,6 +1882,7 @@ impl CSOBuilder {
             shader,
             table_variables,
             debug_names,
+            warnings,
         } = self;
 
         let shader = shader.ok_or(PipelineBuildError::MissingShader { stage: "compute" })?;
@@ -1324,6 +1910,7 @@ impl CSOBuilder {
         let mut bind_tables: [Option<Handle<BindTable>>; 4] = [None; 4];
         let mut table_bindings = HashMap::new();
         let mut defaults = DefaultResources::default();
+        let mut owned = OwnedObjects::default();
 
         for set in 0..4u32 {
             let vars: Vec<dashi::BindTableVariable> = shader
@@ -1367,6 +1954,7 @@ impl CSOBuilder {
             if (set as usize) < bt_layouts.len() {
                 bt_layouts[set as usize] = Some(layout);
             }
+            owned.bind_table_layouts.push(layout);
 
             let mut pending_bindings = Vec::new();
             let mut pending_names = Vec::new();
@@ -1387,6 +1975,7 @@ impl CSOBuilder {
                         res,
                         expected_count,
                     )?;
+                    warnings.emit_config(&var.name, res, size);
                     resources.push(initial_resources);
                     let resource_index = resources.len() - 1;
 
@@ -1413,6 +2002,7 @@ impl CSOBuilder {
                         set,
                     })
                     .map_err(|source| PipelineBuildError::BindTableCreateFailed { set, source })?;
+                owned.bind_tables.push(table);
                 for (name, binding, size) in pending_names {
                     table_bindings.insert(
                         name,
@@ -1455,12 +2045,20 @@ impl CSOBuilder {
                 source,
             })?;
 
+        owned.take_defaults(defaults);
+
         Ok(CSO {
             layout,
             handle: pipeline,
             bind_table: bind_tables,
             table_bindings,
+            shader,
+            bt_layouts,
+            pipeline_name: debug_names.pipeline,
+            ctx_epoch: context_epoch(ctx),
             ctx: NonNull::from(ctx),
+            warnings,
+            owned,
         })
     }
 }
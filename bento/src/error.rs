@@ -1,3 +1,4 @@
+use crate::ShaderPrimitive;
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -7,6 +8,56 @@ pub struct MissingBinding {
     pub binding: u32,
 }
 
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// One problem shaderc reported, located in the source when shaderc said where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{line}:")?;
+        }
+        if let Some(column) = self.column {
+            write!(f, "{column}:")?;
+        }
+        if self.file.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+
+        let severity = match self.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Note => "note",
+        };
+        write!(f, "{severity}: {}", self.message)
+    }
+}
+
+fn join_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(Diagnostic::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[derive(Debug, Error)]
 pub enum PipelineBuildError {
     #[error("Missing {stage} shader stage")]
@@ -35,6 +86,16 @@ pub enum PipelineBuildError {
     #[error("Invalid resource slots for {name}: expected slots 0..{expected}")]
     InvalidResourceSlots { name: String, expected: u32 },
 
+    #[error(
+        "Buffer range for {name} at offset {offset} with size {size} does not fit the {available}-byte view"
+    )]
+    BufferRangeOutOfBounds {
+        name: String,
+        offset: u64,
+        size: u64,
+        available: u64,
+    },
+
     #[error("Failed to create default {resource_type} resource for {name}: {source}")]
     DefaultResourceCreateFailed {
         name: String,
@@ -64,12 +125,33 @@ pub enum PipelineBuildError {
         source: dashi::GPUError,
     },
 
+    #[error(
+        "Vertex attribute at location {location} is a {primitive:?}, which dashi has no vertex \
+         format for"
+    )]
+    UnsupportedVertexAttribute {
+        location: usize,
+        primitive: ShaderPrimitive,
+    },
+
+    #[error("{topology:?} topology requires a tessellation stage")]
+    MissingTessellationStage { topology: dashi::Topology },
+
     #[error("Failed to create {pipeline} pipeline: {source}")]
     PipelineCreateFailed {
         pipeline: &'static str,
         #[source]
         source: dashi::GPUError,
     },
+
+    #[error("Reloaded {pipeline} shader's bindings or push constants differ from the pipeline's")]
+    InterfaceMismatch { pipeline: &'static str },
+
+    #[error("Failed to create render pass: {source}")]
+    RenderPassCreateFailed {
+        #[source]
+        source: dashi::GPUError,
+    },
 }
 
 /// Error variants surfaced by Bento shader compilation and inspection routines.
@@ -87,6 +169,10 @@ pub enum BentoError {
     #[error("Shader compilation error: {0}")]
     ShaderCompilation(String),
 
+    /// shaderc rejected the source; each diagnostic it reported is kept for editors to highlight.
+    #[error("Shader compilation failed:\n{}", join_diagnostics(.diagnostics))]
+    CompilationFailed { diagnostics: Vec<Diagnostic> },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -95,4 +181,24 @@ pub enum BentoError {
 
     #[error("Shader backend error: {0}")]
     Dashi(#[from] dashi::GPUError),
+
+    #[error(
+        "The native {backend} compiler is required but unavailable; set SLANG_LIB_DIR to the \
+         directory containing the Slang library"
+    )]
+    BackendUnavailable { backend: &'static str },
+
+    #[error(
+        "Slang module imports are not supported: sources are compiled through shaderc's HLSL \
+         front end, which can't resolve them; inline the modules with `Request::include_dirs` \
+         instead"
+    )]
+    UnsupportedSlangImports,
+
+    #[error("Failed to load stage from {path}: {source}")]
+    StageFile {
+        path: String,
+        #[source]
+        source: Box<BentoError>,
+    },
 }
@@ -28,6 +28,9 @@ enum StageArg {
     Vertex,
     Fragment,
     Compute,
+    Geometry,
+    TessControl,
+    TessEval,
 }
 
 impl From<StageArg> for dashi::ShaderType {
@@ -36,6 +39,9 @@ impl From<StageArg> for dashi::ShaderType {
             StageArg::Vertex => dashi::ShaderType::Vertex,
             StageArg::Fragment => dashi::ShaderType::Fragment,
             StageArg::Compute => dashi::ShaderType::Compute,
+            StageArg::Geometry => dashi::ShaderType::Geometry,
+            StageArg::TessControl => dashi::ShaderType::TessellationControl,
+            StageArg::TessEval => dashi::ShaderType::TessellationEvaluation,
         }
     }
 }
@@ -125,6 +131,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         optimization: args.optimization.into(),
         debug_symbols: args.debug_symbols,
         defines,
+        ..Default::default()
     };
 
     let compiler = Compiler::new()?;
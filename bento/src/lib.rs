@@ -1,11 +1,16 @@
+pub mod archive;
 pub mod builder;
 pub mod error;
+pub mod pool;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
-    path::Path,
+    hash::Hasher,
+    path::{Path, PathBuf},
 };
 
+use fnv::FnvHasher;
+use rayon::prelude::*;
 use regex::Regex;
 use rspirv::{
     binary::Assemble,
@@ -14,11 +19,16 @@ use rspirv::{
 };
 use serde::{Deserialize, Serialize};
 use shaderc::{
-    CompileOptions, Compiler as ShadercCompiler, EnvVersion, OptimizationLevel as ShadercOpt,
-    ShaderKind, SourceLanguage, SpirvVersion, TargetEnv,
+    CompileOptions, Compiler as ShadercCompiler, EnvVersion, GlslProfile as ShadercProfile,
+    IncludeCallbackResult, IncludeType, OptimizationLevel as ShadercOpt, ResolvedInclude,
+    ResourceKind, ShaderKind, SourceLanguage, SpirvVersion, TargetEnv,
 };
+#[cfg(feature = "spirv-validation")]
+use spirv_tools::val::Validator;
 
+pub use archive::ShaderArchive;
 pub use error::*;
+pub use pool::CompilerPool;
 
 /// Supported input languages for Bento shader compilation.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -38,6 +48,72 @@ pub enum OptimizationLevel {
     Performance,
 }
 
+/// GLSL profile paired with a forced `#version`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GlslProfile {
+    None,
+    Core,
+    Compatibility,
+    Es,
+}
+
+/// Vulkan environment a shader is compiled against.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TargetEnvVersion {
+    Vulkan1_0,
+    Vulkan1_1,
+    #[default]
+    Vulkan1_2,
+    Vulkan1_3,
+}
+
+/// SPIR-V version written into the module header.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpirvTargetVersion {
+    V1_0,
+    V1_1,
+    V1_2,
+    #[default]
+    V1_3,
+    V1_4,
+    V1_5,
+    V1_6,
+}
+
+/// HLSL register class, named after the register prefix it covers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum RegisterClass {
+    /// `t` registers: textures and read-only buffers.
+    Texture,
+    /// `b` registers: constant buffers.
+    ConstantBuffer,
+    /// `u` registers: unordered access views.
+    UnorderedAccess,
+    /// `s` registers: samplers.
+    Sampler,
+}
+
+impl RegisterClass {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "t" => Some(Self::Texture),
+            "b" => Some(Self::ConstantBuffer),
+            "u" => Some(Self::UnorderedAccess),
+            "s" => Some(Self::Sampler),
+            _ => None,
+        }
+    }
+
+    fn resource_kind(self) -> ResourceKind {
+        match self {
+            Self::Texture => ResourceKind::Texture,
+            Self::ConstantBuffer => ResourceKind::Buffer,
+            Self::UnorderedAccess => ResourceKind::UnorderedAccessView,
+            Self::Sampler => ResourceKind::Sampler,
+        }
+    }
+}
+
 /// Representation of a bind group variable discovered during reflection.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ShaderVariable {
@@ -45,6 +121,77 @@ pub struct ShaderVariable {
     #[serde(default)]
     pub set: u32,
     pub kind: dashi::BindTableVariable,
+    /// Specialization constant sizing this binding's descriptor array, if any. `kind.count`
+    /// holds the constant's default.
+    #[serde(default)]
+    pub spec_count: Option<SpecConstantCount>,
+    /// Declared as a runtime-sized array (`textures[]`). `kind.count` is `0` for these; the
+    /// binding is sized by whatever backs it, not by the shader.
+    #[serde(default)]
+    pub unbounded: bool,
+    /// Set for texel buffers (`samplerBuffer`, `imageBuffer`). dashi has no texel buffer
+    /// variable type, so `kind.var_type` reads `Uniform` for both kinds and this tells them
+    /// apart. The pipeline builders can't bind texel buffers until dashi can.
+    #[serde(default)]
+    pub texel_buffer: Option<TexelBufferKind>,
+}
+
+/// Descriptor type of a texel buffer binding.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TexelBufferKind {
+    /// `UNIFORM_TEXEL_BUFFER`, read through a `samplerBuffer` or `Buffer<T>`.
+    Uniform,
+    /// `STORAGE_TEXEL_BUFFER`, read and written through an `imageBuffer` or `RWBuffer<T>`.
+    Storage,
+}
+
+/// Specialization constant that controls the element count of a descriptor array.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpecConstantCount {
+    /// `constant_id` the array length is declared with.
+    pub constant_id: u32,
+    /// Array length when the constant is left unspecialized.
+    pub default: u32,
+}
+
+/// Orders variables by `(set, binding, name)`, the order reflection emits them in. `count` and
+/// the remaining fields break ties, so only variables that compare `Eq` order as equal.
+impl Ord for ShaderVariable {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.set
+            .cmp(&other.set)
+            .then_with(|| self.kind.binding.cmp(&other.kind.binding))
+            .then_with(|| self.name.cmp(&other.name))
+            .then_with(|| self.kind.count.cmp(&other.kind.count))
+            .then_with(|| {
+                var_type_rank(self.kind.var_type).cmp(&var_type_rank(other.kind.var_type))
+            })
+            .then_with(|| self.spec_count.cmp(&other.spec_count))
+            .then_with(|| self.unbounded.cmp(&other.unbounded))
+            .then_with(|| self.texel_buffer.cmp(&other.texel_buffer))
+    }
+}
+
+impl PartialOrd for ShaderVariable {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Position of a variable type in `ShaderVariable`'s ordering.
+fn var_type_rank(var_type: dashi::BindTableVariableType) -> u8 {
+    use dashi::BindTableVariableType;
+
+    match var_type {
+        BindTableVariableType::Uniform => 0,
+        BindTableVariableType::DynamicUniform => 1,
+        BindTableVariableType::Storage => 2,
+        BindTableVariableType::DynamicStorage => 3,
+        BindTableVariableType::SampledImage => 4,
+        BindTableVariableType::Image => 5,
+        BindTableVariableType::StorageImage => 6,
+        BindTableVariableType::Sampler => 7,
+    }
 }
 
 /// Stage-specific metadata discovered during reflection.
@@ -54,8 +201,118 @@ pub struct ShaderMetadata {
     pub inputs: Vec<InterfaceVariable>,
     pub outputs: Vec<InterfaceVariable>,
     pub workgroup_size: Option<[u32; 3]>,
+    /// Whether `workgroup_size` comes from specialization constants, in which case it holds
+    /// their default values and pipelines may override it.
+    #[serde(default)]
+    pub workgroup_size_specializable: bool,
     #[serde(default)]
     pub vertex: Option<VertexLayout>,
+    /// Whether a fragment entry point can discard (`OpKill`, `OpTerminateInvocation` or
+    /// `OpDemoteToHelperInvocation`), which rules out forcing early fragment tests.
+    #[serde(default)]
+    pub may_discard: bool,
+    /// Whether any function performs an atomic operation (`OpAtomic*`), so passes writing the
+    /// same buffers may race with it rather than just overwrite it.
+    #[serde(default)]
+    pub uses_atomics: bool,
+    /// The `push_constant` block, if the shader declares one.
+    #[serde(default)]
+    pub push_constants: Option<BlockLayout>,
+}
+
+/// Byte layout of a reflected block, such as a shader's push-constant block.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockLayout {
+    /// Name of the block's struct type, or empty when the module carries no debug names.
+    #[serde(default)]
+    pub name: String,
+    /// Bytes from the start of the block to the end of its last member.
+    pub size: u32,
+    pub members: Vec<BlockMember>,
+}
+
+/// One member of a [`BlockLayout`], in declaration order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockMember {
+    /// The member's name, or `member{index}` when the module carries no debug names.
+    pub name: String,
+    pub offset: u32,
+    /// The member's type as a dashi primitive, when dashi has an equivalent.
+    pub format: Option<dashi::ShaderPrimitiveType>,
+    /// The member's type including widths dashi cannot express. For matrices this is the
+    /// column type.
+    pub primitive: Option<ShaderPrimitive>,
+    /// Column count when the member is a matrix.
+    pub columns: Option<u32>,
+}
+
+impl BlockLayout {
+    /// Size of the block when its members are packed under std140 rules, rounded up to the
+    /// block's alignment (at least 16 bytes).
+    ///
+    /// Returns `None` if any member's type wasn't reflected, like arrays and nested structs.
+    pub fn std140_size(&self) -> Option<u32> {
+        self.packed(true).map(|(_, size)| size)
+    }
+
+    /// Size of the block when its members are packed under std430 rules, rounded up to the
+    /// largest member alignment.
+    ///
+    /// Returns `None` if any member's type wasn't reflected, like arrays and nested structs.
+    pub fn std430_size(&self) -> Option<u32> {
+        self.packed(false).map(|(_, size)| size)
+    }
+
+    /// Padding bytes std140 inserts before each member, in member order.
+    pub fn std140_padding(&self) -> Option<Vec<u32>> {
+        self.packed(true).map(|(padding, _)| padding)
+    }
+
+    /// Padding bytes std430 inserts before each member, in member order.
+    pub fn std430_padding(&self) -> Option<Vec<u32>> {
+        self.packed(false).map(|(padding, _)| padding)
+    }
+
+    fn packed(&self, std140: bool) -> Option<(Vec<u32>, u32)> {
+        let mut end = 0;
+        let mut block_alignment = if std140 { 16 } else { 1 };
+        let mut padding = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            let (alignment, size) = member.packed_extent(std140)?;
+            let offset = end.next_multiple_of(alignment);
+            padding.push(offset - end);
+            end = offset + size;
+            block_alignment = block_alignment.max(alignment);
+        }
+
+        Some((padding, end.next_multiple_of(block_alignment)))
+    }
+}
+
+impl BlockMember {
+    /// Base alignment and size of the member under std140 or std430 rules.
+    fn packed_extent(&self, std140: bool) -> Option<(u32, u32)> {
+        let primitive = self.primitive?;
+        let component = primitive.width / 8;
+        let vector_alignment = match primitive.components {
+            1 | 2 => component * primitive.components,
+            _ => component * 4,
+        };
+
+        match self.columns {
+            None => Some((vector_alignment, component * primitive.components)),
+            // Column-major matrices pack like an array of columns, and std140 rounds array
+            // strides up to a vec4.
+            Some(columns) => {
+                let stride = if std140 {
+                    vector_alignment.next_multiple_of(16)
+                } else {
+                    vector_alignment
+                };
+                Some((stride, stride * columns))
+            }
+        }
+    }
 }
 
 /// Representation of a shader interface variable (inputs/outputs).
@@ -63,17 +320,94 @@ pub struct ShaderMetadata {
 pub struct InterfaceVariable {
     pub name: String,
     pub location: Option<u32>,
+    /// The variable's type as a dashi primitive, when dashi has an equivalent.
     #[serde(default)]
     pub format: Option<dashi::ShaderPrimitiveType>,
+    /// The variable's type including widths dashi cannot express. For matrices this is the
+    /// column type.
+    #[serde(default)]
+    pub primitive: Option<ShaderPrimitive>,
+    /// Column count when the variable is a matrix. Each column takes its own location, starting
+    /// at `location`.
+    #[serde(default)]
+    pub columns: Option<u32>,
+}
+
+impl InterfaceVariable {
+    /// Every location the variable occupies, one per matrix column.
+    fn locations(&self) -> impl Iterator<Item = u32> {
+        let location = self.location;
+        let columns = self.columns.unwrap_or(1);
+        location.into_iter().flat_map(move |first| first..first + columns)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VertexEntry {
-    pub format: dashi::ShaderPrimitiveType,
+    /// The attribute's type as a dashi primitive, when dashi has an equivalent.
+    pub format: Option<dashi::ShaderPrimitiveType>,
+    pub primitive: ShaderPrimitive,
     pub location: usize,
     pub offset: usize,
 }
 
+/// Component kind of a [`ShaderPrimitive`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum PrimitiveKind {
+    Float,
+    Int,
+    Uint,
+}
+
+/// Width-aware scalar or vector type of an interface variable.
+///
+/// A superset of `dashi::ShaderPrimitiveType` that also covers 16- and 64-bit components and
+/// every vector width from 1 to 4.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ShaderPrimitive {
+    pub kind: PrimitiveKind,
+    /// Bits per component.
+    pub width: u32,
+    pub components: u32,
+}
+
+impl ShaderPrimitive {
+    /// Size of the primitive in bytes.
+    pub fn size(&self) -> usize {
+        (self.width / 8 * self.components) as usize
+    }
+
+    /// The matching dashi primitive, if dashi can describe this type.
+    pub fn to_dashi(&self) -> Option<dashi::ShaderPrimitiveType> {
+        match (self.kind, self.width, self.components) {
+            (PrimitiveKind::Float, 32, 2) => Some(dashi::ShaderPrimitiveType::Vec2),
+            (PrimitiveKind::Float, 32, 3) => Some(dashi::ShaderPrimitiveType::Vec3),
+            (PrimitiveKind::Float, 32, 4) => Some(dashi::ShaderPrimitiveType::Vec4),
+            (PrimitiveKind::Int, 32, 4) => Some(dashi::ShaderPrimitiveType::IVec4),
+            (PrimitiveKind::Uint, 32, 4) => Some(dashi::ShaderPrimitiveType::UVec4),
+            _ => None,
+        }
+    }
+}
+
+impl From<dashi::ShaderPrimitiveType> for ShaderPrimitive {
+    fn from(format: dashi::ShaderPrimitiveType) -> Self {
+        let (kind, components) = match format {
+            dashi::ShaderPrimitiveType::Vec2 => (PrimitiveKind::Float, 2),
+            dashi::ShaderPrimitiveType::Vec3 => (PrimitiveKind::Float, 3),
+            dashi::ShaderPrimitiveType::Vec4 => (PrimitiveKind::Float, 4),
+            dashi::ShaderPrimitiveType::IVec4 => (PrimitiveKind::Int, 4),
+            dashi::ShaderPrimitiveType::UVec4 => (PrimitiveKind::Uint, 4),
+        };
+
+        Self {
+            kind,
+            width: 32,
+            components,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VertexLayout {
     pub entries: Vec<VertexEntry>,
@@ -91,6 +425,138 @@ impl PartialEq for VertexLayout {
 
 impl Eq for VertexLayout {}
 
+/// One way a vertex layout disagrees with another, as reported by [`VertexLayout::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VertexLayoutDifference {
+    Stride { ours: usize, theirs: usize },
+    Rate,
+    /// An attribute location only this layout reads.
+    Removed { location: usize },
+    /// An attribute location only the other layout reads.
+    Added { location: usize },
+    /// Both layouts read the location, but with a different type or offset.
+    Changed {
+        location: usize,
+        ours: VertexEntry,
+        theirs: VertexEntry,
+    },
+}
+
+impl VertexLayout {
+    /// Whether vertex buffers laid out for `self` can feed a pipeline built for `other`.
+    pub fn compatible_with(&self, other: &VertexLayout) -> bool {
+        self.diff(other).is_empty()
+    }
+
+    /// Every difference between the two layouts, with attributes matched by location.
+    pub fn diff(&self, other: &VertexLayout) -> Vec<VertexLayoutDifference> {
+        let mut differences = Vec::new();
+        if self.stride != other.stride {
+            differences.push(VertexLayoutDifference::Stride {
+                ours: self.stride,
+                theirs: other.stride,
+            });
+        }
+        if std::mem::discriminant(&self.rate) != std::mem::discriminant(&other.rate) {
+            differences.push(VertexLayoutDifference::Rate);
+        }
+
+        for ours in &self.entries {
+            match other.entries.iter().find(|e| e.location == ours.location) {
+                None => differences.push(VertexLayoutDifference::Removed {
+                    location: ours.location,
+                }),
+                Some(theirs)
+                    if theirs.primitive != ours.primitive || theirs.offset != ours.offset =>
+                {
+                    differences.push(VertexLayoutDifference::Changed {
+                        location: ours.location,
+                        ours: ours.clone(),
+                        theirs: theirs.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for theirs in &other.entries {
+            if !self.entries.iter().any(|e| e.location == theirs.location) {
+                differences.push(VertexLayoutDifference::Added {
+                    location: theirs.location,
+                });
+            }
+        }
+
+        differences
+    }
+
+    /// Whether a host-defined layout supplies every attribute the shader reads, with the same
+    /// type at each location. Offsets, stride and rate are the host's to choose, so only the
+    /// locations and types are compared.
+    pub fn matches_shader(&self, result: &CompilationResult) -> bool {
+        let Some(shader) = &result.metadata.vertex else {
+            return false;
+        };
+
+        self.entries.len() == shader.entries.len()
+            && shader.entries.iter().all(|theirs| {
+                self.entries.iter().any(|ours| {
+                    ours.location == theirs.location && ours.primitive == theirs.primitive
+                })
+            })
+    }
+}
+
+/// Builds a [`VertexLayout`] on the host, packing attributes in the order they are added, for
+/// checking against a compiled shader with [`VertexLayout::matches_shader`].
+#[derive(Debug, Clone)]
+pub struct VertexLayoutBuilder {
+    entries: Vec<VertexEntry>,
+    stride: usize,
+    rate: dashi::VertexRate,
+}
+
+impl Default for VertexLayoutBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VertexLayoutBuilder {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            stride: 0,
+            rate: dashi::VertexRate::Vertex,
+        }
+    }
+
+    /// Appends an attribute at `location`, placed right after the previous one.
+    pub fn add(mut self, format: impl Into<ShaderPrimitive>, location: usize) -> Self {
+        let primitive = format.into();
+        self.entries.push(VertexEntry {
+            format: primitive.to_dashi(),
+            primitive,
+            location,
+            offset: self.stride,
+        });
+        self.stride += primitive.size();
+        self
+    }
+
+    pub fn rate(mut self, rate: dashi::VertexRate) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn build(self) -> VertexLayout {
+        VertexLayout {
+            entries: self.entries,
+            stride: self.stride,
+            rate: self.rate,
+        }
+    }
+}
+
 /// Parameters describing how a shader should be compiled into a Bento File.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Request {
@@ -101,6 +567,59 @@ pub struct Request {
     pub debug_symbols: bool,
     #[serde(default)]
     pub defines: HashMap<String, Option<String>>,
+    /// Re-adds source-declared bindings that optimization stripped from the module, so shader
+    /// variants keep the same reflected layout.
+    #[serde(default)]
+    pub preserve_all_bindings: bool,
+    /// Overrides the GLSL `#version` and profile, so shaders that omit or disagree on a version
+    /// compile the same way everywhere. Ignored for other languages.
+    #[serde(default)]
+    pub forced_glsl_version: Option<(u32, GlslProfile)>,
+    /// HLSL shader model as `major * 10 + minor` (e.g. `60`). shaderc has no shader model
+    /// switch, so this defines `__SHADER_TARGET_MAJOR` and `__SHADER_TARGET_MINOR` the way DXC
+    /// does. Ignored for other languages.
+    #[serde(default)]
+    pub hlsl_shader_model: Option<u32>,
+    /// Offsets added to HLSL register numbers per register class, so `t0` and `b0` can land on
+    /// different Vulkan bindings. Ignored for other languages.
+    #[serde(default)]
+    pub hlsl_register_shifts: HashMap<RegisterClass, u32>,
+    /// Writes the source-declared binding names into the module as `OpName`s. Shipping builds
+    /// can turn this off to keep shaderc's output as-is; reflected `variables` are unaffected.
+    #[serde(default = "default_rewrite_binding_names")]
+    pub rewrite_binding_names: bool,
+    /// Compiles files as `stage` even when their extension (`.vert`, `.geom`, ...) names a
+    /// different one, instead of failing with `BentoError::InvalidInput`.
+    #[serde(default)]
+    pub ignore_extension_stage: bool,
+    /// Entry point to compile, e.g. `VSMain` in an HLSL or Slang module. `None` means `main`.
+    /// Naming a function the module doesn't export fails with `BentoError::InvalidInput`.
+    #[serde(default)]
+    pub entry_point: Option<String>,
+    /// Vulkan version the module may rely on. Defaults to Vulkan 1.2.
+    #[serde(default)]
+    pub target_env: TargetEnvVersion,
+    /// SPIR-V version to emit. Defaults to 1.3; newer versions need a matching `target_env`
+    /// (1.4 and 1.5 need Vulkan 1.2, 1.6 needs Vulkan 1.3).
+    #[serde(default)]
+    pub target_spirv: SpirvTargetVersion,
+    /// Runs the finished module, binding-name rewrites included, through the SPIR-V validator
+    /// for `target_env`. Invalid modules fail with `BentoError::ShaderCompilation`. Needs the
+    /// `spirv-validation` feature; without it, requests that set this fail with
+    /// `BentoError::InvalidInput`.
+    #[serde(default)]
+    pub validate_spirv: bool,
+    /// Directories searched, in order, for Slang `import name;` modules and `#include` files.
+    /// When set, sources compiled through `compile` and `compile_from_file` have their imports
+    /// inlined before compilation; unresolvable ones fail with `BentoError::InvalidInput`.
+    /// `#include` directives are resolved by the preprocessor, so ones under a disabled `#if`
+    /// are never looked up.
+    #[serde(default)]
+    pub include_dirs: Vec<PathBuf>,
+}
+
+fn default_rewrite_binding_names() -> bool {
+    true
 }
 
 impl Default for Request {
@@ -112,6 +631,17 @@ impl Default for Request {
             optimization: OptimizationLevel::Performance,
             debug_symbols: Default::default(),
             defines: Default::default(),
+            preserve_all_bindings: false,
+            forced_glsl_version: None,
+            hlsl_shader_model: None,
+            hlsl_register_shifts: HashMap::new(),
+            rewrite_binding_names: true,
+            ignore_extension_stage: false,
+            entry_point: None,
+            target_env: TargetEnvVersion::default(),
+            target_spirv: SpirvTargetVersion::default(),
+            validate_spirv: false,
+            include_dirs: Vec::new(),
         }
     }
 }
@@ -126,6 +656,9 @@ pub struct CompilationResult {
     pub variables: Vec<ShaderVariable>,
     pub metadata: ShaderMetadata,
     pub spirv: Vec<u32>,
+    /// Warnings shaderc reported while compiling, one per line of its output.
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 /// Identifies whether a pipeline is used for graphics rendering or compute workloads.
@@ -142,11 +675,18 @@ pub enum Pipeline {
     Compute(ComputePipeline),
 }
 
-/// A graphics pipeline made from vertex and fragment shader results.
+/// A graphics pipeline made from vertex and fragment shader results, plus optional geometry and
+/// tessellation stages.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GraphicsPipeline {
     pub vertex: CompilationResult,
     pub fragment: CompilationResult,
+    #[serde(default)]
+    pub geometry: Option<CompilationResult>,
+    #[serde(default)]
+    pub tessellation_control: Option<CompilationResult>,
+    #[serde(default)]
+    pub tessellation_evaluation: Option<CompilationResult>,
 }
 
 /// A compute pipeline that contains a single compute shader result.
@@ -158,7 +698,9 @@ pub struct ComputePipeline {
 impl Pipeline {
     /// Creates a pipeline from an arbitrary collection of stage compilation results.
     ///
-    /// * Graphics pipelines require both a vertex stage and a fragment stage.
+    /// * Graphics pipelines require both a vertex stage and a fragment stage. Geometry and
+    ///   tessellation stages are optional, but tessellation needs both its control and
+    ///   evaluation stages.
     /// * Compute pipelines contain exactly one compute stage and cannot be mixed with graphics stages.
     pub fn from_stages<I>(stages: I) -> Result<Self, BentoError>
     where
@@ -166,6 +708,9 @@ impl Pipeline {
     {
         let mut vertex: Option<CompilationResult> = None;
         let mut fragment: Option<CompilationResult> = None;
+        let mut geometry: Option<CompilationResult> = None;
+        let mut tessellation_control: Option<CompilationResult> = None;
+        let mut tessellation_evaluation: Option<CompilationResult> = None;
         let mut compute: Option<CompilationResult> = None;
 
         for stage in stages {
@@ -196,9 +741,30 @@ impl Pipeline {
                         "ShaderType::All cannot be used to build a pipeline".into(),
                     ));
                 }
-                dashi::ShaderType::TessellationControl => todo!(),
-                dashi::ShaderType::TessellationEvaluation => todo!(),
-                dashi::ShaderType::Geometry => todo!(),
+                dashi::ShaderType::Geometry => {
+                    if geometry.replace(stage).is_some() {
+                        return Err(BentoError::InvalidInput(
+                            "Graphics pipelines can only contain one geometry stage".into(),
+                        ));
+                    }
+                }
+                dashi::ShaderType::TessellationControl => {
+                    if tessellation_control.replace(stage).is_some() {
+                        return Err(BentoError::InvalidInput(
+                            "Graphics pipelines can only contain one tessellation control stage"
+                                .into(),
+                        ));
+                    }
+                }
+                dashi::ShaderType::TessellationEvaluation => {
+                    if tessellation_evaluation.replace(stage).is_some() {
+                        return Err(BentoError::InvalidInput(
+                            "Graphics pipelines can only contain one tessellation evaluation \
+                             stage"
+                                .into(),
+                        ));
+                    }
+                }
                 dashi::ShaderType::Task => todo!(),
                 dashi::ShaderType::Mesh => todo!(),
                 dashi::ShaderType::RayGeneration => todo!(),
@@ -210,8 +776,12 @@ impl Pipeline {
             }
         }
 
+        let has_optional_stages = geometry.is_some()
+            || tessellation_control.is_some()
+            || tessellation_evaluation.is_some();
+
         if let Some(compute) = compute {
-            if vertex.is_some() || fragment.is_some() {
+            if vertex.is_some() || fragment.is_some() || has_optional_stages {
                 return Err(BentoError::InvalidInput(
                     "Compute pipelines cannot include graphics stages".into(),
                 ));
@@ -228,7 +798,34 @@ impl Pipeline {
             BentoError::InvalidInput("Graphics pipelines require a fragment stage".into())
         })?;
 
-        Ok(Self::Graphics(GraphicsPipeline { vertex, fragment }))
+        if tessellation_control.is_some() != tessellation_evaluation.is_some() {
+            return Err(BentoError::InvalidInput(
+                "Tessellation requires both a control and an evaluation stage".into(),
+            ));
+        }
+
+        Ok(Self::Graphics(GraphicsPipeline {
+            vertex,
+            fragment,
+            geometry,
+            tessellation_control,
+            tessellation_evaluation,
+        }))
+    }
+
+    /// Loads each stage result from disk and assembles them with [`Pipeline::from_stages`].
+    pub fn from_result_files(paths: &[&str]) -> Result<Self, BentoError> {
+        let stages = paths
+            .iter()
+            .map(|path| {
+                CompilationResult::load_from_disk(path).map_err(|source| BentoError::StageFile {
+                    path: path.to_string(),
+                    source: Box::new(source),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_stages(stages)
     }
 
     /// Returns the type of pipeline represented by this instance.
@@ -255,6 +852,14 @@ impl Pipeline {
         }
     }
 
+    /// Returns the geometry shader stage, if the graphics pipeline has one.
+    pub fn geometry(&self) -> Option<&CompilationResult> {
+        match self {
+            Self::Graphics(graphics) => graphics.geometry.as_ref(),
+            Self::Compute(_) => None,
+        }
+    }
+
     /// Returns the compute shader stage, if the pipeline is compute.
     pub fn compute(&self) -> Option<&CompilationResult> {
         match self {
@@ -262,37 +867,107 @@ impl Pipeline {
             Self::Compute(compute) => Some(&compute.compute),
         }
     }
+
+    /// Sorted indices of every descriptor set any stage binds, for sizing a pipeline layout.
+    pub fn descriptor_sets(&self) -> Vec<u32> {
+        let stages = match self {
+            Self::Graphics(graphics) => [&graphics.vertex, &graphics.fragment]
+                .into_iter()
+                .chain(graphics.geometry.as_ref())
+                .chain(graphics.tessellation_control.as_ref())
+                .chain(graphics.tessellation_evaluation.as_ref())
+                .collect(),
+            Self::Compute(compute) => vec![&compute.compute],
+        };
+
+        stages
+            .iter()
+            .flat_map(|stage| stage.variables.iter().map(|var| var.set))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////
 impl ShaderMetadata {
+    /// Vertex attributes for every located input with a dashi format, sorted by location.
+    ///
+    /// Offsets follow the same tight packing as [`VertexLayout`], so inputs dashi can't express
+    /// (half-precision or scalar attributes) still take up their space; only their entry is left
+    /// out. Inputs without a location, like builtins such as `gl_VertexIndex`, are skipped.
     pub fn vertex_inputs(&self) -> Vec<dashi::VertexEntryInfo> {
-        self.inputs
+        let mut attributes: Vec<(u32, ShaderPrimitive, Option<dashi::ShaderPrimitiveType>)> = self
+            .inputs
             .iter()
-            .map(|a| {
-                // TODO: Get format from input, location, and calculate offsets assuming data is
-                // packed.
-                dashi::VertexEntryInfo {
-                    format: todo!(),
-                    location: todo!(),
-                    offset: todo!(),
-                }
+            .flat_map(|input| {
+                let primitive = input.primitive.or(input.format.map(ShaderPrimitive::from));
+                let format = input
+                    .format
+                    .or_else(|| primitive.and_then(|primitive| primitive.to_dashi()));
+                input.locations().filter_map(move |location| {
+                    primitive.map(|primitive| (location, primitive, format))
+                })
             })
-            .collect()
+            .collect();
+        attributes.sort_by_key(|(location, _, _)| *location);
+
+        let mut offset = 0usize;
+        let mut entries = Vec::new();
+        for (location, primitive, format) in attributes {
+            if let Some(format) = format {
+                entries.push(dashi::VertexEntryInfo {
+                    format,
+                    location: location as usize,
+                    offset,
+                });
+            }
+            offset += primitive.size();
+        }
+
+        entries
     }
 }
 
+#[derive(Serialize)]
+struct ReflectionJson<'a> {
+    name: Option<&'a str>,
+    stage: dashi::ShaderType,
+    variables: Vec<ReflectionJsonVariable<'a>>,
+    inputs: &'a [InterfaceVariable],
+    outputs: &'a [InterfaceVariable],
+    vertex: Option<&'a VertexLayout>,
+    entry_points: &'a [String],
+}
+
+#[derive(Serialize)]
+struct ReflectionJsonVariable<'a> {
+    name: &'a str,
+    set: u32,
+    binding: u32,
+    #[serde(rename = "type")]
+    var_type: dashi::BindTableVariableType,
+    count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec_constant_id: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    unbounded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    texel_buffer: Option<TexelBufferKind>,
+}
+
+// Header of `CompilationResult::to_bytes`. Bincode ignores `#[serde(default)]`, so any field
+// added to the result changes the encoding; bump the version when that happens.
+const RESULT_MAGIC: &[u8; 4] = b"BNTO";
+const RESULT_VERSION: u32 = 1;
+const RESULT_HEADER_LEN: usize = RESULT_MAGIC.len() + 4;
+
 impl CompilationResult {
     pub fn save_to_disk(&self, path: &str) -> Result<(), BentoError> {
         let path = Path::new(path);
-
-        if let Some(parent) = path.parent() {
-            if !parent.as_os_str().is_empty() {
-                fs::create_dir_all(parent)?;
-            }
-        }
+        create_parent_dir(path)?;
 
         let bytes = self.to_bytes()?;
         fs::write(path, bytes)?;
@@ -300,12 +975,36 @@ impl CompilationResult {
         Ok(())
     }
 
+    /// Bincode encoding of the result behind a magic tag and format version.
     pub fn to_bytes(&self) -> Result<Vec<u8>, BentoError> {
-        Ok(bincode::serialize(self)?)
+        let body = bincode::serialize(self)?;
+        let mut bytes = Vec::with_capacity(RESULT_HEADER_LEN + body.len());
+        bytes.extend_from_slice(RESULT_MAGIC);
+        bytes.extend_from_slice(&RESULT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&body);
+        Ok(bytes)
     }
 
+    /// Decodes bytes written by `to_bytes`. Results written by another format version, or
+    /// before results carried a version, are rejected and must be recompiled.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, BentoError> {
-        Ok(bincode::deserialize(bytes)?)
+        if bytes.len() < RESULT_HEADER_LEN || &bytes[..RESULT_MAGIC.len()] != RESULT_MAGIC {
+            return Err(BentoError::InvalidInput(
+                "Compiled shader has no format version; it predates versioned results and must \
+                 be recompiled"
+                    .into(),
+            ));
+        }
+
+        let version = u32::from_le_bytes(bytes[4..8].try_into().expect("4-byte version"));
+        if version != RESULT_VERSION {
+            return Err(BentoError::InvalidInput(format!(
+                "Unsupported compiled shader version {version}, expected {RESULT_VERSION}; \
+                 recompile the shader"
+            )));
+        }
+
+        Ok(bincode::deserialize(&bytes[RESULT_HEADER_LEN..])?)
     }
 
     pub fn load_from_disk(path: &str) -> Result<Self, BentoError> {
@@ -313,85 +1012,663 @@ impl CompilationResult {
         Self::from_bytes(&bytes)
     }
 
+    /// Pretty-printed JSON form of the whole result, for asset pipelines that want to diff or
+    /// review compiled shaders. SPIR-V is written as a plain array of words. Bincode via
+    /// `to_bytes` stays the compact default.
+    pub fn to_json(&self) -> Result<String, BentoError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, BentoError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn save_to_json_disk(&self, path: &str) -> Result<(), BentoError> {
+        let path = Path::new(path);
+        create_parent_dir(path)?;
+
+        fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    pub fn load_from_json_disk(path: &str) -> Result<Self, BentoError> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// Checks `spirv` with the SPIR-V validator for `target_env`, e.g. after loading a result
+    /// from disk.
+    #[cfg(feature = "spirv-validation")]
+    pub fn validate(&self, target_env: TargetEnvVersion) -> Result<(), BentoError> {
+        validate_spirv(&self.spirv, target_env)
+    }
+
     pub fn bind_group_variables(&self) -> Vec<dashi::BindTableVariable> {
         let s: Vec<dashi::BindTableVariable> =
             self.variables.iter().map(|a| a.kind.clone()).collect();
 
         return s;
     }
-}
 
-//////////////////////////////////////////////////////////////////////////////
-//////////////////////////////////////////////////////////////////////////////
-//////////////////////////////////////////////////////////////////////////////
+    /// Serializes the shader's interface as JSON for tools that do not link against the GPU
+    /// types.
+    ///
+    /// The output holds `name`, `stage`, `variables` (flattened to `name`/`set`/`binding`/
+    /// `type`/`count`), `inputs`, `outputs`, `vertex` and `entry_points`. The SPIR-V blob is
+    /// deliberately left out so the format stays small and stable.
+    pub fn to_reflection_json(&self) -> String {
+        let reflection = ReflectionJson {
+            name: self.name.as_deref(),
+            stage: self.stage,
+            variables: self
+                .variables
+                .iter()
+                .map(|var| ReflectionJsonVariable {
+                    name: &var.name,
+                    set: var.set,
+                    binding: var.kind.binding,
+                    var_type: var.kind.var_type,
+                    count: var.kind.count,
+                    spec_constant_id: var.spec_count.map(|count| count.constant_id),
+                    unbounded: var.unbounded,
+                    texel_buffer: var.texel_buffer,
+                })
+                .collect(),
+            inputs: &self.metadata.inputs,
+            outputs: &self.metadata.outputs,
+            vertex: self.metadata.vertex.as_ref(),
+            entry_points: &self.metadata.entry_points,
+        };
 
-/// High-level wrapper around shaderc that emits Bento Files.
-pub struct Compiler {
-    compiler: ShadercCompiler,
-}
+        serde_json::to_string(&reflection).expect("reflection data is always serializable")
+    }
 
-impl Compiler {
-    pub fn new() -> Result<Self, BentoError> {
-        let compiler = ShadercCompiler::new()
-            .ok_or_else(|| BentoError::ShaderCompilation("Failed to initialize compiler".into()))?;
+    /// The reflected variable named `name`, carrying its set and binding.
+    pub fn binding_by_name(&self, name: &str) -> Option<&ShaderVariable> {
+        self.variables.iter().find(|var| var.name == name)
+    }
 
-        Ok(Self { compiler })
+    /// Location of the stage input named `name`, if it has one.
+    pub fn location_of_input(&self, name: &str) -> Option<u32> {
+        self.metadata
+            .inputs
+            .iter()
+            .find(|var| var.name == name)
+            .and_then(|var| var.location)
     }
 
-    pub fn compile(
-        &self,
-        shader: &[u8],
-        request: &Request,
-    ) -> Result<CompilationResult, BentoError> {
-        self.compile_with_path(shader, request, None)
+    /// Returns the reflected variables sorted by `(set, binding, name)`, independent of the
+    /// order reflection happened to produce them in.
+    pub fn sorted_variables(&self) -> Vec<ShaderVariable> {
+        let mut variables = self.variables.clone();
+        variables.sort();
+        variables
     }
 
-    pub fn compile_from_file(
-        &self,
-        path: &str,
-        request: &Request,
-    ) -> Result<CompilationResult, BentoError> {
-        let bytes = fs::read(path)
-            .map_err(|e| BentoError::Io(std::io::Error::new(e.kind(), format!("{path}: {e}"))))?;
-        let mut result = self.compile_with_path(&bytes, request, Some(path))?;
-        result.file = Some(path.to_string());
+    /// Stable 64-bit hash of the SPIR-V, stage and sorted variables, for build caches.
+    ///
+    /// `name` and `file` are ignored, so identical compiles of differently named sources hash
+    /// equal. The hash is FNV-1a over a fixed encoding and does not change between runs or
+    /// toolchains.
+    pub fn content_hash(&self) -> u64 {
+        let interface = serde_json::to_vec(&(self.stage, self.sorted_variables()))
+            .expect("interface data is always serializable");
+
+        let mut hasher = FnvHasher::default();
+        for word in &self.spirv {
+            hasher.write(&word.to_le_bytes());
+        }
+        hasher.write(&interface);
+        hasher.finish()
+    }
+
+    /// Whether `other` has the same stage, bindings and push constants, so a pipeline built for
+    /// this shader can run `other`'s SPIR-V with the same layouts and bind tables.
+    pub fn interface_eq(&self, other: &CompilationResult) -> bool {
+        self.stage == other.stage
+            && self.sorted_variables() == other.sorted_variables()
+            && self.metadata.push_constants == other.metadata.push_constants
+    }
+
+    /// Reflected variables the shader's functions never reference.
+    ///
+    /// A binding counts as used when any instruction in a function body (a load, access chain,
+    /// image or atomic operation, ...) refers to its variable. Unoptimized builds keep such
+    /// bindings in the module, so they still occupy a descriptor slot the host has to fill.
+    pub fn unused_bindings(&self) -> Vec<&ShaderVariable> {
+        let Ok(referenced) = referenced_bindings(&self.spirv) else {
+            return Vec::new();
+        };
+
+        self.variables
+            .iter()
+            .filter(|var| !referenced.contains(&(var.set, var.kind.binding)))
+            .collect()
+    }
+
+    /// Reflected variables that are combined image samplers (`sampler2D` and friends).
+    ///
+    /// Engines that bind textures and samplers separately, like furikake's bindless tables,
+    /// can't feed these bindings, so loaders can reject or recompile such shaders up front.
+    pub fn combined_sampler_bindings(&self) -> Vec<&ShaderVariable> {
+        self.variables
+            .iter()
+            .filter(|var| var.kind.var_type == dashi::BindTableVariableType::SampledImage)
+            .collect()
+    }
+
+    /// Color attachment formats matching each fragment output, in location order.
+    ///
+    /// Float outputs map to 8-bit UNORM formats with the same component count, and integer
+    /// outputs to 32-bit integer formats so values aren't normalized. 16-bit float outputs map
+    /// to `RG16F` for one or two components, leaving a single-component output's second channel
+    /// unused, and to `RGBA16F` otherwise. Outputs of unknown type fall back to `RGBA8`.
+    pub fn inferred_attachment_formats(&self) -> Vec<dashi::Format> {
+        self.inferred_attachments()
+            .into_iter()
+            .map(|(_, format)| format)
+            .collect()
+    }
+
+    /// `inferred_attachment_formats` paired with each output's location. Outputs without a
+    /// location come last, in reflection order.
+    pub(crate) fn inferred_attachments(&self) -> Vec<(Option<u32>, dashi::Format)> {
+        use dashi::Format;
+
+        let mut attachments: Vec<(Option<u32>, Format)> = self
+            .metadata
+            .outputs
+            .iter()
+            .map(|output| {
+                let primitive = output.primitive.or_else(|| output.format.map(Into::into));
+                let Some(primitive) = primitive else {
+                    return (output.location, Format::RGBA8);
+                };
+
+                let format = match (primitive.kind, primitive.width, primitive.components) {
+                    // One-channel half outputs use RG16F and leave its green channel unused.
+                    (PrimitiveKind::Float, 16, 1 | 2) => Format::RG16F,
+                    (PrimitiveKind::Float, 16, _) => Format::RGBA16F,
+                    (PrimitiveKind::Float, _, 1) => Format::R8,
+                    (PrimitiveKind::Float, _, 2) => Format::RG8,
+                    (PrimitiveKind::Float, _, 3) => Format::RGB8,
+                    (PrimitiveKind::Float, _, _) => Format::RGBA8,
+                    (PrimitiveKind::Int, _, 1) => Format::R32Sint,
+                    (PrimitiveKind::Int, _, _) => Format::RGBA32Sint,
+                    (PrimitiveKind::Uint, _, 1) => Format::R32Uint,
+                    (PrimitiveKind::Uint, _, _) => Format::RGBA32Uint,
+                };
+                (output.location, format)
+            })
+            .collect();
+        attachments.sort_by_key(|(location, _)| location.unwrap_or(u32::MAX));
+        attachments
+    }
+
+    /// Reports holes in each descriptor set's binding sequence as `(set, binding)` pairs.
+    ///
+    /// Bindings are expected to be contiguous starting at 0; any binding number below the
+    /// highest used binding in a set that is not reflected is returned. This is purely
+    /// diagnostic and does not affect compilation.
+    pub fn binding_gaps(&self) -> Vec<(u32, u32)> {
+        let mut bindings_by_set: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+        for var in &self.variables {
+            bindings_by_set
+                .entry(var.set)
+                .or_default()
+                .insert(var.kind.binding);
+        }
+
+        let mut gaps = Vec::new();
+        for (set, bindings) in bindings_by_set {
+            let Some(highest) = bindings.last().copied() else {
+                continue;
+            };
+
+            for binding in 0..highest {
+                if !bindings.contains(&binding) {
+                    gaps.push((set, binding));
+                }
+            }
+        }
+
+        gaps
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////
+
+/// High-level wrapper around shaderc that emits Bento Files.
+pub struct Compiler {
+    compiler: ShadercCompiler,
+    cache_dir: Option<PathBuf>,
+}
+
+impl Compiler {
+    pub fn new() -> Result<Self, BentoError> {
+        let compiler = ShadercCompiler::new()
+            .ok_or_else(|| BentoError::ShaderCompilation("Failed to initialize compiler".into()))?;
+
+        Ok(Self {
+            compiler,
+            cache_dir: None,
+        })
+    }
+
+    /// Creates a compiler that caches `compile` and `compile_from_file` results in `dir`.
+    ///
+    /// Artifacts are stored as `<key>.bento`, where the key hashes the source bytes together with
+    /// the resolved language and every `Request` option, so changing any of them misses the
+    /// cache. Unreadable or stale artifacts are recompiled and overwritten.
+    pub fn with_cache(dir: PathBuf) -> Result<Self, BentoError> {
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            cache_dir: Some(dir),
+            ..Self::new()?
+        })
+    }
+
+    pub fn compile(
+        &self,
+        shader: &[u8],
+        request: &Request,
+    ) -> Result<CompilationResult, BentoError> {
+        self.compile_with_path(shader, request, None, None)
+    }
+
+    pub fn compile_from_file(
+        &self,
+        path: &str,
+        request: &Request,
+    ) -> Result<CompilationResult, BentoError> {
+        check_extension_stage(Path::new(path), request)?;
+        let bytes = fs::read(path)
+            .map_err(|e| BentoError::Io(std::io::Error::new(e.kind(), format!("{path}: {e}"))))?;
+        let mut result = self.compile_with_path(&bytes, request, Some(path), None)?;
+        result.file = Some(path.to_string());
 
         Ok(result)
     }
 
+    /// Compiles the shader at `base.join(relative)`, resolving `#include` directives against
+    /// `base` at runtime instead of any build-time directory.
+    ///
+    /// Quoted includes are looked up next to the including file first; angle-bracket includes
+    /// are looked up in `base`. This lets installed applications load shaders from wherever
+    /// they were shipped.
+    pub fn compile_from_file_in(
+        &self,
+        base: &Path,
+        relative: &str,
+        request: &Request,
+    ) -> Result<CompilationResult, BentoError> {
+        let path = base.join(relative);
+        check_extension_stage(&path, request)?;
+        let display = path.to_string_lossy().into_owned();
+        let bytes = fs::read(&path).map_err(|e| {
+            BentoError::Io(std::io::Error::new(e.kind(), format!("{display}: {e}")))
+        })?;
+
+        let roots = IncludeRoots {
+            base: Some(base),
+            source_dir: Some(path.parent().unwrap_or(base)),
+            include_dirs: &request.include_dirs,
+        };
+        let mut result = self.compile_with_path(&bytes, request, Some(&display), Some(&roots))?;
+        result.file = Some(display);
+
+        Ok(result)
+    }
+
+    /// Compiles `shader`, read from `path` if it came from a file, through the cache.
+    /// `#include` directives resolve against `includes`, or against the file's directory and
+    /// `request.include_dirs` when it is `None`.
     fn compile_with_path(
         &self,
         shader: &[u8],
         request: &Request,
         path: Option<&str>,
+        includes: Option<&IncludeRoots<'_>>,
     ) -> Result<CompilationResult, BentoError> {
         let source = std::str::from_utf8(shader)
             .map_err(|_| BentoError::InvalidInput("Shader source is not valid UTF-8".into()))?;
 
-        let mut options = CompileOptions::new()
-            .ok_or_else(|| BentoError::ShaderCompilation("Failed to create options".into()))?;
-
         let resolved_lang = if matches!(request.lang, ShaderLang::Infer) {
             infer_shader_lang(source, path)
         } else {
             request.lang
         };
 
-        options.set_auto_combined_image_sampler(false);
+        // Slang imports are inlined as text and `#include` directives expanded by the
+        // preprocessor, both up front so the cache key covers the included files too.
+        let entry_point = request.entry_point.as_deref().unwrap_or("main");
+        let default_roots = IncludeRoots {
+            base: None,
+            source_dir: path.map(Path::new).and_then(Path::parent),
+            include_dirs: &request.include_dirs,
+        };
+        let expanded;
+        let (source, shader) = if includes.is_none() && request.include_dirs.is_empty() {
+            (source, shader)
+        } else {
+            let imported = if resolved_lang == ShaderLang::Slang {
+                inline_imports(source, path.map(Path::new), &request.include_dirs)?
+            } else {
+                source.to_string()
+            };
+            let roots = includes.unwrap_or(&default_roots);
+            let options = compile_options(resolved_lang, request, Some(roots))?;
+            expanded = self
+                .compiler
+                .preprocess(&imported, path.unwrap_or("shader"), entry_point, Some(&options))
+                .map_err(compilation_failed)?
+                .as_text();
+            (expanded.as_str(), expanded.as_bytes())
+        };
+
+        let Some(dir) = &self.cache_dir else {
+            #[cfg(feature = "log")]
+            log::debug!(
+                target: "bento",
+                "compiling {:?} (source hash {:016x}, no cache)",
+                request.name,
+                cache_key(shader, resolved_lang, request)?
+            );
+            return self.compile_entry(source, resolved_lang, request, entry_point);
+        };
+
+        let key = cache_key(shader, resolved_lang, request)?;
+        let artifact = dir.join(format!("{key:016x}.bento"));
+        let cached = fs::read(&artifact)
+            .ok()
+            .and_then(|bytes| CompilationResult::from_bytes(&bytes).ok());
+        if let Some(cached) = cached {
+            #[cfg(feature = "log")]
+            log::debug!(target: "bento", "cache hit for {:?} ({key:016x})", request.name);
+            return Ok(cached);
+        }
+        #[cfg(feature = "log")]
+        log::debug!(target: "bento", "cache miss for {:?} ({key:016x})", request.name);
+
+        let result = self.compile_entry(source, resolved_lang, request, entry_point)?;
+        // A cache that can't be written only costs a recompile next time.
+        let _ = result.to_bytes().map(|bytes| fs::write(&artifact, bytes));
+
+        Ok(result)
+    }
+
+    /// Compiles `shader` twice: once unoptimized with debug symbols and once optimized without.
+    ///
+    /// Returns `(debug, optimized)`. Each build goes through the same include handling and
+    /// cache as [`Compiler::compile`], keyed on its own request. The optimized build uses
+    /// `request.optimization`, falling back to `OptimizationLevel::Performance` when the request
+    /// asks for none. Both builds must reflect the same variables, otherwise an error is
+    /// returned.
+    pub fn compile_dual(
+        &self,
+        shader: &[u8],
+        request: &Request,
+    ) -> Result<(CompilationResult, CompilationResult), BentoError> {
+        let debug_request = Request {
+            optimization: OptimizationLevel::None,
+            debug_symbols: true,
+            ..request.clone()
+        };
+        let optimized_request = Request {
+            optimization: match request.optimization {
+                OptimizationLevel::None => OptimizationLevel::Performance,
+                level => level,
+            },
+            debug_symbols: false,
+            ..request.clone()
+        };
+
+        let debug = self.compile_with_path(shader, &debug_request, None, None)?;
+        let optimized = self.compile_with_path(shader, &optimized_request, None, None)?;
+
+        if debug.variables != optimized.variables {
+            return Err(BentoError::ShaderCompilation(
+                "Debug and optimized builds reflect different variables".into(),
+            ));
+        }
+
+        Ok((debug, optimized))
+    }
+
+    /// Compiles every `(source, request)` pair on a rayon thread pool and returns one result per
+    /// pair, in input order. A shader that fails doesn't stop the rest of the batch.
+    ///
+    /// shaderc compilers can't be shared between threads, so each worker creates its own and
+    /// reuses it for every shader it picks up. Workers share this compiler's cache directory.
+    pub fn compile_batch(
+        &self,
+        requests: &[(Vec<u8>, Request)],
+    ) -> Vec<Result<CompilationResult, BentoError>> {
+        let cache_dir = &self.cache_dir;
+        requests
+            .par_iter()
+            .map_init(
+                || {
+                    Compiler::new().map(|compiler| Compiler {
+                        cache_dir: cache_dir.clone(),
+                        ..compiler
+                    })
+                },
+                |worker, (source, request)| match worker {
+                    Ok(compiler) => compiler.compile(source, request),
+                    Err(_) => Err(BentoError::ShaderCompilation(
+                        "Failed to initialize compiler".into(),
+                    )),
+                },
+            )
+            .collect()
+    }
+
+    /// Compiles one permutation of `source` for every combination of the values in `matrix`,
+    /// which maps a define name to the values it takes, and returns each permutation's defines
+    /// alongside its result.
+    ///
+    /// Permutation defines are added on top of `base_request.defines`, replacing any with the
+    /// same name. The returned maps hold only the matrix defines. Permutations are compiled as a
+    /// [`Compiler::compile_batch`], so one failing doesn't stop the rest.
+    pub fn compile_permutations(
+        &self,
+        source: &[u8],
+        base_request: &Request,
+        matrix: &[(&str, &[Option<String>])],
+    ) -> Vec<(HashMap<String, Option<String>>, Result<CompilationResult, BentoError>)> {
+        let mut permutations = vec![HashMap::new()];
+        for (name, values) in matrix {
+            permutations = permutations
+                .into_iter()
+                .flat_map(|defines: HashMap<String, Option<String>>| {
+                    values.iter().map(move |value| {
+                        let mut defines = defines.clone();
+                        defines.insert(name.to_string(), value.clone());
+                        defines
+                    })
+                })
+                .collect();
+        }
+
+        let requests: Vec<(Vec<u8>, Request)> = permutations
+            .iter()
+            .map(|defines| {
+                let mut request = base_request.clone();
+                request.defines.extend(defines.clone());
+                (source.to_vec(), request)
+            })
+            .collect();
+
+        permutations
+            .into_iter()
+            .zip(self.compile_batch(&requests))
+            .collect()
+    }
+
+    /// Compiles several entry points out of a single shader module.
+    ///
+    /// The source is decoded, its language resolved and the module preprocessed once. shaderc
+    /// emits one entry point per invocation, so each requested entry point is then compiled from
+    /// the preprocessed module and reflected on its own, in parallel like
+    /// [`Compiler::compile_batch`], going through the cache keyed on that entry point. Results
+    /// are returned in the order the entry points were given and are named after their entry
+    /// point.
+    pub fn compile_library(
+        &self,
+        source: &[u8],
+        lang: ShaderLang,
+        entry_points: &[(&str, dashi::ShaderType)],
+    ) -> Result<Vec<CompilationResult>, BentoError> {
+        let source = std::str::from_utf8(source)
+            .map_err(|_| BentoError::InvalidInput("Shader source is not valid UTF-8".into()))?;
+
+        let resolved_lang = if matches!(lang, ShaderLang::Infer) {
+            infer_shader_lang(source, None)
+        } else {
+            lang
+        };
+        if resolved_lang == ShaderLang::Slang {
+            check_slang_source(source)?;
+        }
+
+        let mut options = CompileOptions::new()
+            .ok_or_else(|| BentoError::ShaderCompilation("Failed to create options".into()))?;
         options.set_source_language(source_language(resolved_lang)?);
-        options.set_target_env(TargetEnv::Vulkan, EnvVersion::Vulkan1_2 as u32);
-        options.set_target_spirv(SpirvVersion::V1_3);
-        options.set_optimization_level(shaderc_optimization(request.optimization));
+        let module = self
+            .compiler
+            .preprocess(source, "library", "main", Some(&options))
+            .map_err(compilation_failed)?
+            .as_text();
+
+        let cache_dir = &self.cache_dir;
+        entry_points
+            .par_iter()
+            .map_init(
+                || {
+                    Compiler::new().map(|compiler| Compiler {
+                        cache_dir: cache_dir.clone(),
+                        ..compiler
+                    })
+                },
+                |worker, (entry_point, stage)| {
+                    let Ok(compiler) = worker else {
+                        return Err(BentoError::ShaderCompilation(
+                            "Failed to initialize compiler".into(),
+                        ));
+                    };
+                    let request = Request {
+                        name: Some(entry_point.to_string()),
+                        lang: resolved_lang,
+                        stage: *stage,
+                        entry_point: Some(entry_point.to_string()),
+                        ..Default::default()
+                    };
+
+                    compiler.compile_with_path(module.as_bytes(), &request, None, None)
+                },
+            )
+            .collect()
+    }
 
-        for (name, value) in &request.defines {
-            options.add_macro_definition(name, value.as_deref());
+    /// Reflects the layout of every struct a shared header declares.
+    ///
+    /// The header is wrapped in a minimal compute shader that declares one uniform block per
+    /// struct, so the structs get std140 offsets, and compiled with debug names. Layouts are
+    /// returned in declaration order. Only GLSL and HLSL headers are supported.
+    pub fn reflect_header(
+        &self,
+        source: &str,
+        lang: ShaderLang,
+    ) -> Result<Vec<BlockLayout>, BentoError> {
+        let struct_regex = Regex::new(r"\bstruct\s+([A-Za-z_][A-Za-z0-9_]*)\s*\{")
+            .map_err(|e| BentoError::ShaderCompilation(format!("Invalid struct regex: {e}")))?;
+        let structs: Vec<String> = struct_regex
+            .captures_iter(source)
+            .filter_map(|captures| captures.get(1).map(|m| m.as_str().to_string()))
+            .collect();
+        if structs.is_empty() {
+            return Ok(Vec::new());
         }
 
-        if request.debug_symbols {
-            options.set_generate_debug_info();
+        let resolved_lang = if matches!(lang, ShaderLang::Infer) {
+            infer_shader_lang(source, None)
+        } else {
+            lang
+        };
+
+        let mut shell = String::new();
+        match resolved_lang {
+            ShaderLang::Glsl => {
+                if !source.contains("#version") {
+                    shell.push_str("#version 450\n");
+                }
+                shell.push_str(source);
+                shell.push_str("\nlayout(local_size_x = 1) in;\n");
+                for (index, name) in structs.iter().enumerate() {
+                    shell.push_str(&format!(
+                        "layout(set = 0, binding = {index}, std140) uniform \
+                         BentoHeaderBlock{index} {{ {name} value; }} bento_header_{index};\n"
+                    ));
+                }
+                shell.push_str("void main() {}\n");
+            }
+            ShaderLang::Hlsl => {
+                shell.push_str(source);
+                shell.push('\n');
+                for (index, name) in structs.iter().enumerate() {
+                    shell.push_str(&format!(
+                        "cbuffer BentoHeaderBlock{index} : register(b{index}) \
+                         {{ {name} bento_header_{index}; }};\n"
+                    ));
+                }
+                shell.push_str("[numthreads(1, 1, 1)]\nvoid main() {}\n");
+            }
+            other => {
+                return Err(BentoError::InvalidInput(format!(
+                    "Header reflection does not support {other:?} sources"
+                )));
+            }
+        }
+
+        let request = Request {
+            name: Some("header".to_string()),
+            lang: resolved_lang,
+            stage: dashi::ShaderType::Compute,
+            optimization: OptimizationLevel::None,
+            debug_symbols: true,
+            ..Default::default()
+        };
+        let result = self.compile_entry(&shell, resolved_lang, &request, "main")?;
+
+        reflect_struct_layouts(spirv_words_to_bytes(&result.spirv), &structs)
+    }
+
+    fn compile_entry(
+        &self,
+        source: &str,
+        resolved_lang: ShaderLang,
+        request: &Request,
+        entry_point: &str,
+    ) -> Result<CompilationResult, BentoError> {
+        if resolved_lang == ShaderLang::Slang {
+            check_slang_source(source)?;
         }
 
+        let options = compile_options(resolved_lang, request, None)?;
+
         let shader_kind = shader_stage(request.stage)?;
+        // shaderc names a GLSL module's entry point after the request but always compiles
+        // `main`, so GLSL keeps its own name and the reflection check below rejects the rest.
+        let compiled_entry_point = if resolved_lang == ShaderLang::Glsl {
+            "main"
+        } else {
+            entry_point
+        };
 
         let artifact = self
             .compiler
@@ -399,36 +1676,66 @@ impl Compiler {
                 source,
                 shader_kind,
                 request.name.as_deref().unwrap_or("shader"),
-                "main",
+                compiled_entry_point,
                 Some(&options),
             )
-            .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
+            .map_err(compilation_failed)?;
+        let warnings = warning_lines(&artifact.get_warning_messages());
 
         let spirv = artifact.as_binary().to_vec();
+        if !spirv_entry_points(&spirv).iter().any(|name| name == entry_point) {
+            return Err(missing_entry_point(entry_point));
+        }
         let reflection_spirv = if request.debug_symbols {
             strip_debug_instructions(&spirv)
         } else {
             spirv.clone()
         };
+        let (frozen_spirv, spec_counts) = spec_sized_bindings(&reflection_spirv)?;
         let reflected = reflect_bindings(
-            spirv_words_to_bytes(&reflection_spirv),
+            spirv_words_to_bytes(&frozen_spirv),
             source,
             resolved_lang,
+            &request.hlsl_register_shifts,
+            &spec_counts,
         )?;
-        let variables = reflected.variables;
+        let mut variables = reflected.variables;
+        let rename = request.rewrite_binding_names;
         let (metadata_spirv, final_spirv) = if request.debug_symbols {
-            match rewrite_spirv_binding_names(&spirv, &variables, &reflected.remap) {
+            match rewrite_spirv_binding_names(&spirv, &variables, &reflected.remap, rename) {
                 Ok(rewritten) => (rewritten.clone(), rewritten),
                 Err(_) => (reflection_spirv.clone(), spirv.clone()),
             }
         } else {
-            match rewrite_spirv_binding_names(&reflection_spirv, &variables, &reflected.remap) {
+            match rewrite_spirv_binding_names(
+                &reflection_spirv,
+                &variables,
+                &reflected.remap,
+                rename,
+            ) {
                 Ok(rewritten) => (rewritten.clone(), rewritten),
                 Err(_) => (reflection_spirv.clone(), reflection_spirv.clone()),
             }
         };
         let metadata = reflect_metadata(spirv_words_to_bytes(&metadata_spirv))?;
         let spirv = final_spirv;
+        if request.validate_spirv {
+            #[cfg(feature = "spirv-validation")]
+            validate_spirv(&spirv, request.target_env)?;
+            #[cfg(not(feature = "spirv-validation"))]
+            return Err(BentoError::InvalidInput(
+                "SPIR-V validation needs bento's `spirv-validation` feature".into(),
+            ));
+        }
+
+        if request.preserve_all_bindings {
+            restore_stripped_bindings(
+                &mut variables,
+                source,
+                resolved_lang,
+                &request.hlsl_register_shifts,
+            )?;
+        }
 
         Ok(CompilationResult {
             name: request.name.clone(),
@@ -438,20 +1745,346 @@ impl Compiler {
             variables,
             metadata,
             spirv,
+            warnings,
+        })
+    }
+}
+
+/// Turns a failed shaderc compile into one diagnostic per reported problem.
+fn compilation_failed(error: shaderc::Error) -> BentoError {
+    let output = match &error {
+        shaderc::Error::CompilationError(_, output) => output.clone(),
+        other => other.to_string(),
+    };
+
+    BentoError::CompilationFailed {
+        diagnostics: parse_diagnostics(&output),
+    }
+}
+
+/// Parses shaderc's `file:line: severity: message` output. Lines without a location become
+/// errors carrying the whole line; the trailing `N errors generated.` summary is dropped.
+fn parse_diagnostics(output: &str) -> Vec<Diagnostic> {
+    let Ok(located) =
+        Regex::new(r"^(.*?):(\d+):(?:(\d+):)?\s*(error|warning|note):\s*(.*)$")
+    else {
+        return Vec::new();
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = diagnostic_lines(output)
+        .map(|line| match located.captures(line) {
+            Some(captures) => Diagnostic {
+                file: captures
+                    .get(1)
+                    .map(|m| m.as_str().to_string())
+                    .filter(|file| !file.is_empty()),
+                line: captures.get(2).and_then(|m| m.as_str().parse().ok()),
+                column: captures.get(3).and_then(|m| m.as_str().parse().ok()),
+                severity: match captures.get(4).map(|m| m.as_str()) {
+                    Some("warning") => DiagnosticSeverity::Warning,
+                    Some("note") => DiagnosticSeverity::Note,
+                    _ => DiagnosticSeverity::Error,
+                },
+                message: captures
+                    .get(5)
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default(),
+            },
+            None => Diagnostic {
+                file: None,
+                line: None,
+                column: None,
+                severity: DiagnosticSeverity::Error,
+                message: line.to_string(),
+            },
+        })
+        .collect();
+
+    if diagnostics.is_empty() {
+        diagnostics.push(Diagnostic {
+            file: None,
+            line: None,
+            column: None,
+            severity: DiagnosticSeverity::Error,
+            message: output.trim().to_string(),
+        });
+    }
+
+    diagnostics
+}
+
+fn warning_lines(output: &str) -> Vec<String> {
+    diagnostic_lines(output).map(str::to_string).collect()
+}
+
+/// Non-empty lines of shaderc output, minus its `N errors generated.` summary.
+fn diagnostic_lines(output: &str) -> impl Iterator<Item = &str> {
+    output.lines().map(str::trim).filter(|line| {
+        !line.is_empty() && !(line.ends_with(" generated.") && line.starts_with(char::is_numeric))
+    })
+}
+
+fn create_parent_dir(path: &Path) -> Result<(), BentoError> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// shaderc options for compiling `request`, with `#include` directives resolved against
+/// `includes` when given.
+fn compile_options<'a>(
+    resolved_lang: ShaderLang,
+    request: &Request,
+    includes: Option<&'a IncludeRoots<'a>>,
+) -> Result<CompileOptions<'a>, BentoError> {
+    let mut options = CompileOptions::new()
+        .ok_or_else(|| BentoError::ShaderCompilation("Failed to create options".into()))?;
+
+    if let Some(roots) = includes {
+        options.set_include_callback(move |requested, include_type, requesting, _depth| {
+            roots.resolve(requested, include_type, requesting)
+        });
+    }
+
+    options.set_auto_combined_image_sampler(false);
+    options.set_source_language(source_language(resolved_lang)?);
+    options.set_target_env(TargetEnv::Vulkan, shaderc_env_version(request.target_env) as u32);
+    options.set_target_spirv(shaderc_spirv_version(request.target_spirv));
+    options.set_optimization_level(shaderc_optimization(request.optimization));
+
+    for (name, value) in &request.defines {
+        options.add_macro_definition(name, value.as_deref());
+    }
+
+    match resolved_lang {
+        ShaderLang::Glsl => {
+            if let Some((version, profile)) = request.forced_glsl_version {
+                options.set_forced_version_profile(version, shaderc_profile(profile));
+            }
+        }
+        ShaderLang::Hlsl => {
+            if let Some(model) = request.hlsl_shader_model {
+                let (major, minor) = ((model / 10).to_string(), (model % 10).to_string());
+                options.add_macro_definition("__SHADER_TARGET_MAJOR", Some(&major));
+                options.add_macro_definition("__SHADER_TARGET_MINOR", Some(&minor));
+            }
+            if !request.hlsl_register_shifts.is_empty() {
+                options.set_hlsl_io_mapping(true);
+                for (class, shift) in &request.hlsl_register_shifts {
+                    options.set_binding_base(class.resource_kind(), *shift);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if request.debug_symbols {
+        options.set_generate_debug_info();
+    }
+
+    Ok(options)
+}
+
+fn missing_entry_point(entry_point: &str) -> BentoError {
+    BentoError::InvalidInput(format!("Entry point '{entry_point}' not found in shader module"))
+}
+
+/// Directories `#include` directives resolve against. `Request::include_dirs` are searched
+/// after the including file's directory for quoted includes and after `base` for angle-bracket
+/// ones.
+struct IncludeRoots<'a> {
+    base: Option<&'a Path>,
+    source_dir: Option<&'a Path>,
+    include_dirs: &'a [PathBuf],
+}
+
+impl IncludeRoots<'_> {
+    fn resolve(
+        &self,
+        requested: &str,
+        include_type: IncludeType,
+        requesting: &str,
+    ) -> IncludeCallbackResult {
+        let requesting = Path::new(requesting);
+        let local = match include_type {
+            IncludeType::Relative if requesting.is_file() => {
+                requesting.parent().or(self.source_dir)
+            }
+            IncludeType::Relative => self.source_dir,
+            IncludeType::Standard => self.base,
+        };
+
+        let path = local
+            .into_iter()
+            .chain(self.include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(requested))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| format!("Unable to resolve '{requested}' in the include directories"))?;
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        Ok(ResolvedInclude {
+            resolved_name: path.to_string_lossy().into_owned(),
+            content,
         })
     }
 }
 
+/// Inlines Slang `import name;` lines from `include_dirs`, looking next to the importing file
+/// first. Each module is inlined once, so a module two branches share is only emitted the first
+/// time, and `#line` directives keep diagnostics pointing at the line that pulled it in. A module
+/// that ends up importing itself is an error. `#include` is left to the preprocessor.
+fn inline_imports(
+    source: &str,
+    path: Option<&Path>,
+    include_dirs: &[PathBuf],
+) -> Result<String, BentoError> {
+    let mut visited = HashSet::new();
+    let mut stack: Vec<PathBuf> = path.map(canonical_path).into_iter().collect();
+    visited.extend(stack.iter().cloned());
+    inline_imports_in(
+        source,
+        path.and_then(Path::parent),
+        include_dirs,
+        &mut visited,
+        &mut stack,
+    )
+}
+
+fn inline_imports_in(
+    source: &str,
+    source_dir: Option<&Path>,
+    include_dirs: &[PathBuf],
+    visited: &mut HashSet<PathBuf>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<String, BentoError> {
+    let mut resolved = String::with_capacity(source.len());
+    for (index, line) in source.lines().enumerate() {
+        let Some(requested) = import_target(line) else {
+            resolved.push_str(line);
+            resolved.push('\n');
+            continue;
+        };
+
+        let found = source_dir
+            .into_iter()
+            .chain(include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&requested))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| {
+                BentoError::InvalidInput(format!(
+                    "Unable to resolve '{}' in the include directories",
+                    requested.display()
+                ))
+            })?;
+
+        let canonical = canonical_path(&found);
+        if let Some(start) = stack.iter().position(|active| *active == canonical) {
+            let cycle: Vec<String> = stack[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|file| file.display().to_string())
+                .collect();
+            return Err(BentoError::InvalidInput(format!(
+                "Circular include: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        if visited.insert(canonical.clone()) {
+            let content = fs::read_to_string(&found)?;
+            stack.push(canonical);
+            let inlined = inline_imports_in(
+                &content,
+                found.parent(),
+                include_dirs,
+                visited,
+                stack,
+            )?;
+            stack.pop();
+            resolved.push_str("#line 1\n");
+            resolved.push_str(&inlined);
+        }
+        resolved.push_str(&format!("#line {}\n", index + 2));
+    }
+
+    Ok(resolved)
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// File an `import name;` or `import "file";` line refers to. `import a.b;` names `a/b.slang`.
+fn import_target(line: &str) -> Option<PathBuf> {
+    let module = line.trim().strip_prefix("import ")?.strip_suffix(';')?.trim();
+    if let Some(file) = module.strip_prefix('"').and_then(|m| m.strip_suffix('"')) {
+        return Some(PathBuf::from(file));
+    }
+    Some(PathBuf::from(format!("{}.slang", module.replace('.', "/"))))
+}
+
 fn shader_stage(stage: dashi::ShaderType) -> Result<ShaderKind, BentoError> {
     match stage {
         dashi::ShaderType::Vertex => Ok(ShaderKind::Vertex),
         dashi::ShaderType::Fragment => Ok(ShaderKind::Fragment),
         dashi::ShaderType::Compute => Ok(ShaderKind::Compute),
+        dashi::ShaderType::Geometry => Ok(ShaderKind::Geometry),
+        dashi::ShaderType::TessellationControl => Ok(ShaderKind::TessControl),
+        dashi::ShaderType::TessellationEvaluation => Ok(ShaderKind::TessEvaluation),
         dashi::ShaderType::All => Err(BentoError::InvalidInput(
             "ShaderType::All is not supported for compilation".into(),
         )),
-        _ => todo!(),
+        other => Err(BentoError::InvalidInput(format!(
+            "ShaderType::{other:?} is not supported for compilation"
+        ))),
+    }
+}
+
+/// FNV-1a hash of a shader source and everything about the request that affects its compiled
+/// output.
+fn cache_key(source: &[u8], lang: ShaderLang, request: &Request) -> Result<u64, BentoError> {
+    // Going through `Value` sorts object keys, so `defines` hash the same in any order.
+    let options = serde_json::to_vec(&(lang, serde_json::to_value(request)?))?;
+
+    let mut hasher = FnvHasher::default();
+    hasher.write(source);
+    hasher.write(&options);
+    Ok(hasher.finish())
+}
+
+/// Rejects compiling a file whose extension names a stage (`.vert`, `.frag`, `.comp`, `.geom`,
+/// `.tesc`, `.tese`) as another stage, unless the request opts out. Other extensions, like
+/// `.glsl`, don't imply a stage.
+fn check_extension_stage(path: &Path, request: &Request) -> Result<(), BentoError> {
+    let implied = match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("vert") => dashi::ShaderType::Vertex,
+        Some("frag") => dashi::ShaderType::Fragment,
+        Some("comp") => dashi::ShaderType::Compute,
+        Some("geom") => dashi::ShaderType::Geometry,
+        Some("tesc") => dashi::ShaderType::TessellationControl,
+        Some("tese") => dashi::ShaderType::TessellationEvaluation,
+        _ => return Ok(()),
+    };
+
+    if request.ignore_extension_stage || implied == request.stage {
+        return Ok(());
     }
+
+    Err(BentoError::InvalidInput(format!(
+        "{} is a {implied:?} shader by extension but was requested as {:?}",
+        path.display(),
+        request.stage
+    )))
 }
 
 fn infer_shader_lang(source: &str, filename: Option<&str>) -> ShaderLang {
@@ -513,6 +2146,26 @@ fn infer_shader_lang(source: &str, filename: Option<&str>) -> ShaderLang {
     detected.unwrap_or(ShaderLang::Glsl)
 }
 
+// Slang sources need the native Slang library linked. They are still compiled through shaderc's
+// HLSL front end, which can't follow module imports; such sources would be misparsed, so they are
+// rejected up front unless `Request::include_dirs` already inlined the modules.
+fn check_slang_source(source: &str) -> Result<(), BentoError> {
+    if !slang_rs::is_available() {
+        return Err(BentoError::BackendUnavailable { backend: "slang" });
+    }
+    if uses_slang_modules(source) {
+        return Err(BentoError::UnsupportedSlangImports);
+    }
+    Ok(())
+}
+
+fn uses_slang_modules(source: &str) -> bool {
+    source.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("import ") && line.trim_end().ends_with(';')
+    })
+}
+
 fn source_language(lang: ShaderLang) -> Result<SourceLanguage, BentoError> {
     match lang {
         ShaderLang::Glsl => Ok(SourceLanguage::GLSL),
@@ -524,6 +2177,50 @@ fn source_language(lang: ShaderLang) -> Result<SourceLanguage, BentoError> {
     }
 }
 
+fn shaderc_profile(profile: GlslProfile) -> ShadercProfile {
+    match profile {
+        GlslProfile::None => ShadercProfile::None,
+        GlslProfile::Core => ShadercProfile::Core,
+        GlslProfile::Compatibility => ShadercProfile::Compatibility,
+        GlslProfile::Es => ShadercProfile::Es,
+    }
+}
+
+fn shaderc_env_version(version: TargetEnvVersion) -> EnvVersion {
+    match version {
+        TargetEnvVersion::Vulkan1_0 => EnvVersion::Vulkan1_0,
+        TargetEnvVersion::Vulkan1_1 => EnvVersion::Vulkan1_1,
+        TargetEnvVersion::Vulkan1_2 => EnvVersion::Vulkan1_2,
+        TargetEnvVersion::Vulkan1_3 => EnvVersion::Vulkan1_3,
+    }
+}
+
+fn shaderc_spirv_version(version: SpirvTargetVersion) -> SpirvVersion {
+    match version {
+        SpirvTargetVersion::V1_0 => SpirvVersion::V1_0,
+        SpirvTargetVersion::V1_1 => SpirvVersion::V1_1,
+        SpirvTargetVersion::V1_2 => SpirvVersion::V1_2,
+        SpirvTargetVersion::V1_3 => SpirvVersion::V1_3,
+        SpirvTargetVersion::V1_4 => SpirvVersion::V1_4,
+        SpirvTargetVersion::V1_5 => SpirvVersion::V1_5,
+        SpirvTargetVersion::V1_6 => SpirvVersion::V1_6,
+    }
+}
+
+#[cfg(feature = "spirv-validation")]
+fn validate_spirv(spirv: &[u32], target_env: TargetEnvVersion) -> Result<(), BentoError> {
+    let env = match target_env {
+        TargetEnvVersion::Vulkan1_0 => spirv_tools::TargetEnv::Vulkan_1_0,
+        TargetEnvVersion::Vulkan1_1 => spirv_tools::TargetEnv::Vulkan_1_1,
+        TargetEnvVersion::Vulkan1_2 => spirv_tools::TargetEnv::Vulkan_1_2,
+        TargetEnvVersion::Vulkan1_3 => spirv_tools::TargetEnv::Vulkan_1_3,
+    };
+
+    spirv_tools::val::create(Some(env))
+        .validate(spirv, None)
+        .map_err(|err| BentoError::ShaderCompilation(format!("Invalid SPIR-V: {err}")))
+}
+
 fn shaderc_optimization(level: OptimizationLevel) -> ShadercOpt {
     match level {
         OptimizationLevel::None => ShadercOpt::Zero,
@@ -538,12 +2235,126 @@ struct SourceBinding {
     binding: Option<u32>,
     name: String,
     order: usize,
+    var_type: Option<dashi::BindTableVariableType>,
+}
+
+fn restore_stripped_bindings(
+    variables: &mut Vec<ShaderVariable>,
+    source: &str,
+    lang: ShaderLang,
+    shifts: &HashMap<RegisterClass, u32>,
+) -> Result<(), BentoError> {
+    for declared in parse_source_bindings(source, lang, shifts)? {
+        let (Some(binding), Some(var_type)) = (declared.binding, declared.var_type) else {
+            continue;
+        };
+
+        let present = variables.iter().any(|var| {
+            (var.set == declared.set && var.kind.binding == binding) || var.name == declared.name
+        });
+        if present {
+            continue;
+        }
+
+        variables.push(ShaderVariable {
+            name: declared.name,
+            set: declared.set,
+            kind: dashi::BindTableVariable {
+                var_type,
+                binding,
+                count: 1,
+            },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
+        });
+    }
+
+    variables.sort_by(|a, b| {
+        a.set
+            .cmp(&b.set)
+            .then_with(|| a.kind.binding.cmp(&b.kind.binding))
+    });
+
+    Ok(())
+}
+
+fn glsl_binding_type(declaration: &str) -> Option<dashi::BindTableVariableType> {
+    let words: Vec<&str> = declaration
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.contains(&"buffer") {
+        return Some(dashi::BindTableVariableType::Storage);
+    }
+
+    if !words.contains(&"uniform") {
+        return None;
+    }
+
+    for word in &words {
+        if *word == "sampler" || *word == "samplerShadow" {
+            return Some(dashi::BindTableVariableType::Sampler);
+        }
+        if word.starts_with("sampler") {
+            return Some(dashi::BindTableVariableType::SampledImage);
+        }
+        if word.starts_with("texture") || word.starts_with("image") {
+            return Some(dashi::BindTableVariableType::Image);
+        }
+    }
+
+    Some(dashi::BindTableVariableType::Uniform)
+}
+
+fn hlsl_binding_type(declaration: &str) -> Option<dashi::BindTableVariableType> {
+    let declaration = declaration.trim_start();
+    let declaration = declaration
+        .strip_prefix("uniform")
+        .map(str::trim_start)
+        .unwrap_or(declaration);
+
+    if declaration.starts_with("cbuffer") || declaration.starts_with("ConstantBuffer") {
+        Some(dashi::BindTableVariableType::Uniform)
+    } else if declaration.starts_with("RWStructuredBuffer")
+        || declaration.starts_with("StructuredBuffer")
+        || declaration.starts_with("RWByteAddressBuffer")
+        || declaration.starts_with("ByteAddressBuffer")
+    {
+        Some(dashi::BindTableVariableType::Storage)
+    } else if declaration.starts_with("RWTexture") || declaration.starts_with("Texture") {
+        Some(dashi::BindTableVariableType::Image)
+    } else if declaration.starts_with("Sampler") {
+        Some(dashi::BindTableVariableType::Sampler)
+    } else {
+        None
+    }
 }
 
 fn spirv_words_to_bytes(words: &[u32]) -> &[u8] {
     unsafe { std::slice::from_raw_parts(words.as_ptr() as *const u8, words.len() * 4) }
 }
 
+/// Names of the `OpEntryPoint`s `spirv` declares, or none if it does not parse.
+fn spirv_entry_points(spirv: &[u32]) -> Vec<String> {
+    use rspirv_reflect::Reflection;
+
+    let Ok(reflection) = Reflection::new_from_spirv(spirv_words_to_bytes(spirv)) else {
+        return Vec::new();
+    };
+
+    reflection
+        .0
+        .entry_points
+        .iter()
+        .filter_map(|instruction| match instruction.operands.get(2) {
+            Some(Operand::LiteralString(name)) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
 fn strip_debug_instructions(spirv: &[u32]) -> Vec<u32> {
     use rspirv::spirv::Op;
 
@@ -587,6 +2398,111 @@ fn strip_debug_instructions(spirv: &[u32]) -> Vec<u32> {
     stripped
 }
 
+/// Descriptor arrays whose length is a specialization constant, keyed by the `(set, binding)`
+/// decorated in the module.
+///
+/// Also returns a copy of the module with those lengths frozen to their defaults, since
+/// reflection only reads array counts from plain constants.
+fn spec_sized_bindings(
+    spirv: &[u32],
+) -> Result<(Vec<u32>, HashMap<(u32, u32), SpecConstantCount>), BentoError> {
+    use rspirv_reflect::Reflection;
+    use spirv::{Decoration, Op};
+
+    let reflection = Reflection::new_from_spirv(spirv_words_to_bytes(spirv))
+        .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
+    let module = &reflection.0;
+
+    let mut sets = HashMap::new();
+    let mut bindings = HashMap::new();
+    let mut spec_ids = HashMap::new();
+    for annotation in &module.annotations {
+        if annotation.class.opcode != Op::Decorate {
+            continue;
+        }
+        let (
+            Some(Operand::IdRef(id)),
+            Some(Operand::Decoration(decoration)),
+            Some(Operand::LiteralBit32(value)),
+        ) = (
+            annotation.operands.first(),
+            annotation.operands.get(1),
+            annotation.operands.get(2),
+        )
+        else {
+            continue;
+        };
+        match decoration {
+            Decoration::DescriptorSet => sets.insert(*id, *value),
+            Decoration::Binding => bindings.insert(*id, *value),
+            Decoration::SpecId => spec_ids.insert(*id, *value),
+            _ => None,
+        };
+    }
+
+    let definition = |id: u32| {
+        module
+            .types_global_values
+            .iter()
+            .find(|instruction| instruction.result_id == Some(id))
+    };
+    let spec_length = |array_type: u32| -> Option<(u32, SpecConstantCount)> {
+        let array = definition(array_type).filter(|ty| ty.class.opcode == Op::TypeArray)?;
+        let Some(Operand::IdRef(length)) = array.operands.get(1) else {
+            return None;
+        };
+        let constant = definition(*length).filter(|c| c.class.opcode == Op::SpecConstant)?;
+        let Some(Operand::LiteralBit32(default)) = constant.operands.first() else {
+            return None;
+        };
+        let constant_id = *spec_ids.get(length)?;
+        Some((*length, SpecConstantCount { constant_id, default: *default }))
+    };
+
+    let mut counts = HashMap::new();
+    let mut frozen = HashSet::new();
+    for variable in &module.types_global_values {
+        if variable.class.opcode != Op::Variable {
+            continue;
+        }
+        let Some(id) = variable.result_id else {
+            continue;
+        };
+        let Some(binding) = bindings.get(&id) else {
+            continue;
+        };
+        let pointee = variable
+            .result_type
+            .and_then(definition)
+            .and_then(|pointer| match pointer.operands.get(1) {
+                Some(Operand::IdRef(pointee)) => Some(*pointee),
+                _ => None,
+            });
+        let Some((length, count)) = pointee.and_then(spec_length) else {
+            continue;
+        };
+        counts.insert((sets.get(&id).copied().unwrap_or(0), *binding), count);
+        frozen.insert(length);
+    }
+
+    let mut patched = spirv.to_vec();
+    let mut index = 5;
+    while !frozen.is_empty() && index < patched.len() {
+        let word_count = (patched[index] >> 16) as usize;
+        if word_count == 0 || index + word_count > patched.len() {
+            break;
+        }
+        if patched[index] & 0xFFFF == Op::SpecConstant as u32
+            && frozen.contains(&patched[index + 2])
+        {
+            patched[index] = (patched[index] & 0xFFFF_0000) | Op::Constant as u32;
+        }
+        index += word_count;
+    }
+
+    Ok((patched, counts))
+}
+
 struct ReflectedBindings {
     variables: Vec<ShaderVariable>,
     remap: HashMap<(u32, u32), (u32, u32)>,
@@ -596,13 +2512,15 @@ fn reflect_bindings(
     spirv_bytes: &[u8],
     source: &str,
     lang: ShaderLang,
+    shifts: &HashMap<RegisterClass, u32>,
+    spec_counts: &HashMap<(u32, u32), SpecConstantCount>,
 ) -> Result<ReflectedBindings, BentoError> {
     use rspirv_reflect::{BindingCount, DescriptorType, Reflection};
 
     let reflection = Reflection::new_from_spirv(spirv_bytes)
         .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
 
-    let mut source_bindings = parse_source_bindings(source, lang)?;
+    let mut source_bindings = parse_source_bindings(source, lang, shifts)?;
     let descriptor_sets = reflection
         .get_descriptor_sets()
         .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
@@ -646,41 +2564,109 @@ fn reflect_bindings(
                 }
                 _ => dashi::BindTableVariableType::Uniform,
             };
+            let texel_buffer = match info.ty {
+                DescriptorType::UNIFORM_TEXEL_BUFFER => Some(TexelBufferKind::Uniform),
+                DescriptorType::STORAGE_TEXEL_BUFFER => Some(TexelBufferKind::Storage),
+                _ => None,
+            };
+
+            let count = match info.binding_count {
+                BindingCount::One => 1,
+                BindingCount::StaticSized(value) => value as u32,
+                BindingCount::Unbounded => 0,
+            };
+
+            variables.push(ShaderVariable {
+                name,
+                set: resolved_set,
+                kind: dashi::BindTableVariable {
+                    var_type,
+                    binding: resolved_binding,
+                    count,
+                },
+                spec_count: spec_counts.get(&(*set, *binding)).copied(),
+                unbounded: matches!(info.binding_count, BindingCount::Unbounded),
+                texel_buffer,
+            });
+        }
+    }
+
+    variables.sort_by(|a, b| {
+        a.set
+            .cmp(&b.set)
+            .then_with(|| a.kind.binding.cmp(&b.kind.binding))
+    });
+
+    Ok(ReflectedBindings { variables, remap })
+}
+
+/// `(set, binding)` pairs whose variables are referenced from a function body.
+fn referenced_bindings(spirv: &[u32]) -> Result<HashSet<(u32, u32)>, BentoError> {
+    use rspirv_reflect::Reflection;
+
+    let reflection = Reflection::new_from_spirv(spirv_words_to_bytes(spirv))
+        .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
+    let module = reflection.0;
+
+    let mut bindings_by_id: HashMap<u32, (Option<u32>, Option<u32>)> = HashMap::new();
+    for annotation in &module.annotations {
+        if annotation.class.opcode != spirv::Op::Decorate {
+            continue;
+        }
+
+        let (
+            Some(Operand::IdRef(id)),
+            Some(Operand::Decoration(decoration)),
+            Some(Operand::LiteralBit32(value)),
+        ) = (
+            annotation.operands.first(),
+            annotation.operands.get(1),
+            annotation.operands.get(2),
+        )
+        else {
+            continue;
+        };
+
+        let entry = bindings_by_id.entry(*id).or_default();
+        match decoration {
+            spirv::Decoration::DescriptorSet => entry.0 = Some(*value),
+            spirv::Decoration::Binding => entry.1 = Some(*value),
+            _ => {}
+        }
+    }
 
-            let count = match info.binding_count {
-                BindingCount::One => 1,
-                BindingCount::StaticSized(value) => value as u32,
-                BindingCount::Unbounded => 0,
+    let mut referenced = HashSet::new();
+    let instructions = module
+        .functions
+        .iter()
+        .flat_map(|function| function.blocks.iter())
+        .flat_map(|block| block.instructions.iter());
+    for instruction in instructions {
+        for operand in &instruction.operands {
+            let Operand::IdRef(id) = operand else {
+                continue;
             };
-
-            variables.push(ShaderVariable {
-                name,
-                set: resolved_set,
-                kind: dashi::BindTableVariable {
-                    var_type,
-                    binding: resolved_binding,
-                    count,
-                },
-            });
+            if let Some((set, Some(binding))) = bindings_by_id.get(id) {
+                referenced.insert((set.unwrap_or(0), *binding));
+            }
         }
     }
 
-    variables.sort_by(|a, b| {
-        a.set
-            .cmp(&b.set)
-            .then_with(|| a.kind.binding.cmp(&b.kind.binding))
-    });
-
-    Ok(ReflectedBindings { variables, remap })
+    Ok(referenced)
 }
 
 fn rewrite_spirv_binding_names(
     spirv: &[u32],
     variables: &[ShaderVariable],
     remap: &HashMap<(u32, u32), (u32, u32)>,
+    rename: bool,
 ) -> Result<Vec<u32>, BentoError> {
     use rspirv_reflect::Reflection;
 
+    if !rename && remap.is_empty() {
+        return Ok(spirv.to_vec());
+    }
+
     let reflection = Reflection::new_from_spirv(spirv_words_to_bytes(spirv))
         .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
     let mut module = reflection.0;
@@ -755,6 +2741,10 @@ fn rewrite_spirv_binding_names(
         }
     }
 
+    if !rename {
+        return Ok(module.assemble());
+    }
+
     let mut remap_inverse = HashMap::new();
     for (old, new) in remap {
         remap_inverse.insert(*new, *old);
@@ -810,10 +2800,15 @@ fn rewrite_spirv_binding_names(
     Ok(module.assemble())
 }
 
-fn parse_source_bindings(source: &str, lang: ShaderLang) -> Result<Vec<SourceBinding>, BentoError> {
+fn parse_source_bindings(
+    source: &str,
+    lang: ShaderLang,
+    shifts: &HashMap<RegisterClass, u32>,
+) -> Result<Vec<SourceBinding>, BentoError> {
     match lang {
         ShaderLang::Glsl => parse_glsl_bindings(source),
-        ShaderLang::Hlsl | ShaderLang::Slang => parse_hlsl_like_bindings(source),
+        ShaderLang::Hlsl => parse_hlsl_like_bindings(source, shifts),
+        ShaderLang::Slang => parse_hlsl_like_bindings(source, &HashMap::new()),
         ShaderLang::Other => Err(BentoError::InvalidInput(
             "Unsupported shader language for reflection".into(),
         )),
@@ -894,6 +2889,7 @@ fn parse_glsl_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoError> {
             binding: Some(binding),
             name,
             order: bindings.len(),
+            var_type: glsl_binding_type(&declaration),
         });
     }
 
@@ -936,7 +2932,10 @@ fn glsl_declaration_from(source: &str, start: usize) -> Option<String> {
     None
 }
 
-fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoError> {
+fn parse_hlsl_like_bindings(
+    source: &str,
+    shifts: &HashMap<RegisterClass, u32>,
+) -> Result<Vec<SourceBinding>, BentoError> {
     let vk_binding_regex = Regex::new(
         r"(?m)^\s*\[\[\s*vk::binding\s*\(\s*(\d+)\s*(?:,\s*(\d+)\s*)?\)\s*\]\]\s*([^;\n]+);",
     )
@@ -960,6 +2959,7 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
         set: u32,
         order: usize,
         register_index: Option<u32>,
+        var_type: Option<dashi::BindTableVariableType>,
     }
 
     let mut explicit_bindings = Vec::new();
@@ -987,6 +2987,7 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
             binding: Some(binding),
             name,
             order: index,
+            var_type: hlsl_binding_type(declaration),
         });
     }
 
@@ -1009,7 +3010,8 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
         let register_index = register_capture
             .as_ref()
             .and_then(|capture| capture.get(2))
-            .and_then(|m| m.as_str().parse::<u32>().ok());
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+            .map(|index| index + register_shift(register_capture.as_ref(), shifts));
         let set = register_capture
             .as_ref()
             .and_then(|capture| capture.get(3))
@@ -1021,6 +3023,7 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
             set,
             order: index,
             register_index,
+            var_type: hlsl_binding_type(declaration_match.as_str()),
         });
     }
 
@@ -1039,7 +3042,8 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
         let register_index = register_capture
             .as_ref()
             .and_then(|capture| capture.get(2))
-            .and_then(|m| m.as_str().parse::<u32>().ok());
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+            .map(|index| index + register_shift(register_capture.as_ref(), shifts));
         let set = register_capture
             .as_ref()
             .and_then(|capture| capture.get(3))
@@ -1051,6 +3055,7 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
             set,
             order: starting_index + offset,
             register_index,
+            var_type: hlsl_binding_type(declaration),
         });
     }
 
@@ -1061,6 +3066,7 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
             binding: parsed.register_index,
             name: parsed.name,
             order: parsed.order,
+            var_type: parsed.var_type,
         });
     }
 
@@ -1072,6 +3078,19 @@ fn parse_hlsl_like_bindings(source: &str) -> Result<Vec<SourceBinding>, BentoErr
     Ok(explicit_bindings)
 }
 
+/// Shift configured for the class of a captured `register(...)` annotation.
+fn register_shift(
+    capture: Option<&regex::Captures<'_>>,
+    shifts: &HashMap<RegisterClass, u32>,
+) -> u32 {
+    capture
+        .and_then(|capture| capture.get(1))
+        .and_then(|m| RegisterClass::from_prefix(m.as_str()))
+        .and_then(|class| shifts.get(&class))
+        .copied()
+        .unwrap_or(0)
+}
+
 fn take_source_binding(
     set: u32,
     binding: u32,
@@ -1196,67 +3215,12 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
 
     let mut inputs = Vec::new();
     let mut outputs = Vec::new();
-    let mut scalar_types = HashMap::new();
-    let mut vector_types = HashMap::new();
-    let mut pointer_types = HashMap::new();
-
-    for instruction in &module.types_global_values {
-        match instruction.class.opcode {
-            spirv::Op::TypeFloat => {
-                if let (Some(id), Some(rspirv_reflect::rspirv::dr::Operand::LiteralBit32(width))) =
-                    (instruction.result_id, instruction.operands.get(0))
-                {
-                    scalar_types.insert(id, ScalarType::Float(*width));
-                }
-            }
-            spirv::Op::TypeInt => {
-                if let (
-                    Some(id),
-                    Some(rspirv_reflect::rspirv::dr::Operand::LiteralBit32(width)),
-                    Some(rspirv_reflect::rspirv::dr::Operand::LiteralBit32(signedness)),
-                ) = (
-                    instruction.result_id,
-                    instruction.operands.get(0),
-                    instruction.operands.get(1),
-                ) {
-                    scalar_types.insert(
-                        id,
-                        ScalarType::Int {
-                            width: *width,
-                            signed: *signedness == 1,
-                        },
-                    );
-                }
-            }
-            spirv::Op::TypeVector => {
-                if let (
-                    Some(id),
-                    Some(rspirv_reflect::rspirv::dr::Operand::IdRef(component_type)),
-                    Some(rspirv_reflect::rspirv::dr::Operand::LiteralBit32(component_count)),
-                ) = (
-                    instruction.result_id,
-                    instruction.operands.get(0),
-                    instruction.operands.get(1),
-                ) {
-                    vector_types.insert(
-                        id,
-                        VectorType {
-                            component_type: *component_type,
-                            component_count: *component_count,
-                        },
-                    );
-                }
-            }
-            spirv::Op::TypePointer => {
-                if let (Some(id), Some(rspirv_reflect::rspirv::dr::Operand::IdRef(pointee_type))) =
-                    (instruction.result_id, instruction.operands.get(1))
-                {
-                    pointer_types.insert(id, *pointee_type);
-                }
-            }
-            _ => {}
-        }
-    }
+    let TypeTables {
+        scalars: scalar_types,
+        vectors: vector_types,
+        matrices: matrix_types,
+        pointers: pointer_types,
+    } = TypeTables::collect(module);
 
     for instruction in &module.types_global_values {
         if instruction.class.opcode != spirv::Op::Variable {
@@ -1277,14 +3241,17 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
             .cloned()
             .unwrap_or_else(|| format!("var_{id}"));
         let location = locations.get(&id).copied();
-        let format = instruction
+        let resolved = instruction
             .result_type
             .and_then(|ty| pointer_types.get(&ty).copied().or(Some(ty)))
-            .and_then(|ty| resolve_primitive(ty, &scalar_types, &vector_types));
+            .and_then(|ty| resolve_primitive(ty, &scalar_types, &vector_types, &matrix_types));
+        let primitive = resolved.map(|(primitive, _)| primitive);
         let variable = InterfaceVariable {
             name,
             location,
-            format,
+            format: primitive.and_then(|primitive| primitive.to_dashi()),
+            primitive,
+            columns: resolved.and_then(|(_, columns)| columns),
         };
 
         match storage_class {
@@ -1310,7 +3277,8 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
     });
 
     let mut has_vertex_entry_point = false;
-    let entry_points = module
+    let mut has_fragment_entry_point = false;
+    let mut entry_points: Vec<String> = module
         .entry_points
         .iter()
         .filter_map(|instruction| {
@@ -1324,6 +3292,9 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
                 if *model == spirv::ExecutionModel::Vertex {
                     has_vertex_entry_point = true;
                 }
+                if *model == spirv::ExecutionModel::Fragment {
+                    has_fragment_entry_point = true;
+                }
             }
 
             match instruction.operands.get(2) {
@@ -1334,27 +3305,37 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
             }
         })
         .collect();
-
-    let workgroup_size = reflection
-        .get_compute_group_size()
-        .map(|(x, y, z)| [x, y, z]);
+    entry_points.sort();
+
+    let specialized_workgroup_size = spec_workgroup_size(module);
+    let workgroup_size_specializable = specialized_workgroup_size.is_some();
+    let workgroup_size = specialized_workgroup_size.or_else(|| {
+        reflection
+            .get_compute_group_size()
+            .map(|(x, y, z)| [x, y, z])
+    });
 
     let vertex = if has_vertex_entry_point {
-        let mut attributes: Vec<(u32, dashi::ShaderPrimitiveType)> = inputs
+        let mut attributes: Vec<(u32, ShaderPrimitive)> = inputs
             .iter()
-            .filter_map(|var| var.location.zip(var.format))
+            .flat_map(|var| {
+                let primitive = var.primitive;
+                var.locations()
+                    .filter_map(move |location| primitive.map(|primitive| (location, primitive)))
+            })
             .collect();
         attributes.sort_by_key(|(location, _)| *location);
 
         let mut offset = 0usize;
         let mut entries = Vec::new();
-        for (location, format) in attributes {
+        for (location, primitive) in attributes {
             entries.push(VertexEntry {
-                format,
+                format: primitive.to_dashi(),
+                primitive,
                 location: location as usize,
                 offset,
             });
-            offset += primitive_size(format);
+            offset += primitive.size();
         }
 
         if entries.is_empty() {
@@ -1370,15 +3351,431 @@ fn reflect_metadata(spirv_bytes: &[u8]) -> Result<ShaderMetadata, BentoError> {
         None
     };
 
+    // Matched by opcode number so the check doesn't depend on which SPIR-V header revision
+    // names the demote instruction.
+    const OP_TERMINATE_INVOCATION: u32 = 4416;
+    const OP_DEMOTE_TO_HELPER_INVOCATION: u32 = 5380;
+    const OP_ATOMIC_FMIN_EXT: u32 = 5614;
+    const OP_ATOMIC_FMAX_EXT: u32 = 5615;
+    const OP_ATOMIC_FADD_EXT: u32 = 6035;
+    let push_constants = module
+        .types_global_values
+        .iter()
+        .find(|instruction| {
+            instruction.class.opcode == spirv::Op::Variable
+                && matches!(
+                    instruction.operands.first(),
+                    Some(rspirv_reflect::rspirv::dr::Operand::StorageClass(
+                        spirv::StorageClass::PushConstant
+                    ))
+                )
+        })
+        .and_then(|variable| variable.result_type)
+        .and_then(|pointer| pointer_types.get(&pointer).copied())
+        .and_then(|block| {
+            reflect_block_layout(module, block, &scalar_types, &vector_types, &matrix_types)
+        });
+
+    let may_discard = has_fragment_entry_point
+        && module
+            .functions
+            .iter()
+            .flat_map(|function| function.blocks.iter())
+            .flat_map(|block| block.instructions.iter())
+            .any(|instruction| {
+                let opcode = instruction.class.opcode;
+                opcode == spirv::Op::Kill
+                    || matches!(
+                        opcode as u32,
+                        OP_TERMINATE_INVOCATION | OP_DEMOTE_TO_HELPER_INVOCATION
+                    )
+            });
+
+    // `OpAtomicLoad` through `OpAtomicXor` are numbered contiguously.
+    let atomic_ops = spirv::Op::AtomicLoad as u32..=spirv::Op::AtomicXor as u32;
+    let uses_atomics = module
+        .functions
+        .iter()
+        .flat_map(|function| function.blocks.iter())
+        .flat_map(|block| block.instructions.iter())
+        .any(|instruction| {
+            let opcode = instruction.class.opcode as u32;
+            atomic_ops.contains(&opcode)
+                || instruction.class.opcode == spirv::Op::AtomicFlagTestAndSet
+                || instruction.class.opcode == spirv::Op::AtomicFlagClear
+                || matches!(
+                    opcode,
+                    OP_ATOMIC_FMIN_EXT | OP_ATOMIC_FMAX_EXT | OP_ATOMIC_FADD_EXT
+                )
+        });
+
     Ok(ShaderMetadata {
         entry_points,
         inputs,
         outputs,
         workgroup_size,
+        workgroup_size_specializable,
         vertex,
+        may_discard,
+        uses_atomics,
+        push_constants,
+    })
+}
+
+/// Members of the struct `block`, with the block size covering the last member. Offsets come
+/// from the module's decorations, so the struct must be used by a block.
+/// Returns `None` for a block without members.
+fn reflect_block_layout(
+    module: &rspirv_reflect::rspirv::dr::Module,
+    block: u32,
+    scalars: &HashMap<u32, ScalarType>,
+    vectors: &HashMap<u32, VectorType>,
+    matrices: &HashMap<u32, MatrixType>,
+) -> Option<BlockLayout> {
+    use rspirv_reflect::rspirv::dr::Operand;
+    use rspirv_reflect::spirv::{Decoration, Op};
+
+    let definition = |id: u32| {
+        module
+            .types_global_values
+            .iter()
+            .find(|instruction| instruction.result_id == Some(id))
+    };
+    let member_types: Vec<u32> = definition(block)
+        .filter(|ty| ty.class.opcode == Op::TypeStruct)?
+        .operands
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::IdRef(id) => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    let mut offsets = HashMap::new();
+    let mut matrix_strides = HashMap::new();
+    let mut array_strides = HashMap::new();
+    for annotation in &module.annotations {
+        match (annotation.class.opcode, annotation.operands.as_slice()) {
+            (
+                Op::MemberDecorate,
+                [
+                    Operand::IdRef(id),
+                    Operand::LiteralBit32(member),
+                    Operand::Decoration(decoration),
+                    Operand::LiteralBit32(value),
+                ],
+            ) if *id == block => match decoration {
+                Decoration::Offset => {
+                    offsets.insert(*member, *value);
+                }
+                Decoration::MatrixStride => {
+                    matrix_strides.insert(*member, *value);
+                }
+                _ => {}
+            },
+            (
+                Op::Decorate,
+                [
+                    Operand::IdRef(id),
+                    Operand::Decoration(Decoration::ArrayStride),
+                    Operand::LiteralBit32(stride),
+                ],
+            ) => {
+                array_strides.insert(*id, *stride);
+            }
+            _ => {}
+        }
+    }
+
+    let mut name = String::new();
+    let mut names = HashMap::new();
+    for instruction in &module.debug_names {
+        if let (Op::Name, [Operand::IdRef(id), Operand::LiteralString(block_name)]) =
+            (instruction.class.opcode, instruction.operands.as_slice())
+        {
+            if *id == block {
+                name = block_name.clone();
+            }
+        }
+        if let (
+            Op::MemberName,
+            [Operand::IdRef(id), Operand::LiteralBit32(member), Operand::LiteralString(name)],
+        ) = (instruction.class.opcode, instruction.operands.as_slice())
+        {
+            if *id == block {
+                names.insert(*member, name.clone());
+            }
+        }
+    }
+
+    let mut size = 0;
+    let mut members = Vec::new();
+    for (index, ty) in member_types.into_iter().enumerate() {
+        let index = index as u32;
+        let offset = offsets.get(&index).copied().unwrap_or_default();
+        let resolved = resolve_primitive(ty, scalars, vectors, matrices);
+        let primitive = resolved.map(|(primitive, _)| primitive);
+        let columns = resolved.and_then(|(_, columns)| columns);
+
+        let member_size = match (primitive, columns) {
+            (Some(primitive), Some(columns)) => {
+                let stride = matrix_strides.get(&index).copied();
+                stride.unwrap_or(primitive.size() as u32) * columns
+            }
+            (Some(primitive), None) => primitive.size() as u32,
+            // Arrays are sized by their stride; other aggregates only contribute their offset.
+            (None, _) => definition(ty)
+                .filter(|array| array.class.opcode == Op::TypeArray)
+                .and_then(|array| match array.operands.get(1) {
+                    Some(Operand::IdRef(length)) => definition(*length),
+                    _ => None,
+                })
+                .and_then(|length| match length.operands.first() {
+                    Some(Operand::LiteralBit32(length)) => Some(*length),
+                    _ => None,
+                })
+                .map(|length| length * array_strides.get(&ty).copied().unwrap_or_default())
+                .unwrap_or_default(),
+        };
+        size = size.max(offset + member_size);
+
+        members.push(BlockMember {
+            name: names
+                .remove(&index)
+                .unwrap_or_else(|| format!("member{index}")),
+            offset,
+            format: primitive.and_then(|primitive| primitive.to_dashi()),
+            primitive,
+            columns,
+        });
+    }
+
+    (!members.is_empty()).then_some(BlockLayout {
+        name,
+        size,
+        members,
     })
 }
 
+/// Default workgroup size of a compute shader whose local size is driven by specialization
+/// constants, or `None` when the size is a plain literal.
+fn spec_workgroup_size(module: &rspirv_reflect::rspirv::dr::Module) -> Option<[u32; 3]> {
+    use rspirv_reflect::rspirv::dr::Operand;
+    use rspirv_reflect::spirv::{BuiltIn, Decoration, ExecutionMode, Op};
+
+    let id_refs = |operands: &[Operand]| -> Vec<u32> {
+        operands
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::IdRef(id) => Some(*id),
+                _ => None,
+            })
+            .collect()
+    };
+    let constant = |id: u32| -> Option<(u32, bool)> {
+        let instruction = module
+            .types_global_values
+            .iter()
+            .find(|instruction| instruction.result_id == Some(id))?;
+        let specializable = match instruction.class.opcode {
+            Op::SpecConstant => true,
+            Op::Constant => false,
+            _ => return None,
+        };
+        match instruction.operands.first() {
+            Some(Operand::LiteralBit32(value)) => Some((*value, specializable)),
+            _ => None,
+        }
+    };
+    let resolve = |ids: &[u32]| -> Option<[u32; 3]> {
+        let dims = ids
+            .iter()
+            .map(|id| constant(*id))
+            .collect::<Option<Vec<_>>>()?;
+        match dims.as_slice() {
+            [x, y, z] if dims.iter().any(|(_, specializable)| *specializable) => {
+                Some([x.0, y.0, z.0])
+            }
+            _ => None,
+        }
+    };
+
+    let local_size_id = module.execution_modes.iter().find_map(|mode| {
+        match mode.operands.get(1) {
+            Some(Operand::ExecutionMode(ExecutionMode::LocalSizeId)) => {
+                resolve(&id_refs(&mode.operands[2..]))
+            }
+            _ => None,
+        }
+    });
+    if let Some(size) = local_size_id {
+        return Some(size);
+    }
+
+    // glslang lowers `local_size_*_id` to a spec-constant composite decorated as the
+    // `WorkgroupSize` builtin, which takes precedence over the `LocalSize` literal.
+    let composite = module.annotations.iter().find_map(|annotation| {
+        match (annotation.class.opcode, annotation.operands.as_slice()) {
+            (
+                Op::Decorate,
+                [
+                    Operand::IdRef(id),
+                    Operand::Decoration(Decoration::BuiltIn),
+                    Operand::BuiltIn(BuiltIn::WorkgroupSize),
+                    ..,
+                ],
+            ) => Some(*id),
+            _ => None,
+        }
+    })?;
+    let composite = module.types_global_values.iter().find(|instruction| {
+        instruction.result_id == Some(composite)
+            && instruction.class.opcode == Op::SpecConstantComposite
+    })?;
+    resolve(&id_refs(&composite.operands))
+}
+
+/// Layouts of the structs named `struct_names`, in that order. Fails if a struct isn't in the
+/// module or has no members.
+fn reflect_struct_layouts(
+    spirv_bytes: &[u8],
+    struct_names: &[String],
+) -> Result<Vec<BlockLayout>, BentoError> {
+    use rspirv_reflect::rspirv::dr::Operand;
+    use rspirv_reflect::{Reflection, spirv::Op};
+
+    let reflection = Reflection::new_from_spirv(spirv_bytes)
+        .map_err(|e| BentoError::ShaderCompilation(e.to_string()))?;
+    let module = &reflection.0;
+    let types = TypeTables::collect(module);
+
+    struct_names
+        .iter()
+        .map(|name| {
+            module
+                .debug_names
+                .iter()
+                .filter_map(|instruction| match instruction.operands.as_slice() {
+                    [Operand::IdRef(id), Operand::LiteralString(named)]
+                        if instruction.class.opcode == Op::Name && named == name =>
+                    {
+                        Some(*id)
+                    }
+                    _ => None,
+                })
+                .find_map(|id| {
+                    let TypeTables {
+                        scalars,
+                        vectors,
+                        matrices,
+                        ..
+                    } = &types;
+                    reflect_block_layout(module, id, scalars, vectors, matrices)
+                })
+                .ok_or_else(|| {
+                    BentoError::InvalidInput(format!("Struct '{name}' was not reflected"))
+                })
+        })
+        .collect()
+}
+
+/// Scalar, vector, matrix and pointer types a module declares, keyed by result id.
+#[derive(Default)]
+struct TypeTables {
+    scalars: HashMap<u32, ScalarType>,
+    vectors: HashMap<u32, VectorType>,
+    matrices: HashMap<u32, MatrixType>,
+    /// Pointer type id to pointee type id.
+    pointers: HashMap<u32, u32>,
+}
+
+impl TypeTables {
+    fn collect(module: &rspirv_reflect::rspirv::dr::Module) -> Self {
+        use rspirv_reflect::rspirv::dr::Operand;
+        use rspirv_reflect::spirv;
+
+        let mut tables = Self::default();
+        for instruction in &module.types_global_values {
+            match instruction.class.opcode {
+                spirv::Op::TypeFloat => {
+                    if let (Some(id), Some(Operand::LiteralBit32(width))) =
+                        (instruction.result_id, instruction.operands.get(0))
+                    {
+                        tables.scalars.insert(id, ScalarType::Float(*width));
+                    }
+                }
+                spirv::Op::TypeInt => {
+                    if let (
+                        Some(id),
+                        Some(Operand::LiteralBit32(width)),
+                        Some(Operand::LiteralBit32(signedness)),
+                    ) = (
+                        instruction.result_id,
+                        instruction.operands.get(0),
+                        instruction.operands.get(1),
+                    ) {
+                        tables.scalars.insert(
+                            id,
+                            ScalarType::Int {
+                                width: *width,
+                                signed: *signedness == 1,
+                            },
+                        );
+                    }
+                }
+                spirv::Op::TypeVector => {
+                    if let (
+                        Some(id),
+                        Some(Operand::IdRef(component_type)),
+                        Some(Operand::LiteralBit32(component_count)),
+                    ) = (
+                        instruction.result_id,
+                        instruction.operands.get(0),
+                        instruction.operands.get(1),
+                    ) {
+                        tables.vectors.insert(
+                            id,
+                            VectorType {
+                                component_type: *component_type,
+                                component_count: *component_count,
+                            },
+                        );
+                    }
+                }
+                spirv::Op::TypeMatrix => {
+                    if let (
+                        Some(id),
+                        Some(Operand::IdRef(column_type)),
+                        Some(Operand::LiteralBit32(column_count)),
+                    ) = (
+                        instruction.result_id,
+                        instruction.operands.get(0),
+                        instruction.operands.get(1),
+                    ) {
+                        tables.matrices.insert(
+                            id,
+                            MatrixType {
+                                column_type: *column_type,
+                                column_count: *column_count,
+                            },
+                        );
+                    }
+                }
+                spirv::Op::TypePointer => {
+                    if let (Some(id), Some(Operand::IdRef(pointee_type))) =
+                        (instruction.result_id, instruction.operands.get(1))
+                    {
+                        tables.pointers.insert(id, *pointee_type);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        tables
+    }
+}
+
 #[derive(Clone, Copy)]
 enum ScalarType {
     Float(u32),
@@ -1391,44 +3788,50 @@ struct VectorType {
     component_count: u32,
 }
 
+#[derive(Clone, Copy)]
+struct MatrixType {
+    column_type: u32,
+    column_count: u32,
+}
+
+/// Resolves a scalar, vector or matrix type. Matrices resolve to their column vector together
+/// with the column count.
 fn resolve_primitive(
     type_id: u32,
     scalars: &HashMap<u32, ScalarType>,
     vectors: &HashMap<u32, VectorType>,
-) -> Option<dashi::ShaderPrimitiveType> {
-    let vector = vectors.get(&type_id)?;
-    let scalar = scalars.get(&vector.component_type)?;
-
-    match (scalar, vector.component_count) {
-        (ScalarType::Float(32), 2) => Some(dashi::ShaderPrimitiveType::Vec2),
-        (ScalarType::Float(32), 3) => Some(dashi::ShaderPrimitiveType::Vec3),
-        (ScalarType::Float(32), 4) => Some(dashi::ShaderPrimitiveType::Vec4),
-        (
-            ScalarType::Int {
-                width: 32,
-                signed: true,
-            },
-            4,
-        ) => Some(dashi::ShaderPrimitiveType::IVec4),
-        (
-            ScalarType::Int {
-                width: 32,
-                signed: false,
-            },
-            4,
-        ) => Some(dashi::ShaderPrimitiveType::UVec4),
-        _ => None,
+    matrices: &HashMap<u32, MatrixType>,
+) -> Option<(ShaderPrimitive, Option<u32>)> {
+    if let Some(matrix) = matrices.get(&type_id) {
+        let (column, _) = resolve_primitive(matrix.column_type, scalars, vectors, matrices)?;
+        return Some((column, Some(matrix.column_count)));
     }
-}
 
-fn primitive_size(format: dashi::ShaderPrimitiveType) -> usize {
-    match format {
-        dashi::ShaderPrimitiveType::Vec2 => 8,
-        dashi::ShaderPrimitiveType::Vec3 => 12,
-        dashi::ShaderPrimitiveType::Vec4 => 16,
-        dashi::ShaderPrimitiveType::IVec4 => 16,
-        dashi::ShaderPrimitiveType::UVec4 => 16,
-    }
+    let (component_type, components) = match vectors.get(&type_id) {
+        Some(vector) => (vector.component_type, vector.component_count),
+        None => (type_id, 1),
+    };
+
+    let (kind, width) = match scalars.get(&component_type)? {
+        ScalarType::Float(width) => (PrimitiveKind::Float, *width),
+        ScalarType::Int {
+            width,
+            signed: true,
+        } => (PrimitiveKind::Int, *width),
+        ScalarType::Int {
+            width,
+            signed: false,
+        } => (PrimitiveKind::Uint, *width),
+    };
+
+    matches!(width, 16 | 32 | 64).then_some((
+        ShaderPrimitive {
+            kind,
+            width,
+            components,
+        },
+        None,
+    ))
 }
 
 #[cfg(test)]
@@ -1454,15 +3857,23 @@ mod tests {
                     binding: 0,
                     count: 1,
                 },
+                spec_count: None,
+                unbounded: false,
+                texel_buffer: None,
             }],
             metadata: ShaderMetadata {
                 entry_points: vec!["main".to_string()],
                 inputs: vec![],
                 outputs: vec![],
                 workgroup_size: Some([1, 1, 1]),
+                workgroup_size_specializable: false,
                 vertex: None,
+                may_discard: false,
+                uses_atomics: false,
+                push_constants: None,
             },
             spirv: vec![0x0723_0203, 1, 2, 3],
+            warnings: Vec::new(),
         }
     }
 
@@ -1477,6 +3888,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn rejects_unversioned_and_mismatched_binary_results() -> Result<(), BentoError> {
+        let original = sample_compilation_result();
+        let unversioned = bincode::serialize(&original)?;
+        assert!(matches!(
+            CompilationResult::from_bytes(&unversioned),
+            Err(BentoError::InvalidInput(message)) if message.contains("format version")
+        ));
+
+        let mut bytes = original.to_bytes()?;
+        bytes[4..8].copy_from_slice(&(RESULT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            CompilationResult::from_bytes(&bytes),
+            Err(BentoError::InvalidInput(message)) if message.contains("version")
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn saves_and_loads_from_disk() -> Result<(), BentoError> {
         let original = sample_compilation_result();
@@ -1502,6 +3932,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn round_trips_with_json_serialization() -> Result<(), BentoError> {
+        let compiler = Compiler::new()?;
+        let shader = include_str!("../tests/fixtures/simple_vertex.glsl");
+        let original = compiler.compile(shader.as_bytes(), &sample_vertex_request())?;
+        assert!(original.metadata.vertex.is_some());
+
+        let restored = CompilationResult::from_json(&original.to_json()?)?;
+        assert_eq!(original, restored);
+
+        let path = std::env::temp_dir()
+            .join("bento_tests")
+            .join(format!("compilation_result_{}.json", std::process::id()));
+        original.save_to_json_disk(path.to_str().unwrap())?;
+        let loaded = CompilationResult::load_from_json_disk(path.to_str().unwrap())?;
+        assert_eq!(original, loaded);
+
+        fs::remove_file(&path).ok();
+
+        Ok(())
+    }
+
     fn sample_request() -> Request {
         Request {
             name: Some("sample".to_string()),
@@ -1510,6 +3962,7 @@ mod tests {
             optimization: OptimizationLevel::None,
             debug_symbols: false,
             defines: HashMap::new(),
+            ..Default::default()
         }
     }
 
@@ -1521,6 +3974,7 @@ mod tests {
             optimization: OptimizationLevel::None,
             debug_symbols: false,
             defines: HashMap::new(),
+            ..Default::default()
         }
     }
 
@@ -1661,7 +4115,7 @@ cbuffer SceneCamera : register(b5)
 ConstantBuffer<SceneCamera> camera : register(b6);
 "#;
 
-        let bindings = parse_hlsl_like_bindings(source)?;
+        let bindings = parse_hlsl_like_bindings(source, &HashMap::new())?;
         let mut names_by_binding = HashMap::new();
         for binding in bindings {
             names_by_binding.insert(binding.binding.unwrap_or_default(), binding.name);
@@ -1673,6 +4127,69 @@ ConstantBuffer<SceneCamera> camera : register(b6);
         Ok(())
     }
 
+    #[test]
+    fn sorts_reflected_entry_points() -> Result<(), BentoError> {
+        let mut builder = rspirv::dr::Builder::new();
+        builder.set_version(1, 3);
+        builder.capability(spirv::Capability::Shader);
+        builder.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+
+        let void = builder.type_void();
+        let function_type = builder.type_function(void, vec![]);
+        for name in ["zeta", "alpha", "middle"] {
+            let function = builder
+                .begin_function(void, None, spirv::FunctionControl::NONE, function_type)
+                .expect("begin function");
+            builder.begin_block(None).expect("begin block");
+            builder.ret().expect("return");
+            builder.end_function().expect("end function");
+            builder.entry_point(spirv::ExecutionModel::GLCompute, function, name, vec![]);
+            builder.execution_mode(function, spirv::ExecutionMode::LocalSize, vec![1, 1, 1]);
+        }
+
+        let spirv = builder.module().assemble();
+        let metadata = reflect_metadata(spirv_words_to_bytes(&spirv))?;
+
+        assert_eq!(
+            metadata.entry_points,
+            vec!["alpha".to_string(), "middle".to_string(), "zeta".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reflects_half_precision_vertex_inputs() -> Result<(), BentoError> {
+        let compiler = Compiler::new()?;
+        let request = sample_vertex_request();
+        let path = "tests/fixtures/half_vertex.glsl";
+
+        let result = compiler.compile_from_file(path, &request)?;
+
+        let vertex = result
+            .metadata
+            .vertex
+            .expect("expected vertex layout metadata for vertex shader");
+        assert_eq!(vertex.entries.len(), 2);
+
+        let uv = &vertex.entries[1];
+        assert_eq!(uv.location, 1);
+        assert_eq!(uv.offset, 12);
+        assert_eq!(
+            uv.primitive,
+            ShaderPrimitive {
+                kind: PrimitiveKind::Float,
+                width: 16,
+                components: 2,
+            }
+        );
+        assert_eq!(uv.primitive.size(), 4);
+        assert_eq!(uv.format, None);
+        assert_eq!(vertex.stride, 16);
+
+        Ok(())
+    }
+
     #[test]
     fn reflects_vertex_layout_metadata() -> Result<(), BentoError> {
         let compiler = Compiler::new()?;
@@ -1695,13 +4212,107 @@ ConstantBuffer<SceneCamera> camera : register(b6);
         let first = &vertex.entries[0];
         assert_eq!(first.location, 0);
         assert_eq!(first.offset, 0);
-        assert_eq!(first.format, dashi::ShaderPrimitiveType::Vec3);
+        assert_eq!(first.format, Some(dashi::ShaderPrimitiveType::Vec3));
 
         let second = &vertex.entries[1];
         assert_eq!(second.location, 1);
         assert_eq!(second.offset, 12);
-        assert_eq!(second.format, dashi::ShaderPrimitiveType::Vec2);
+        assert_eq!(second.format, Some(dashi::ShaderPrimitiveType::Vec2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn host_vertex_layout_matches_shader() -> Result<(), BentoError> {
+        let compiler = Compiler::new()?;
+        let path = "tests/fixtures/simple_vertex.glsl";
+        let result = compiler.compile_from_file(path, &sample_vertex_request())?;
+
+        let host = VertexLayoutBuilder::new()
+            .add(dashi::ShaderPrimitiveType::Vec3, 0)
+            .add(dashi::ShaderPrimitiveType::Vec2, 1)
+            .build();
+        assert_eq!(host.stride, 20);
+        assert_eq!(host.entries[1].offset, 12);
+        assert!(host.matches_shader(&result));
+
+        let mismatched = VertexLayoutBuilder::new()
+            .add(dashi::ShaderPrimitiveType::Vec3, 0)
+            .add(dashi::ShaderPrimitiveType::Vec4, 1)
+            .build();
+        assert!(!mismatched.matches_shader(&result));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reflects_scalar_and_matrix_vertex_inputs() -> Result<(), BentoError> {
+        let compiler = Compiler::new()?;
+        let request = sample_vertex_request();
+        let path = "tests/fixtures/instanced_vertex.glsl";
+
+        let result = compiler.compile_from_file(path, &request)?;
+
+        let model = result
+            .metadata
+            .inputs
+            .iter()
+            .find(|input| input.name == "model")
+            .expect("expected the matrix input");
+        assert_eq!(model.columns, Some(4));
+
+        let vertex = result
+            .metadata
+            .vertex
+            .expect("expected vertex layout metadata for vertex shader");
+        let layout: Vec<(usize, usize)> = vertex
+            .entries
+            .iter()
+            .map(|entry| (entry.location, entry.offset))
+            .collect();
+        assert_eq!(layout, vec![(0, 0), (1, 12), (2, 16), (3, 32), (4, 48), (5, 64)]);
+        assert_eq!(vertex.entries[1].primitive.components, 1);
+        assert!(vertex.entries[2..]
+            .iter()
+            .all(|entry| entry.format == Some(dashi::ShaderPrimitiveType::Vec4)));
+        assert_eq!(vertex.stride, 16 + 64);
+
+        let inputs = result.metadata.vertex_inputs();
+        assert_eq!(inputs.len(), 5);
+        assert_eq!(inputs[1].location, 2);
+        assert_eq!(inputs[1].offset, 16);
+        assert_eq!(inputs[4].location, 5);
+        assert_eq!(inputs[4].offset, 64);
 
         Ok(())
     }
+
+    #[test]
+    fn vertex_inputs_pack_located_attributes() {
+        let input = |name: &str, location, format| InterfaceVariable {
+            name: name.to_string(),
+            location,
+            format,
+            primitive: None,
+            columns: None,
+        };
+        let metadata = ShaderMetadata {
+            inputs: vec![
+                input("uv", Some(1), Some(dashi::ShaderPrimitiveType::Vec2)),
+                input("gl_VertexIndex", None, None),
+                input("position", Some(0), Some(dashi::ShaderPrimitiveType::Vec3)),
+            ],
+            ..Default::default()
+        };
+
+        let entries = metadata.vertex_inputs();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].location, 0);
+        assert_eq!(entries[0].offset, 0);
+        assert_eq!(entries[0].format, dashi::ShaderPrimitiveType::Vec3);
+        assert_eq!(entries[1].location, 1);
+        assert_eq!(entries[1].offset, 12);
+        assert_eq!(entries[1].format, dashi::ShaderPrimitiveType::Vec2);
+    }
 }
@@ -0,0 +1,79 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{BentoError, CompilationResult, Compiler, Request};
+
+/// A fixed set of [`Compiler`]s shared between threads.
+///
+/// `shaderc` compilers can move between threads but not be used from two at once, so each
+/// `compile` call checks an instance out, blocking until one is idle, and returns it when done.
+pub struct CompilerPool {
+    idle: Mutex<Vec<Compiler>>,
+    available: Condvar,
+}
+
+impl CompilerPool {
+    pub fn new(size: usize) -> Result<Self, BentoError> {
+        if size == 0 {
+            return Err(BentoError::InvalidInput(
+                "Compiler pool needs at least one compiler".into(),
+            ));
+        }
+
+        let idle = (0..size)
+            .map(|_| Compiler::new())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            idle: Mutex::new(idle),
+            available: Condvar::new(),
+        })
+    }
+
+    pub fn compile(
+        &self,
+        shader: &[u8],
+        request: &Request,
+    ) -> Result<CompilationResult, BentoError> {
+        self.with_compiler(|compiler| compiler.compile(shader, request))
+    }
+
+    /// Runs `f` with an idle compiler, blocking until one is available.
+    pub fn with_compiler<T>(&self, f: impl FnOnce(&Compiler) -> T) -> T {
+        let mut idle = self.lock();
+        let compiler = loop {
+            if let Some(compiler) = idle.pop() {
+                break compiler;
+            }
+            idle = self
+                .available
+                .wait(idle)
+                .unwrap_or_else(PoisonError::into_inner);
+        };
+        drop(idle);
+
+        let checkout = Checkout {
+            pool: self,
+            compiler: Some(compiler),
+        };
+        f(checkout.compiler.as_ref().expect("checked out compiler"))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Compiler>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the compiler to the pool even if the caller panics while using it.
+struct Checkout<'a> {
+    pool: &'a CompilerPool,
+    compiler: Option<Compiler>,
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        if let Some(compiler) = self.compiler.take() {
+            self.pool.lock().push(compiler);
+            self.pool.available.notify_one();
+        }
+    }
+}
@@ -1,17 +1,20 @@
 use bento::{
     BentoError, CompilationResult, Compiler, OptimizationLevel, PipelineBuildError, Request,
-    ShaderLang,
-    builder::{CSOBuilder, PSOBuilder},
+    ShaderLang, ShaderVariable,
+    builder::{CSOBuilder, DynamicVariableType, PSOBuilder, PipelineWarning},
 };
+use dashi::driver::command::{BeginRenderPass, DrawIndexed};
 use dashi::{
-    BufferInfo, BufferUsage, BufferView, DebugMessageSeverity, DebugMessageType, DebugMessenger,
-    DebugMessengerCreateInfo, IndexedResource, MemoryVisibility, ShaderResource,
+    BufferInfo, BufferUsage, BufferView, ClearValue, CommandQueueInfo2, CommandStream,
+    DebugMessageSeverity, DebugMessageType, DebugMessenger, DebugMessengerCreateInfo, FRect2D,
+    Format, ImageInfo, ImageView, IndexedResource, MemoryVisibility, Rect2D, ShaderResource,
+    SubmitInfo, Topology, Viewport,
 };
 use dashi::{Context, ContextInfo, gpu::vulkan::GPUError};
 use serial_test::serial;
 use std::collections::HashMap;
 use std::ffi::{CStr, c_void};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 const SIMPLE_COMPUTE: &str = r#"
@@ -36,6 +39,20 @@ void main() {
 }
 "#;
 
+const BUFFERED_COMPUTE_RELOADED: &str = r#"
+#version 450
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+layout(set = 0, binding = 0) uniform Config {
+    uint value;
+} config;
+layout(set = 1, binding = 0) buffer Data {
+    uint values[];
+} data;
+void main() {
+    data.values[0] = config.value + 7;
+}
+"#;
+
 const GRAPHICS_VERTEX_SIMPLE: &str = r#"
 #version 450
 void main() {
@@ -168,6 +185,10 @@ impl std::ops::DerefMut for ValidationContext {
 
 impl Drop for ValidationContext {
     fn drop(&mut self) {
+        // Pipelines point at the context where it lives now, so retire it before `take` moves it.
+        if let Some(ctx) = &self.ctx {
+            bento::builder::retire_context(ctx);
+        }
         if let Some(ctx) = self.ctx.take() {
             if let Some(mut guard) = self.guard.take() {
                 guard.teardown(&ctx);
@@ -263,6 +284,7 @@ fn compile_shader(stage: dashi::ShaderType, source: &str) -> CompilationResult {
         optimization: OptimizationLevel::Performance,
         debug_symbols: false,
         defines: HashMap::new(),
+        ..Default::default()
     };
 
     compiler
@@ -270,6 +292,14 @@ fn compile_shader(stage: dashi::ShaderType, source: &str) -> CompilationResult {
         .expect("shader should compile")
 }
 
+fn variable_at(stage: &CompilationResult, set: u32, binding: u32) -> &ShaderVariable {
+    stage
+        .variables
+        .iter()
+        .find(|var| var.set == set && var.kind.binding == binding)
+        .unwrap_or_else(|| panic!("no variable at set {set}, binding {binding}"))
+}
+
 #[test]
 #[serial]
 fn builds_simple_compute_pipeline_without_validation_errors() {
@@ -343,6 +373,50 @@ fn builds_compute_pipeline_with_resources_and_table_updates() {
     );
 }
 
+#[test]
+#[serial]
+fn reloads_compute_spirv_keeping_bind_tables() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, BUFFERED_COMPUTE);
+
+    let uniform = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "config_reload",
+            byte_size: 16,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::UNIFORM,
+            initial_data: None,
+        })
+        .expect("uniform buffer");
+
+    let mut pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_variable("config", ShaderResource::Buffer(uniform.into()))
+        .add_table_variable("data", 2)
+        .build(&mut ctx)
+        .expect("pipeline should build");
+    let tables = pipeline.tables();
+    let data_binding = pipeline.table_binding("data").expect("data binding");
+
+    let reloaded = compile_shader(dashi::ShaderType::Compute, BUFFERED_COMPUTE_RELOADED);
+    pipeline
+        .reload_spirv(&mut ctx, &reloaded)
+        .expect("identical interface should reload");
+
+    assert_eq!(pipeline.tables(), tables);
+    assert_eq!(
+        pipeline.table_binding("data").map(|binding| binding.table),
+        Some(data_binding.table)
+    );
+
+    let mismatched = compile_shader(dashi::ShaderType::Compute, SIMPLE_COMPUTE);
+    assert!(matches!(
+        pipeline.reload_spirv(&mut ctx, &mismatched),
+        Err(PipelineBuildError::InterfaceMismatch { pipeline: "compute" })
+    ));
+    assert_eq!(pipeline.tables(), tables);
+}
+
 #[test]
 #[serial]
 fn compute_pipeline_fails_when_data_is_missing() {
@@ -454,12 +528,7 @@ fn builds_compute_pipeline_with_initial_table_resources() {
 fn compute_table_count_can_be_overridden_with_resources_length() {
     let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
     let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_TABLE_SINGLE);
-    let data_name = compute_stage
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 0)
-        .map(|var| var.name.clone())
-        .expect("data variable name");
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
 
     let first = BufferView::new(
         ctx.make_buffer(&BufferInfo {
@@ -507,12 +576,7 @@ fn compute_table_count_can_be_overridden_with_resources_length() {
 fn compute_table_rejects_out_of_range_slots() {
     let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
     let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_TABLE_SINGLE);
-    let data_name = compute_stage
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 0)
-        .map(|var| var.name.clone())
-        .expect("data variable name");
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
 
     let invalid = BufferView::new(
         ctx.make_buffer(&BufferInfo {
@@ -554,6 +618,89 @@ fn builds_graphics_pipeline_without_resources() {
     assert!(pipeline.is_ok());
 }
 
+#[test]
+#[serial]
+fn graphics_pipeline_rejects_vertex_attributes_without_a_dashi_format() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(
+        dashi::ShaderType::Vertex,
+        include_str!("fixtures/half_vertex.glsl"),
+    );
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+
+    let pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .build(&mut ctx);
+
+    match pipeline {
+        Err(BentoError::PipelineBuild(PipelineBuildError::UnsupportedVertexAttribute {
+            location,
+            primitive,
+        })) => {
+            assert_eq!(location, 1);
+            assert_eq!(primitive.width, 16);
+        }
+        other => panic!("expected unsupported vertex attribute error, got {other:?}"),
+    }
+}
+
+#[test]
+#[serial]
+fn graphics_pipeline_infers_srgb_attachment_formats() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+
+    let pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .srgb_outputs(true)
+        .build(&mut ctx)
+        .expect("pipeline should build with sRGB outputs");
+
+    assert_eq!(pipeline.attachment_formats().len(), 1);
+    assert!(matches!(pipeline.attachment_formats()[0], Format::RGBA8_SRGB));
+}
+
+#[test]
+#[serial]
+fn previews_attachment_formats_before_build() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+
+    let builder = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment));
+    let preview = builder.preview_attachments();
+    assert_eq!(preview.len(), 1);
+    assert!(matches!(preview[0], Format::RGBA8));
+
+    let pipeline = builder.build(&mut ctx).expect("pipeline should build");
+    assert!(pipeline.attachment_formats() == preview.as_slice());
+}
+
+#[test]
+#[serial]
+fn builds_line_list_graphics_pipeline() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+
+    let pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .topology(Topology::LineList)
+        .build(&mut ctx);
+
+    assert!(pipeline.is_ok());
+}
+
 #[test]
 #[serial]
 fn builds_graphics_pipeline_with_shared_uniform_bindings() {
@@ -610,18 +757,8 @@ fn graphics_pipeline_tracks_multiple_sets() {
     let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SET0);
     let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SET2);
 
-    let globals_name = vertex
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 0)
-        .map(|var| var.name.clone())
-        .expect("globals variable name");
-    let data_name = fragment
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 2)
-        .map(|var| var.name.clone())
-        .expect("data variable name");
+    let globals_name = variable_at(&vertex, 0, 0).name.clone();
+    let data_name = variable_at(&fragment, 2, 0).name.clone();
 
     let globals = BufferView::new(
         ctx.make_buffer(&BufferInfo {
@@ -668,12 +805,7 @@ fn graphics_table_count_can_be_overridden() {
 
     let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
     let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_STORAGE);
-    let data_name = fragment
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 0)
-        .map(|var| var.name.clone())
-        .expect("data variable name");
+    let data_name = variable_at(&fragment, 0, 0).name.clone();
 
     let first = BufferView::new(
         ctx.make_buffer(&BufferInfo {
@@ -809,12 +941,7 @@ fn graphics_table_rejects_out_of_range_slots() {
 
     let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
     let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_STORAGE);
-    let data_name = fragment
-        .variables
-        .iter()
-        .find(|var| var.kind.binding == 0 && var.set == 0)
-        .map(|var| var.name.clone())
-        .expect("data variable name");
+    let data_name = variable_at(&fragment, 0, 0).name.clone();
 
     let invalid = BufferView::new(
         ctx.make_buffer(&BufferInfo {
@@ -840,3 +967,418 @@ fn graphics_table_rejects_out_of_range_slots() {
         .build(&mut ctx);
     assert!(pipeline.is_err());
 }
+
+#[test]
+#[serial]
+fn graphics_pipeline_warns_on_unknown_table_key() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&warnings);
+    let mut pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .on_warning(move |warning| sink.lock().unwrap().push(warning))
+        .build(&mut ctx)
+        .expect("pipeline should build");
+
+    let buffer = BufferView::new(
+        ctx.make_buffer(&BufferInfo {
+            debug_name: "unknown_key",
+            byte_size: 16,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::STORAGE,
+            initial_data: None,
+        })
+        .expect("unknown key buffer"),
+    );
+
+    pipeline.update_table(
+        "not_a_binding",
+        IndexedResource {
+            resource: ShaderResource::StorageBuffer(buffer),
+            slot: 0,
+        },
+    );
+
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        vec![PipelineWarning::UnknownTableKey {
+            key: "not_a_binding".to_string(),
+        }]
+    );
+}
+
+#[test]
+#[serial]
+fn compute_dynamic_storage_binding_uses_storage_default() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_TABLE_SINGLE);
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
+
+    let pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_dynamic_variable(&data_name, 1, DynamicVariableType::Storage)
+        .build(&mut ctx);
+
+    assert!(pipeline.is_ok());
+}
+
+#[test]
+#[serial]
+fn compute_pipeline_can_be_built_and_destroyed_repeatedly() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_TABLE_SINGLE);
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
+
+    for _ in 0..8 {
+        let pipeline = CSOBuilder::new()
+            .shader_compiled(Some(compute_stage.clone()))
+            .add_table_variable(&data_name, 4)
+            .build(&mut ctx)
+            .expect("pipeline should build with defaulted table resources");
+
+        pipeline.destroy(&mut ctx);
+    }
+}
+
+#[test]
+#[serial]
+fn compute_pipeline_binds_buffer_range() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_TABLE_SINGLE);
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
+
+    let buffer = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "suballocated",
+            byte_size: 256,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::STORAGE,
+            initial_data: None,
+        })
+        .expect("storage buffer");
+    let view = BufferView {
+        handle: buffer,
+        offset: 0,
+        size: 256,
+    };
+
+    let pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage.clone()))
+        .add_buffer_range(&data_name, view, 128, 64)
+        .build(&mut ctx);
+    assert!(pipeline.is_ok());
+
+    let unsized_view = BufferView { size: 0, ..view };
+    for (view, offset) in [(view, 224), (view, u64::MAX), (unsized_view, 0)] {
+        let overflowing = CSOBuilder::new()
+            .shader_compiled(Some(compute_stage.clone()))
+            .add_buffer_range(&data_name, view, offset, 64)
+            .build(&mut ctx);
+        assert!(matches!(
+            overflowing,
+            Err(BentoError::PipelineBuild(
+                PipelineBuildError::BufferRangeOutOfBounds { .. }
+            ))
+        ));
+    }
+}
+
+const FRAGMENT_SAMPLER_ARRAY: &str = r#"
+#version 450
+layout(set = 0, binding = 0) uniform sampler2D textures[4];
+layout(location = 0) out vec4 color;
+void main() {
+    color = texture(textures[0], vec2(0.5)) + texture(textures[3], vec2(0.5));
+}
+"#;
+
+const COMPUTE_BUFFER_ARRAY: &str = r#"
+#version 450
+layout(local_size_x = 1) in;
+layout(set = 0, binding = 0) buffer Data {
+    uint values[];
+} data[4];
+void main() {
+    data[3].values[0] = data[0].values[0];
+}
+"#;
+
+#[test]
+#[serial]
+fn graphics_pipeline_binds_fixed_sampler_array() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, FRAGMENT_SAMPLER_ARRAY);
+    let textures = variable_at(&fragment, 0, 0);
+    assert_eq!(textures.kind.count, 4);
+    let textures = textures.name.clone();
+
+    let pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .add_table_variable(&textures, 4)
+        .build(&mut ctx);
+
+    assert!(pipeline.is_ok());
+}
+
+#[test]
+#[serial]
+fn single_binding_fills_fixed_buffer_array() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_BUFFER_ARRAY);
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
+
+    let buffer = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "shared array element",
+            byte_size: 64,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::STORAGE,
+            initial_data: None,
+        })
+        .expect("storage buffer");
+
+    let pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_variable(&data_name, ShaderResource::StorageBuffer(buffer.into()))
+        .build(&mut ctx);
+
+    assert!(pipeline.is_ok());
+}
+
+const COMPUTE_BUFFER_ARRAY_3: &str = r#"
+#version 450
+layout(local_size_x = 1) in;
+layout(set = 0, binding = 0) buffer Data {
+    uint values[];
+} data[3];
+void main() {
+    data[2].values[0] = data[0].values[0] + data[1].values[0];
+}
+"#;
+
+#[test]
+#[serial]
+fn buffer_array_fills_consecutive_slots() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, COMPUTE_BUFFER_ARRAY_3);
+    let data_name = variable_at(&compute_stage, 0, 0).name.clone();
+
+    let views: Vec<BufferView> = (0..3)
+        .map(|_| {
+            BufferView::new(
+                ctx.make_buffer(&BufferInfo {
+                    debug_name: "buffer array element",
+                    byte_size: 16,
+                    visibility: MemoryVisibility::CpuAndGpu,
+                    usage: BufferUsage::STORAGE,
+                    initial_data: None,
+                })
+                .expect("storage buffer"),
+            )
+        })
+        .collect();
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = warnings.clone();
+    let pipeline = CSOBuilder::new()
+        .on_warning(move |warning| sink.lock().unwrap().push(warning))
+        .shader_compiled(Some(compute_stage.clone()))
+        .add_buffer_array(&data_name, &views)
+        .build(&mut ctx)
+        .expect("pipeline should build with a buffer array");
+
+    let target = pipeline.table_binding(&data_name).expect("buffer array binding");
+    assert_eq!(target.size, 3);
+    assert!(warnings.lock().unwrap().is_empty());
+
+    let short = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_buffer_array(&data_name, &views[..2])
+        .build(&mut ctx);
+    assert!(matches!(
+        short,
+        Err(BentoError::PipelineBuild(
+            PipelineBuildError::InvalidResourceCount {
+                expected: 3,
+                provided: 2,
+                ..
+            }
+        ))
+    ));
+}
+
+#[test]
+#[serial]
+fn draws_into_compatible_render_pass() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+
+    let vertex = compile_shader(dashi::ShaderType::Vertex, GRAPHICS_VERTEX_SIMPLE);
+    let fragment = compile_shader(dashi::ShaderType::Fragment, GRAPHICS_FRAGMENT_SIMPLE);
+    let pipeline = PSOBuilder::new()
+        .vertex_compiled(Some(vertex))
+        .fragment_compiled(Some(fragment))
+        .build(&mut ctx)
+        .expect("pipeline should build");
+
+    let viewport = Viewport {
+        area: FRect2D {
+            w: 64.0,
+            h: 64.0,
+            ..Default::default()
+        },
+        scissor: Rect2D {
+            w: 64,
+            h: 64,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let render_pass = pipeline
+        .make_compatible_render_pass(&mut ctx, viewport)
+        .expect("render pass should match the pipeline");
+
+    let target = ctx
+        .make_image(&ImageInfo {
+            debug_name: "compatible_target",
+            dim: [64, 64, 1],
+            format: pipeline.attachment_formats()[0],
+            ..Default::default()
+        })
+        .expect("target image");
+    let indices = [0u32, 1, 2];
+    // The vertex shader reads no attributes, so one buffer can back both draw inputs.
+    let geometry = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "compatible_geometry",
+            byte_size: std::mem::size_of_val(&indices) as u32,
+            visibility: MemoryVisibility::Gpu,
+            usage: BufferUsage::ALL,
+            initial_data: unsafe { Some(indices.align_to::<u8>().1) },
+        })
+        .expect("geometry buffer");
+
+    let mut ring = ctx
+        .make_command_ring(&CommandQueueInfo2 {
+            debug_name: "compatible_render_pass",
+            ..Default::default()
+        })
+        .expect("command ring");
+    ring.record(|list| {
+        let mut stream = CommandStream::new().begin().begin_render_pass(&BeginRenderPass {
+            viewport,
+            render_pass,
+            color_attachments: [
+                Some(ImageView {
+                    img: target,
+                    ..Default::default()
+                }),
+                None,
+                None,
+                None,
+            ],
+            depth_attachment: None,
+            clear_values: [Some(ClearValue::Color([0.0, 0.0, 0.0, 1.0])), None, None, None],
+            depth_clear: None,
+        });
+        stream = stream
+            .bind_graphics_pipeline(pipeline.handle)
+            .update_viewport(&viewport)
+            .draw_indexed(&DrawIndexed {
+                vertices: geometry,
+                indices: geometry,
+                index_count: indices.len() as u32,
+                ..Default::default()
+            })
+            .unbind_graphics_pipeline();
+        stream.stop_drawing().end().append(list);
+    })
+    .expect("record draw");
+    ring.submit(&SubmitInfo::default()).expect("submit draw");
+    ring.wait_all().expect("wait for draw");
+}
+
+#[test]
+#[serial]
+#[cfg(debug_assertions)]
+fn table_update_after_context_retired_panics() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, BUFFERED_COMPUTE);
+
+    let mut pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_table_variable("config", 1)
+        .add_table_variable("data", 1)
+        .build(&mut ctx)
+        .expect("pipeline should build");
+
+    let buffer = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "stale update",
+            byte_size: 16,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::STORAGE,
+            initial_data: None,
+        })
+        .expect("storage buffer");
+
+    bento::builder::retire_context(&ctx);
+    let update = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pipeline.update_table(
+            "data",
+            IndexedResource {
+                resource: ShaderResource::StorageBuffer(buffer.into()),
+                slot: 0,
+            },
+        );
+    }));
+
+    assert!(update.is_err());
+}
+
+#[test]
+#[serial]
+#[cfg(debug_assertions)]
+fn table_update_after_context_address_reregistered_panics() {
+    let mut ctx = ValidationContext::headless(&ContextInfo::default()).expect("headless context");
+    let compute_stage = compile_shader(dashi::ShaderType::Compute, BUFFERED_COMPUTE);
+
+    let mut pipeline = CSOBuilder::new()
+        .shader_compiled(Some(compute_stage))
+        .add_table_variable("config", 1)
+        .add_table_variable("data", 1)
+        .build(&mut ctx)
+        .expect("pipeline should build");
+
+    let buffer = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "reused address update",
+            byte_size: 16,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::STORAGE,
+            initial_data: None,
+        })
+        .expect("storage buffer");
+
+    // Stands in for a new context created where the pipeline's context lived, with no
+    // `retire_context` call in between.
+    bento::builder::register_context(&ctx);
+    let update = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pipeline.update_table(
+            "data",
+            IndexedResource {
+                resource: ShaderResource::StorageBuffer(buffer.into()),
+                slot: 0,
+            },
+        );
+    }));
+
+    assert!(update.is_err());
+}
@@ -183,15 +183,23 @@ fn inspects_saved_artifact() {
                 binding: 0,
                 count: 1,
             },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
         }],
         metadata: bento::ShaderMetadata {
             entry_points: vec!["main".to_string()],
             inputs: vec![],
             outputs: vec![],
             workgroup_size: Some([1, 1, 1]),
+            workgroup_size_specializable: false,
             vertex: None,
+            may_discard: false,
+            uses_atomics: false,
+            push_constants: None,
         },
         spirv: vec![0x0723_0203, 1, 2],
+        warnings: Vec::new(),
     };
 
     artifact
@@ -224,9 +232,14 @@ fn outputs_json_when_requested() {
             inputs: vec![],
             outputs: vec![],
             workgroup_size: None,
+            workgroup_size_specializable: false,
             vertex: None,
+            may_discard: false,
+            uses_atomics: false,
+            push_constants: None,
         },
         spirv: vec![1, 2, 3, 4],
+        warnings: Vec::new(),
     };
 
     artifact
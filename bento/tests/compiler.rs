@@ -1,4 +1,8 @@
-use bento::{BentoError, Compiler, OptimizationLevel, Request, ShaderLang};
+use bento::{
+    BentoError, Compiler, CompilerPool, DiagnosticSeverity, GlslProfile, OptimizationLevel,
+    RegisterClass, Request, ShaderArchive, ShaderLang, SpecConstantCount, SpirvTargetVersion,
+    TexelBufferKind,
+};
 use std::collections::HashMap;
 
 fn spirv_words_to_bytes(words: &[u32]) -> &[u8] {
@@ -52,6 +56,25 @@ fn has_debug_instructions(spirv: &[u32]) -> bool {
     false
 }
 
+fn op_name_count(spirv: &[u32]) -> usize {
+    use rspirv::spirv::Op;
+
+    let mut count = 0;
+    let mut index = 5;
+    while index < spirv.len() {
+        let word_count = (spirv[index] >> 16) as usize;
+        if word_count == 0 || index + word_count > spirv.len() {
+            break;
+        }
+        if spirv[index] & 0xFFFF == Op::Name as u32 {
+            count += 1;
+        }
+        index += word_count;
+    }
+
+    count
+}
+
 fn sample_request(lang: ShaderLang) -> Request {
     Request {
         name: Some("sample".to_string()),
@@ -60,6 +83,7 @@ fn sample_request(lang: ShaderLang) -> Request {
         optimization: OptimizationLevel::None,
         debug_symbols: false,
         defines: HashMap::new(),
+        ..Default::default()
     }
 }
 
@@ -82,6 +106,409 @@ fn compiles_fixture_shader() -> Result<(), BentoError> {
     Ok(())
 }
 
+#[test]
+fn shader_archive_round_trips_through_disk() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let fixtures = [
+        ("simple", "tests/fixtures/simple_compute.glsl"),
+        ("atomics", "tests/fixtures/atomic_counter.comp.glsl"),
+        ("texels", "tests/fixtures/texel_buffer.comp.glsl"),
+    ];
+
+    let mut archive = ShaderArchive::new();
+    let mut originals = Vec::new();
+    for (name, path) in fixtures {
+        let result = compiler.compile_from_file(path, &request)?;
+        archive.insert(name, &result)?;
+        originals.push((name, result));
+    }
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("shaders.bnta");
+    let path = path.to_str().expect("utf-8 temp path");
+    archive.save_to_disk(path)?;
+
+    let loaded = ShaderArchive::load_from_disk(path)?;
+    assert_eq!(loaded.len(), 3);
+    for (name, original) in &originals {
+        assert_eq!(loaded.get(name).as_ref(), Some(original), "{name}");
+    }
+    assert!(loaded.get("missing").is_none());
+
+    let mut bytes = std::fs::read(path)?;
+    bytes[0] = b'X';
+    assert!(matches!(
+        ShaderArchive::from_bytes(&bytes),
+        Err(BentoError::InvalidInput(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn compiles_fixture_relative_to_runtime_base_dir() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let base = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+    let result = compiler.compile_from_file_in(&base, "includes/relative_compute.glsl", &request)?;
+
+    assert_eq!(result.stage, dashi::ShaderType::Compute);
+    assert!(!result.spirv.is_empty());
+    assert!(result.variables.iter().any(|var| var.kind.binding == 0));
+
+    Ok(())
+}
+
+#[test]
+fn caches_fixture_compiled_relative_to_runtime_base_dir() -> Result<(), BentoError> {
+    let cache = tempfile::tempdir()?;
+    let compiler = Compiler::with_cache(cache.path().to_path_buf())?;
+    let request = sample_request(ShaderLang::Glsl);
+    let base = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+    let first = compiler.compile_from_file_in(&base, "includes/relative_compute.glsl", &request)?;
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 1);
+
+    let cached = compiler.compile_from_file_in(&base, "includes/relative_compute.glsl", &request)?;
+    assert_eq!(cached, first);
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn inlines_imports_from_include_dirs() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut request = sample_request(ShaderLang::Slang);
+    request.include_dirs = vec![fixtures.join("modules")];
+
+    let result = compiler.compile_from_file("tests/fixtures/imports_module.slang", &request)?;
+
+    assert_eq!(result.stage, dashi::ShaderType::Compute);
+    assert!(!result.spirv.is_empty());
+    assert!(result.variables.iter().any(|var| var.kind.binding == 0));
+
+    request.include_dirs = vec![fixtures.join("includes")];
+    let err = compiler
+        .compile_from_file("tests/fixtures/imports_module.slang", &request)
+        .expect_err("fill_value is not in the includes fixture dir");
+    assert!(matches!(err, BentoError::InvalidInput(_)));
+
+    Ok(())
+}
+
+#[test]
+fn resolves_includes_from_include_dirs_through_the_preprocessor() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.include_dirs = vec![fixtures.join("includes")];
+    let source = r#"
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout(local_size_x = 1) in;
+
+#include <shared_data.glsl>
+#ifdef MISSING_INCLUDE
+#include "does_not_exist.glsl"
+#endif
+
+void main() {
+    data.values[gl_GlobalInvocationID.x] = 1;
+}
+"#;
+
+    let result = compiler.compile(source.as_bytes(), &request)?;
+    assert!(result.variables.iter().any(|var| var.kind.binding == 0));
+
+    request.defines.insert("MISSING_INCLUDE".to_string(), None);
+    let err = compiler
+        .compile(source.as_bytes(), &request)
+        .expect_err("the guarded include doesn't exist");
+    assert!(matches!(err, BentoError::CompilationFailed { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn reports_circular_imports() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let modules = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+    let mut request = sample_request(ShaderLang::Slang);
+    request.include_dirs = vec![modules];
+
+    let err = compiler
+        .compile_from_file("tests/fixtures/modules/cycle_a.slang", &request)
+        .expect_err("cycle_a and cycle_b import each other");
+    let BentoError::InvalidInput(message) = err else {
+        panic!("expected InvalidInput, got {err:?}");
+    };
+    assert!(message.contains("Circular include"));
+    assert!(message.contains("cycle_a.slang"));
+    assert!(message.contains("cycle_b.slang"));
+
+    Ok(())
+}
+
+#[test]
+fn repeated_compiles_produce_identically_ordered_variables() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let path = "tests/fixtures/scene_cull.comp.glsl";
+
+    let first = compiler.compile_from_file(path, &request)?;
+    let second = compiler.compile_from_file(path, &request)?;
+
+    let sorted = first.sorted_variables();
+    assert_eq!(sorted, second.sorted_variables());
+    assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    let mut shuffled = first.clone();
+    shuffled.variables = sorted.iter().rev().cloned().collect();
+    assert!(shuffled.variables.len() > 1);
+    assert_ne!(shuffled.variables, sorted);
+    assert_eq!(shuffled.sorted_variables(), sorted);
+
+    Ok(())
+}
+
+#[test]
+fn variables_differing_only_in_type_do_not_order_equal() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let result = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+
+    let storage = result.variables[0].clone();
+    let mut uniform = storage.clone();
+    uniform.kind.var_type = dashi::BindTableVariableType::Uniform;
+
+    assert_ne!(storage, uniform);
+    assert_ne!(storage.cmp(&uniform), std::cmp::Ordering::Equal);
+    assert_eq!(storage.cmp(&uniform), uniform.cmp(&storage).reverse());
+
+    Ok(())
+}
+
+#[test]
+fn content_hash_tracks_shader_content() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let source = include_str!("fixtures/simple_compute.glsl");
+    let modified = source.replace("= 1;", "= 2;");
+
+    let first = compiler.compile(source.as_bytes(), &request)?;
+    let second = compiler.compile(source.as_bytes(), &request)?;
+    let changed = compiler.compile(modified.as_bytes(), &request)?;
+
+    assert_eq!(first.content_hash(), second.content_hash());
+    assert_ne!(first.content_hash(), changed.content_hash());
+
+    Ok(())
+}
+
+#[test]
+fn reports_bindings_the_shader_never_reads() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let result = compiler.compile_from_file("tests/fixtures/unused_binding.glsl", &request)?;
+    let unused: Vec<(u32, u32)> = result
+        .unused_bindings()
+        .iter()
+        .map(|var| (var.set, var.kind.binding))
+        .collect();
+
+    assert_eq!(result.variables.len(), 3);
+    assert_eq!(unused, vec![(0, 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn compiler_pool_compiles_concurrently() -> Result<(), BentoError> {
+    let pool = CompilerPool::new(4)?;
+    let request = sample_request(ShaderLang::Glsl);
+    let source = include_str!("fixtures/simple_compute.glsl");
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let jobs: Vec<_> = (0..16)
+            .map(|_| scope.spawn(|| pool.compile(source.as_bytes(), &request)))
+            .collect();
+        jobs.into_iter()
+            .map(|job| job.join().expect("compile thread panicked"))
+            .collect()
+    });
+
+    assert_eq!(results.len(), 16);
+    for result in results {
+        assert!(!result?.spirv.is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn compiles_batches_with_independent_results() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = |stage| Request {
+        stage,
+        ..sample_request(ShaderLang::Glsl)
+    };
+
+    let batch = vec![
+        (
+            include_bytes!("fixtures/simple_compute.glsl").to_vec(),
+            request(dashi::ShaderType::Compute),
+        ),
+        (
+            include_bytes!("fixtures/simple_vertex.glsl").to_vec(),
+            request(dashi::ShaderType::Vertex),
+        ),
+        (
+            include_bytes!("fixtures/opaque.frag.glsl").to_vec(),
+            request(dashi::ShaderType::Fragment),
+        ),
+        (
+            b"#version 450\nvoid main() { undefined_call(); }\n".to_vec(),
+            request(dashi::ShaderType::Compute),
+        ),
+    ];
+
+    let results = compiler.compile_batch(&batch);
+    assert_eq!(results.len(), 4);
+
+    let stages: Vec<_> = results[..3]
+        .iter()
+        .map(|result| result.as_ref().expect("fixture should compile").stage)
+        .collect();
+    assert_eq!(
+        stages,
+        [
+            dashi::ShaderType::Compute,
+            dashi::ShaderType::Vertex,
+            dashi::ShaderType::Fragment,
+        ]
+    );
+    assert!(matches!(results[3], Err(BentoError::CompilationFailed { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn compiles_every_permutation_of_a_define_matrix() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let shader = r#"
+#version 450
+layout(local_size_x = WIDTH) in;
+layout(set = 0, binding = 0) buffer Data { uint values[]; } data;
+void main() {
+    data.values[gl_GlobalInvocationID.x] = SCALE;
+}
+"#;
+    let widths = [Some("1".to_string()), Some("8".to_string())];
+    let scales = [Some("2".to_string()), Some("3".to_string())];
+
+    let permutations = compiler.compile_permutations(
+        shader.as_bytes(),
+        &sample_request(ShaderLang::Glsl),
+        &[("WIDTH", &widths[..]), ("SCALE", &scales[..])],
+    );
+
+    assert_eq!(permutations.len(), 4);
+    let mut seen = Vec::new();
+    for (defines, result) in permutations {
+        let result = result?;
+        assert_eq!(defines.len(), 2);
+        let width: u32 = defines["WIDTH"].as_deref().unwrap().parse().unwrap();
+        assert_eq!(result.metadata.workgroup_size, Some([width, 1, 1]));
+
+        let key = (defines["WIDTH"].clone(), defines["SCALE"].clone());
+        assert!(!seen.contains(&key), "duplicate permutation {key:?}");
+        seen.push(key);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reflects_texel_buffers_apart_from_plain_buffers() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let result = compiler.compile_from_file("tests/fixtures/texel_buffer.comp.glsl", &request)?;
+
+    let weights = result.binding_by_name("weights").expect("weights binding");
+    assert_eq!(weights.texel_buffer, Some(TexelBufferKind::Uniform));
+    let results = result.binding_by_name("results").expect("results binding");
+    assert_eq!(results.texel_buffer, Some(TexelBufferKind::Storage));
+    assert_eq!(results.kind.var_type, dashi::BindTableVariableType::Uniform);
+
+    Ok(())
+}
+
+#[test]
+fn flags_compute_shaders_that_use_atomics() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let counter = compiler.compile_from_file("tests/fixtures/atomic_counter.comp.glsl", &request)?;
+    let plain = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+
+    assert!(counter.metadata.uses_atomics);
+    assert!(!plain.metadata.uses_atomics);
+
+    Ok(())
+}
+
+#[test]
+fn flags_fragment_shaders_that_may_discard() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = Request {
+        stage: dashi::ShaderType::Fragment,
+        ..sample_request(ShaderLang::Glsl)
+    };
+
+    let alpha_test = compiler.compile_from_file("tests/fixtures/alpha_test.frag.glsl", &request)?;
+    let opaque = compiler.compile_from_file("tests/fixtures/opaque.frag.glsl", &request)?;
+
+    assert!(alpha_test.metadata.may_discard);
+    assert!(!opaque.metadata.may_discard);
+
+    Ok(())
+}
+
+#[test]
+fn infers_integer_attachment_formats_for_integer_outputs() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = Request {
+        stage: dashi::ShaderType::Fragment,
+        ..sample_request(ShaderLang::Glsl)
+    };
+    let shader = r#"
+#version 450
+// Declared out of location order; formats still come back by location.
+layout(location = 1) out uvec4 out_ids;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(1.0);
+    out_ids = uvec4(7);
+}
+"#;
+
+    let result = compiler.compile(shader.as_bytes(), &request)?;
+
+    assert_eq!(
+        result.inferred_attachment_formats(),
+        [dashi::Format::RGBA8, dashi::Format::RGBA32Uint]
+    );
+
+    Ok(())
+}
+
 #[test]
 fn compiles_fixture_shader_with_debug_symbols() -> Result<(), BentoError> {
     let compiler = Compiler::new()?;
@@ -183,7 +610,41 @@ fn fails_with_invalid_shader_source() {
         .compile(b"#version 450\nvoid main() {", &request)
         .unwrap_err();
 
-    assert!(matches!(err, BentoError::ShaderCompilation(_)));
+    let BentoError::CompilationFailed { diagnostics } = err else {
+        panic!("Unexpected error: {err:?}");
+    };
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+    );
+}
+
+#[test]
+fn collects_compile_warnings() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    // glslang doesn't warn about unused variables, but it does warn about unknown extensions.
+    let source = "#version 450\n\
+                  #extension GL_BENTO_unknown_extension : warn\n\
+                  layout(local_size_x = 1) in;\n\
+                  void main() {}\n";
+
+    let result = compiler.compile(source.as_bytes(), &request)?;
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("GL_BENTO_unknown_extension")),
+        "warnings: {:?}",
+        result.warnings
+    );
+
+    let clean = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+    assert!(clean.warnings.is_empty());
+
+    Ok(())
 }
 
 #[test]
@@ -275,6 +736,21 @@ fn applies_preprocessor_definitions() -> Result<(), BentoError> {
     Ok(())
 }
 
+#[test]
+fn reflects_spec_constant_workgroup_size_defaults() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let result = compiler.compile_from_file("tests/fixtures/spec_workgroup.comp.glsl", &request)?;
+    assert_eq!(result.metadata.workgroup_size, Some([16, 4, 1]));
+    assert!(result.metadata.workgroup_size_specializable);
+
+    let literal = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+    assert!(!literal.metadata.workgroup_size_specializable);
+
+    Ok(())
+}
+
 #[test]
 fn hlsl_binding_names_follow_registers() -> Result<(), BentoError> {
     let compiler = Compiler::new()?;
@@ -301,6 +777,36 @@ fn hlsl_binding_names_follow_registers() -> Result<(), BentoError> {
     Ok(())
 }
 
+#[test]
+fn shifts_hlsl_register_classes_apart() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Hlsl);
+    request.hlsl_register_shifts = HashMap::from([
+        (RegisterClass::Texture, 10),
+        (RegisterClass::UnorderedAccess, 20),
+    ]);
+    let path = "tests/fixtures/hlsl_register_shift.hlsl";
+
+    let result = compiler.compile_from_file(path, &request)?;
+
+    let bindings: Vec<(u32, String)> = result
+        .variables
+        .iter()
+        .map(|var| (var.kind.binding, var.name.clone()))
+        .collect();
+    assert_eq!(
+        bindings,
+        vec![
+            (0, "Params".to_string()),
+            (10, "colorTex".to_string()),
+            (20, "outputData".to_string()),
+        ]
+    );
+    assert_eq!(bindings, binding_names_from_spirv(&result.spirv));
+
+    Ok(())
+}
+
 #[test]
 fn hlsl_binding_names_follow_declaration_order() -> Result<(), BentoError> {
     let compiler = Compiler::new()?;
@@ -376,3 +882,671 @@ fn slang_binding_names_follow_declaration_order() -> Result<(), BentoError> {
 
     Ok(())
 }
+
+#[test]
+fn reports_binding_gaps_within_a_set() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let shader = r#"
+#version 450
+layout(local_size_x = 1) in;
+
+layout(set = 0, binding = 0) buffer Input {
+    uint values[];
+} input_data;
+
+layout(set = 0, binding = 2) buffer Output {
+    uint values[];
+} output_data;
+
+void main() {
+    output_data.values[gl_GlobalInvocationID.x] = input_data.values[gl_GlobalInvocationID.x];
+}
+"#;
+
+    let result = compiler.compile(shader.as_bytes(), &request)?;
+
+    assert_eq!(result.binding_gaps(), vec![(0, 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn exports_reflection_json_without_spirv() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let shader = r#"
+#version 450
+layout(local_size_x = 1) in;
+
+layout(set = 1, binding = 3) buffer Output {
+    uint values[];
+} output_data;
+
+void main() {
+    output_data.values[gl_GlobalInvocationID.x] = gl_GlobalInvocationID.x;
+}
+"#;
+
+    let result = compiler.compile(shader.as_bytes(), &request)?;
+    let json: serde_json::Value = serde_json::from_str(&result.to_reflection_json())?;
+
+    let variables = json["variables"].as_array().expect("variables array");
+    assert_eq!(variables.len(), 1);
+    assert_eq!(variables[0]["name"], result.variables[0].name.as_str());
+    assert_eq!(variables[0]["set"], 1);
+    assert_eq!(variables[0]["binding"], 3);
+    assert_eq!(json["entry_points"][0], "main");
+    assert!(json.get("spirv").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn compiles_debug_and_optimized_variants_together() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+    let shader = std::fs::read("tests/fixtures/simple_compute.glsl")?;
+
+    let (debug, optimized) = compiler.compile_dual(&shader, &request)?;
+
+    assert_eq!(debug.variables, optimized.variables);
+    assert!(has_debug_instructions(&debug.spirv));
+    assert!(optimized.spirv.len() < debug.spirv.len());
+
+    Ok(())
+}
+
+#[test]
+fn caches_library_entry_points_separately() -> Result<(), BentoError> {
+    let cache = tempfile::tempdir()?;
+    let compiler = Compiler::with_cache(cache.path().to_path_buf())?;
+    let source = r#"
+[[vk::binding(0, 0)]] RWStructuredBuffer<uint> data;
+
+[numthreads(1, 1, 1)]
+void clear_data(uint3 id : SV_DispatchThreadID)
+{
+    data[id.x] = 0;
+}
+
+[numthreads(1, 1, 1)]
+void fill_data(uint3 id : SV_DispatchThreadID)
+{
+    data[id.x] = 1;
+}
+"#;
+    let entry_points = [
+        ("clear_data", dashi::ShaderType::Compute),
+        ("fill_data", dashi::ShaderType::Compute),
+    ];
+
+    let first = compiler.compile_library(source.as_bytes(), ShaderLang::Hlsl, &entry_points)?;
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 2);
+
+    let cached = compiler.compile_library(source.as_bytes(), ShaderLang::Hlsl, &entry_points)?;
+    assert_eq!(cached, first);
+    assert_eq!(std::fs::read_dir(cache.path())?.count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn compiles_slang_library_entry_points() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let source = r#"
+[[vk::binding(0, 0)]] RWStructuredBuffer<uint> data;
+
+[numthreads(1, 1, 1)]
+void clear_data(uint3 id : SV_DispatchThreadID)
+{
+    data[id.x] = 0;
+}
+
+[numthreads(1, 1, 1)]
+void fill_data(uint3 id : SV_DispatchThreadID)
+{
+    data[id.x] = 1;
+}
+"#;
+
+    let results = compiler.compile_library(
+        source.as_bytes(),
+        ShaderLang::Slang,
+        &[
+            ("clear_data", dashi::ShaderType::Compute),
+            ("fill_data", dashi::ShaderType::Compute),
+        ],
+    )?;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].metadata.entry_points, vec!["clear_data".to_string()]);
+    assert_eq!(results[1].metadata.entry_points, vec!["fill_data".to_string()]);
+    assert!(results.iter().all(|result| !result.spirv.is_empty()));
+    assert!(results.iter().all(|result| !result.variables.is_empty()));
+
+    Ok(())
+}
+
+#[test]
+fn preserves_stripped_bindings_across_variants() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let shader = r#"
+#version 450
+layout(local_size_x = 1) in;
+
+layout(set = 0, binding = 0) buffer Output {
+    uint values[];
+} output_data;
+
+layout(set = 0, binding = 1) uniform Params {
+    uint scale;
+} params;
+
+void main() {
+#ifdef USE_PARAMS
+    output_data.values[gl_GlobalInvocationID.x] = params.scale;
+#else
+    output_data.values[gl_GlobalInvocationID.x] = 1;
+#endif
+}
+"#;
+
+    let mut with_params = sample_request(ShaderLang::Glsl);
+    with_params.optimization = OptimizationLevel::Performance;
+    with_params.preserve_all_bindings = true;
+    with_params.defines.insert("USE_PARAMS".into(), None);
+
+    let mut without_params = sample_request(ShaderLang::Glsl);
+    without_params.optimization = OptimizationLevel::Performance;
+    without_params.preserve_all_bindings = true;
+
+    let used = compiler.compile(shader.as_bytes(), &with_params)?;
+    let unused = compiler.compile(shader.as_bytes(), &without_params)?;
+
+    let layout = |result: &bento::CompilationResult| {
+        result
+            .variables
+            .iter()
+            .map(|var| (var.set, var.kind.binding, var.kind.var_type, var.name.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(layout(&used).len(), 2);
+    assert_eq!(layout(&used), layout(&unused));
+
+    Ok(())
+}
+
+#[test]
+fn compiles_with_forced_glsl_profile() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.forced_glsl_version = Some((450, GlslProfile::Core));
+
+    let result = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+    assert!(!result.spirv.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn rejects_incompatible_forced_glsl_version() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    // Compute shaders need GLSL 4.30 or later.
+    request.forced_glsl_version = Some((110, GlslProfile::None));
+
+    let err = compiler
+        .compile_from_file("tests/fixtures/simple_compute.glsl", &request)
+        .expect_err("compute shader should not compile as GLSL 1.10");
+    assert!(matches!(err, BentoError::CompilationFailed { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn hlsl_shader_model_defines_target_macros() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let source = r#"
+#if !defined(__SHADER_TARGET_MAJOR) || __SHADER_TARGET_MAJOR != 6 || __SHADER_TARGET_MINOR != 2
+#error shader model 6.2 was not applied
+#endif
+
+[[vk::binding(0, 0)]] RWStructuredBuffer<uint> data;
+
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    data[id.x] = __SHADER_TARGET_MAJOR * 10 + __SHADER_TARGET_MINOR;
+}
+"#;
+
+    let mut request = sample_request(ShaderLang::Hlsl);
+    let err = compiler
+        .compile(source.as_bytes(), &request)
+        .expect_err("shader model macros should be absent without a shader model");
+    assert!(matches!(err, BentoError::CompilationFailed { .. }));
+
+    request.hlsl_shader_model = Some(62);
+    let result = compiler.compile(source.as_bytes(), &request)?;
+    assert!(!result.spirv.is_empty());
+
+    request.hlsl_shader_model = Some(50);
+    assert!(compiler.compile(source.as_bytes(), &request).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn compiles_for_configured_spirv_version() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let default_request = sample_request(ShaderLang::Glsl);
+    let spirv_1_5 = Request {
+        target_spirv: SpirvTargetVersion::V1_5,
+        ..default_request.clone()
+    };
+
+    let path = "tests/fixtures/simple_compute.glsl";
+    let default = compiler.compile_from_file(path, &default_request)?;
+    let newer = compiler.compile_from_file(path, &spirv_1_5)?;
+
+    // The second header word holds the version as `0x00MMmm00`.
+    assert_eq!(default.spirv[1], 0x0001_0300);
+    assert_eq!(newer.spirv[1], 0x0001_0500);
+
+    Ok(())
+}
+
+#[cfg(feature = "spirv-validation")]
+#[test]
+fn validates_generated_spirv() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = Request {
+        validate_spirv: true,
+        ..sample_request(ShaderLang::Glsl)
+    };
+
+    let mut result = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+    result.validate(request.target_env)?;
+
+    // Dropping the trailing `OpFunctionEnd` leaves `main` unterminated.
+    assert_eq!(result.spirv.pop(), Some(0x0001_0038));
+    let err = result
+        .validate(request.target_env)
+        .expect_err("truncated module should fail validation");
+    assert!(matches!(err, BentoError::ShaderCompilation(message) if message.contains("SPIR-V")));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "spirv-validation"))]
+#[test]
+fn validation_requests_need_the_spirv_validation_feature() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = Request {
+        validate_spirv: true,
+        ..sample_request(ShaderLang::Glsl)
+    };
+
+    let err = compiler
+        .compile_from_file("tests/fixtures/simple_compute.glsl", &request)
+        .expect_err("validation is compiled out");
+    assert!(matches!(err, BentoError::InvalidInput(_)));
+
+    Ok(())
+}
+
+#[test]
+fn slang_module_imports_are_rejected() -> Result<(), BentoError> {
+    if !slang_rs::is_available() {
+        return Ok(());
+    }
+
+    let compiler = Compiler::new()?;
+    let shader = r#"
+import bindless;
+
+[numthreads(1, 1, 1)]
+void main() {}
+"#;
+
+    let err = compiler
+        .compile(shader.as_bytes(), &sample_request(ShaderLang::Slang))
+        .expect_err("Slang module imports are not supported");
+    assert!(matches!(err, BentoError::UnsupportedSlangImports));
+
+    Ok(())
+}
+
+#[test]
+fn slang_sources_report_unavailable_backend_without_native_library() -> Result<(), BentoError> {
+    if slang_rs::is_available() {
+        return Ok(());
+    }
+
+    let compiler = Compiler::new()?;
+    let shader = r#"
+[numthreads(1, 1, 1)]
+void main() {}
+"#;
+
+    let err = compiler
+        .compile(shader.as_bytes(), &sample_request(ShaderLang::Slang))
+        .expect_err("Slang needs the native library");
+    assert!(matches!(
+        err,
+        BentoError::BackendUnavailable { backend: "slang" }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn skips_binding_name_rewrite_when_disabled() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let path = "tests/fixtures/hlsl_binding_map.hlsl";
+    let rewritten = compiler.compile_from_file(path, &sample_request(ShaderLang::Hlsl))?;
+
+    let mut request = sample_request(ShaderLang::Hlsl);
+    request.rewrite_binding_names = false;
+    let shipping = compiler.compile_from_file(path, &request)?;
+
+    assert_eq!(op_name_count(&rewritten.spirv), rewritten.variables.len());
+    assert_eq!(op_name_count(&shipping.spirv), 0);
+    assert_eq!(shipping.variables, rewritten.variables);
+
+    Ok(())
+}
+
+#[test]
+fn reflects_spec_constant_sized_descriptor_arrays() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let result = compiler.compile_from_file("tests/fixtures/spec_sized_array.comp.glsl", &request)?;
+    let data = result
+        .variables
+        .iter()
+        .find(|var| var.set == 0 && var.kind.binding == 0)
+        .expect("storage array binding");
+
+    assert_eq!(data.kind.count, 6);
+    assert_eq!(
+        data.spec_count,
+        Some(SpecConstantCount {
+            constant_id: 2,
+            default: 6,
+        })
+    );
+
+    let mut unspecialized = data.clone();
+    unspecialized.spec_count = None;
+    assert_ne!(data.cmp(&unspecialized), std::cmp::Ordering::Equal);
+
+    let fixed = compiler.compile_from_file("tests/fixtures/simple_compute.glsl", &request)?;
+    assert!(fixed.variables.iter().all(|var| var.spec_count.is_none()));
+
+    Ok(())
+}
+
+#[test]
+fn reflects_unbounded_descriptor_arrays() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Fragment;
+
+    let result =
+        compiler.compile_from_file("tests/fixtures/unbounded_samplers.frag.glsl", &request)?;
+    let binding = |slot| {
+        result
+            .variables
+            .iter()
+            .find(|var| var.set == 0 && var.kind.binding == slot)
+            .expect("sampler array binding")
+    };
+
+    let unbounded = binding(0);
+    assert!(unbounded.unbounded);
+    assert_eq!(unbounded.kind.count, 0);
+
+    let fixed = binding(1);
+    assert!(!fixed.unbounded);
+    assert_eq!(fixed.kind.count, 4);
+
+    Ok(())
+}
+
+#[test]
+fn looks_up_bindings_and_inputs_by_name() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Vertex;
+    let shader = r#"
+#version 450
+layout(location = 0) in vec2 in_pos;
+layout(location = 2) in vec3 in_color;
+layout(location = 0) out vec3 v_color;
+
+layout(set = 1, binding = 2) uniform timing {
+    float current_time_ms;
+    float frame_time_ms;
+} meshi_timing;
+
+void main() {
+    v_color = in_color * meshi_timing.frame_time_ms;
+    gl_Position = vec4(in_pos, 0.0, 1.0);
+}
+"#;
+
+    let result = compiler.compile(shader.as_bytes(), &request)?;
+
+    let timing = result
+        .binding_by_name("meshi_timing")
+        .expect("meshi_timing binding");
+    assert_eq!((timing.set, timing.kind.binding), (1, 2));
+    assert!(result.binding_by_name("missing").is_none());
+
+    assert_eq!(result.location_of_input("in_pos"), Some(0));
+    assert_eq!(result.location_of_input("in_color"), Some(2));
+    assert_eq!(result.location_of_input("v_color"), None);
+
+    Ok(())
+}
+
+#[test]
+fn rejects_stage_conflicting_with_file_extension() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Vertex;
+
+    let err = compiler
+        .compile_from_file("shaders/quad.frag", &request)
+        .expect_err("a .frag file should not compile as a vertex shader");
+    assert!(matches!(err, BentoError::InvalidInput(message) if message.contains("Fragment")));
+
+    request.stage = dashi::ShaderType::Fragment;
+    compiler.compile_from_file("shaders/quad.frag", &request)?;
+
+    Ok(())
+}
+
+#[test]
+fn compiles_named_hlsl_entry_point() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Hlsl);
+    request.stage = dashi::ShaderType::Vertex;
+    request.entry_point = Some("VSMain".to_string());
+    let path = "tests/fixtures/hlsl_vsmain.hlsl";
+
+    let result = compiler.compile_from_file(path, &request)?;
+    assert_eq!(result.metadata.entry_points, vec!["VSMain".to_string()]);
+
+    request.entry_point = Some("PSMain".to_string());
+    let err = compiler
+        .compile_from_file(path, &request)
+        .expect_err("PSMain is not defined in the module");
+    assert!(matches!(err, BentoError::InvalidInput(message) if message.contains("PSMain")));
+
+    Ok(())
+}
+
+#[test]
+fn rejects_named_glsl_entry_point() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.entry_point = Some("compute_main".to_string());
+
+    let err = compiler
+        .compile_from_file("tests/fixtures/simple_compute.glsl", &request)
+        .expect_err("GLSL modules only define main");
+    assert!(matches!(err, BentoError::InvalidInput(message) if message.contains("compute_main")));
+
+    Ok(())
+}
+
+#[test]
+fn compiles_geometry_shader() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Geometry;
+
+    let result = compiler.compile_from_file("tests/fixtures/passthrough.geom.glsl", &request)?;
+    assert_eq!(result.stage, dashi::ShaderType::Geometry);
+    assert!(!result.spirv.is_empty());
+
+    request.stage = dashi::ShaderType::All;
+    let err = compiler
+        .compile_from_file("tests/fixtures/passthrough.geom.glsl", &request)
+        .expect_err("ShaderType::All has no shaderc stage");
+    assert!(matches!(err, BentoError::InvalidInput(_)));
+
+    Ok(())
+}
+
+#[test]
+fn cache_keys_artifacts_by_request_defines() -> Result<(), BentoError> {
+    let cache = tempfile::tempdir()?;
+    let compiler = Compiler::with_cache(cache.path().to_path_buf())?;
+    let path = "tests/fixtures/define_workgroup.glsl";
+    let artifacts =
+        || -> Result<usize, BentoError> { Ok(std::fs::read_dir(cache.path())?.count()) };
+
+    let mut request = sample_request(ShaderLang::Glsl);
+    request
+        .defines
+        .insert("WORKGROUP_SIZE".into(), Some("4".into()));
+    let first = compiler.compile_from_file(path, &request)?;
+    assert_eq!(artifacts()?, 1);
+
+    let cached = compiler.compile_from_file(path, &request)?;
+    assert_eq!(cached, first);
+    assert_eq!(artifacts()?, 1);
+
+    request
+        .defines
+        .insert("WORKGROUP_SIZE".into(), Some("8".into()));
+    let changed = compiler.compile_from_file(path, &request)?;
+    assert_eq!(artifacts()?, 2);
+    assert_eq!(changed.metadata.workgroup_size, Some([8, 1, 1]));
+
+    Ok(())
+}
+
+#[test]
+fn reports_combined_sampler_bindings() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Fragment;
+
+    let combined = r#"
+#version 450
+layout(set = 0, binding = 0) uniform sampler2D albedo;
+layout(location = 0) out vec4 color;
+void main() {
+    color = texture(albedo, vec2(0.5));
+}
+"#;
+    let separate = r#"
+#version 450
+layout(set = 0, binding = 0) uniform texture2D albedo;
+layout(set = 0, binding = 1) uniform sampler linear;
+layout(location = 0) out vec4 color;
+void main() {
+    color = texture(sampler2D(albedo, linear), vec2(0.5));
+}
+"#;
+
+    let result = compiler.compile(combined.as_bytes(), &request)?;
+    let names: Vec<&str> = result
+        .combined_sampler_bindings()
+        .iter()
+        .map(|var| var.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["albedo"]);
+
+    let result = compiler.compile(separate.as_bytes(), &request)?;
+    assert!(result.combined_sampler_bindings().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn reflects_push_constant_block_layout() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let mut request = sample_request(ShaderLang::Glsl);
+    request.stage = dashi::ShaderType::Vertex;
+
+    let result = compiler.compile_from_file("tests/fixtures/push_constants.glsl", &request)?;
+    let block = result
+        .metadata
+        .push_constants
+        .as_ref()
+        .expect("push constant block");
+
+    assert_eq!(block.size, 80);
+    let layout: Vec<(u32, Option<u32>)> = block
+        .members
+        .iter()
+        .map(|member| (member.offset, member.columns))
+        .collect();
+    assert_eq!(layout, vec![(0, Some(4)), (64, None)]);
+    assert_eq!(block.members[1].format, Some(dashi::ShaderPrimitiveType::Vec4));
+
+    let restored = bento::CompilationResult::from_bytes(&result.to_bytes()?)?;
+    assert_eq!(restored.metadata.push_constants, result.metadata.push_constants);
+
+    let plain = compiler.compile_from_file(
+        "tests/fixtures/simple_compute.glsl",
+        &sample_request(ShaderLang::Glsl),
+    )?;
+    assert!(plain.metadata.push_constants.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn packs_block_layout_under_std140_and_std430() -> Result<(), BentoError> {
+    let compiler = Compiler::new()?;
+    let request = sample_request(ShaderLang::Glsl);
+
+    let result = compiler.compile_from_file("tests/fixtures/block_layout.glsl", &request)?;
+    let block = result
+        .metadata
+        .push_constants
+        .as_ref()
+        .expect("push constant block");
+
+    // The vec3 after a float starts on a 16-byte boundary, and the float after it packs into
+    // the vec3's last four bytes.
+    assert_eq!(block.std140_padding(), Some(vec![0, 12, 0, 0]));
+    assert_eq!(block.std430_padding(), Some(vec![0, 12, 0, 0]));
+
+    // std140 rounds the mat2's column stride up to 16 bytes; std430 keeps it at 8.
+    assert_eq!(block.std140_size(), Some(64));
+    assert_eq!(block.std430_size(), Some(48));
+    // Push constants use std430, so the reflected size matches.
+    assert_eq!(block.std430_size(), Some(block.size));
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+#version 450
+layout(local_size_x = 64) in;
+
+layout(set = 0, binding = 0) buffer Counter {
+    uint visible;
+} counter;
+
+void main() {
+    atomicAdd(counter.visible, 1);
+}
@@ -0,0 +1,18 @@
+#version 450
+layout(local_size_x = 1) in;
+
+layout(push_constant) uniform LightConstants {
+    float scale;
+    vec3 position;
+    float radius;
+    mat2 rotation;
+} light;
+
+layout(set = 0, binding = 0) buffer Output {
+    vec4 values[];
+} result;
+
+void main() {
+    vec2 rotated = light.rotation * light.position.xy;
+    result.values[0] = vec4(rotated * light.scale, light.position.z, light.radius);
+}
@@ -0,0 +1,12 @@
+#version 450
+#extension GL_EXT_shader_explicit_arithmetic_types_float16 : require
+
+layout(location = 0) in vec3 a_position;
+layout(location = 1) in f16vec2 a_uv;
+
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    v_uv = vec2(a_uv);
+    gl_Position = vec4(a_position, 1.0);
+}
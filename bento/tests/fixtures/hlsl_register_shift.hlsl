@@ -0,0 +1,14 @@
+// t0, b0 and u0 all land on Vulkan binding 0 unless their register classes are shifted
+Texture2D<float4> colorTex : register(t0);
+cbuffer Params : register(b0)
+{
+    float4 tint;
+};
+RWStructuredBuffer<uint> outputData : register(u0);
+
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    float4 color = colorTex.Load(int3(0, 0, 0)) + tint;
+    outputData[id.x] = asuint(color.r);
+}
@@ -0,0 +1,12 @@
+// Vertex entry point named the way HLSL tooling usually does, instead of `main`
+struct VSOutput
+{
+    float4 position : SV_Position;
+};
+
+VSOutput VSMain(float3 position : POSITION)
+{
+    VSOutput output;
+    output.position = float4(position, 1.0);
+    return output;
+}
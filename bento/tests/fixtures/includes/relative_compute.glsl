@@ -0,0 +1,10 @@
+#version 450
+#extension GL_GOOGLE_include_directive : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+#include "shared_data.glsl"
+
+void main() {
+    uint idx = gl_GlobalInvocationID.x;
+    data.values[idx] = 1;
+}
@@ -0,0 +1,3 @@
+layout(set = 0, binding = 0) buffer Data {
+    uint values[];
+} data;
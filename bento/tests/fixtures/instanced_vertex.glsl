@@ -0,0 +1,8 @@
+#version 450
+layout(location = 0) in vec3 position;
+layout(location = 1) in float weight;
+layout(location = 2) in mat4 model;
+
+void main() {
+    gl_Position = model * vec4(position * weight, 1.0);
+}
@@ -0,0 +1,14 @@
+#version 450
+layout(location = 0) in vec3 position;
+
+layout(push_constant) uniform DrawConstants {
+    mat4 transform;
+    vec4 tint;
+} draw;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    gl_Position = draw.transform * vec4(position, 1.0);
+    color = draw.tint;
+}
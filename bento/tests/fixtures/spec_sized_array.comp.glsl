@@ -0,0 +1,14 @@
+#version 450
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(constant_id = 2) const int BUFFER_COUNT = 6;
+
+layout(set = 0, binding = 0) buffer Data {
+    uint values[];
+} data[BUFFER_COUNT];
+
+void main() {
+    for (int i = 0; i < BUFFER_COUNT; ++i) {
+        data[i].values[0] = uint(i);
+    }
+}
@@ -0,0 +1,14 @@
+#version 450
+
+// The literal sizes are the defaults of specialization constants 0 and 1.
+layout(local_size_x = 16, local_size_y = 4, local_size_z = 1) in;
+layout(local_size_x_id = 0, local_size_y_id = 1) in;
+
+layout(set = 0, binding = 0) buffer Output {
+    uint data[];
+} outputData;
+
+void main() {
+    uint idx = gl_GlobalInvocationID.y * gl_WorkGroupSize.x + gl_GlobalInvocationID.x;
+    outputData.data[idx] = gl_WorkGroupSize.x;
+}
@@ -0,0 +1,10 @@
+#version 450
+layout(local_size_x = 1) in;
+
+layout(set = 0, binding = 0) uniform samplerBuffer weights;
+layout(set = 0, binding = 1, r32f) uniform imageBuffer results;
+
+void main() {
+    int index = int(gl_GlobalInvocationID.x);
+    imageStore(results, index, texelFetch(weights, index) * 2.0);
+}
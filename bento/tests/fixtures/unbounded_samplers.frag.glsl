@@ -0,0 +1,13 @@
+#version 450
+#extension GL_EXT_nonuniform_qualifier : require
+
+layout(set = 0, binding = 0) uniform sampler2D textures[];
+layout(set = 0, binding = 1) uniform sampler2D fixed_textures[4];
+
+layout(location = 0) flat in uint texture_index;
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = texture(textures[nonuniformEXT(texture_index)], vec2(0.5))
+        + texture(fixed_textures[0], vec2(0.5));
+}
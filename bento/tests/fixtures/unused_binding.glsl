@@ -0,0 +1,18 @@
+#version 450
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(set = 0, binding = 0) uniform Used {
+    uint value;
+} used;
+
+layout(set = 0, binding = 1) uniform Unused {
+    uint value;
+} unused;
+
+layout(set = 0, binding = 2) buffer Output {
+    uint values[];
+} result;
+
+void main() {
+    result.values[gl_GlobalInvocationID.x] = used.value;
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "log")]
+
+use bento::{BentoError, Compiler, OptimizationLevel, Request, ShaderLang};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::Mutex;
+
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+#[test]
+fn compile_emits_debug_record() -> Result<(), BentoError> {
+    log::set_logger(&LOGGER).expect("install capturing logger");
+    log::set_max_level(LevelFilter::Debug);
+
+    let compiler = Compiler::new()?;
+    let request = Request {
+        name: Some("logged".to_string()),
+        lang: ShaderLang::Glsl,
+        stage: dashi::ShaderType::Compute,
+        optimization: OptimizationLevel::None,
+        ..Default::default()
+    };
+    compiler.compile(include_str!("fixtures/simple_compute.glsl").as_bytes(), &request)?;
+
+    let records = LOGGER.records.lock().unwrap();
+    assert!(
+        records
+            .iter()
+            .any(|(level, message)| *level == Level::Debug && message.contains("logged")),
+        "expected a debug record for the compile, got {records:?}"
+    );
+
+    Ok(())
+}
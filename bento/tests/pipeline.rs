@@ -14,15 +14,23 @@ fn fake_result(stage: dashi::ShaderType, name: &str) -> CompilationResult {
                 binding: 0,
                 count: 1,
             },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
         }],
         metadata: ShaderMetadata {
             entry_points: vec!["main".to_string()],
             inputs: Vec::new(),
             outputs: Vec::new(),
             workgroup_size: None,
+            workgroup_size_specializable: false,
             vertex: None,
+            may_discard: false,
+            uses_atomics: false,
+            push_constants: None,
         },
         spirv: vec![0x07230203],
+        warnings: Vec::new(),
     }
 }
 
@@ -49,6 +57,31 @@ fn builds_graphics_pipeline_with_required_fragment() {
     );
 }
 
+#[test]
+fn builds_graphics_pipeline_with_geometry_and_tessellation() {
+    let stages = vec![
+        fake_result(dashi::ShaderType::Vertex, "vertex"),
+        fake_result(dashi::ShaderType::TessellationControl, "tesc"),
+        fake_result(dashi::ShaderType::TessellationEvaluation, "tese"),
+        fake_result(dashi::ShaderType::Geometry, "geometry"),
+        fake_result(dashi::ShaderType::Fragment, "fragment"),
+    ];
+
+    let graphics = Pipeline::from_stages(stages).unwrap();
+    assert_eq!(graphics.kind(), bento::PipelineKind::Graphics);
+    assert_eq!(
+        graphics.geometry().unwrap().name.as_deref(),
+        Some("geometry")
+    );
+
+    let control_only = Pipeline::from_stages(vec![
+        fake_result(dashi::ShaderType::Vertex, "vertex"),
+        fake_result(dashi::ShaderType::TessellationControl, "tesc"),
+        fake_result(dashi::ShaderType::Fragment, "fragment"),
+    ]);
+    assert!(control_only.is_err());
+}
+
 #[test]
 fn rejects_invalid_stage_combinations() {
     let vertex = fake_result(dashi::ShaderType::Vertex, "vertex");
@@ -73,3 +106,44 @@ fn rejects_invalid_stage_combinations() {
     let no_stages = Pipeline::from_stages(Vec::<CompilationResult>::new());
     assert!(no_stages.is_err());
 }
+
+#[test]
+fn graphics_pipeline_reports_descriptor_sets_across_stages() {
+    let vertex = fake_result(dashi::ShaderType::Vertex, "vertex");
+    let mut fragment = fake_result(dashi::ShaderType::Fragment, "fragment");
+    fragment.variables[0].set = 2;
+    fragment.variables.push(ShaderVariable {
+        name: "extra".to_string(),
+        ..fragment.variables[0].clone()
+    });
+
+    let graphics = Pipeline::from_stages(vec![vertex, fragment]).unwrap();
+    assert_eq!(graphics.descriptor_sets(), vec![0, 2]);
+}
+
+#[test]
+fn reconstructs_graphics_pipeline_from_result_files() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let vertex_path = tmp_dir.path().join("vertex.bto");
+    let fragment_path = tmp_dir.path().join("fragment.bto");
+    let vertex_path = vertex_path.to_str().unwrap();
+    let fragment_path = fragment_path.to_str().unwrap();
+
+    fake_result(dashi::ShaderType::Vertex, "vertex")
+        .save_to_disk(vertex_path)
+        .unwrap();
+    fake_result(dashi::ShaderType::Fragment, "fragment")
+        .save_to_disk(fragment_path)
+        .unwrap();
+
+    let graphics = Pipeline::from_result_files(&[vertex_path, fragment_path]).unwrap();
+    assert_eq!(graphics.vertex().unwrap().name.as_deref(), Some("vertex"));
+    assert_eq!(graphics.fragment().unwrap().name.as_deref(), Some("fragment"));
+
+    let missing = tmp_dir.path().join("missing.bto");
+    let missing = missing.to_str().unwrap();
+    match Pipeline::from_result_files(&[vertex_path, missing]) {
+        Err(bento::BentoError::StageFile { path, .. }) => assert_eq!(path, missing),
+        other => panic!("expected stage file error, got {other:?}"),
+    }
+}
@@ -32,6 +32,7 @@ fn compiles_repository_shaders() -> Result<(), BentoError> {
             optimization: OptimizationLevel::Performance,
             debug_symbols: false,
             defines: HashMap::new(),
+            ..Default::default()
         };
 
         let shader_path = path.to_str().expect("Shader path should be valid UTF-8");
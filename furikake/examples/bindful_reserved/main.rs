@@ -61,6 +61,10 @@ fn main() {
 
     // Build a bind table from the reservation metadata.
     let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipe book");
+    if book.is_empty() {
+        println!("Shader uses no descriptor bindings; nothing to cook");
+        return;
+    }
     let mut bt_recipes = book.recipes();
     println!("Created {} bind table recipe(s)", bt_recipes.len());
 
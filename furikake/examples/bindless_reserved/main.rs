@@ -54,7 +54,7 @@ fn compile_shader() -> bento::CompilationResult {
             uint projection_kind;
             float _padding0;
         };
-        layout(set = 1, binding = 0) buffer Cameras {
+        layout(set = 0, binding = 1) buffer Cameras {
             Camera cameras[];
         } meshi_bindless_camera;
 
@@ -64,10 +64,10 @@ fn compile_shader() -> bento::CompilationResult {
             uint height;
             uint mip_levels;
         };
-        layout(set = 2, binding = 0) uniform texture2D meshi_bindless_textures[];
-        layout(set = 2, binding = 1) uniform sampler meshi_bindless_samplers[];
+        layout(set = 0, binding = 2) uniform texture2D meshi_bindless_textures[];
+        layout(set = 0, binding = 3) uniform sampler meshi_bindless_samplers[];
 
-        layout(set = 3, binding = 0) buffer Transformations {
+        layout(set = 0, binding = 4) buffer Transformations {
             mat4 transforms[];
         } meshi_bindless_transformations;
 
@@ -81,7 +81,7 @@ fn compile_shader() -> bento::CompilationResult {
             uint render_mask;
             uint _padding;
         };
-        layout(set = 4, binding = 0) buffer Materials {
+        layout(set = 0, binding = 5) buffer Materials {
             Material materials[];
         } meshi_bindless_materials;
 
@@ -175,11 +175,11 @@ fn main() {
     let mut texture_ids = [0u32; 5];
     state
         .reserved_mut::<ReservedBindlessTextures, _>("meshi_bindless_textures", |textures| {
-            texture_ids[0] = textures.add_texture(base_texture) as u32;
-            texture_ids[1] = textures.add_texture(normal_texture) as u32;
-            texture_ids[2] = textures.add_texture(roughness_texture) as u32;
-            texture_ids[3] = textures.add_texture(occlusion_texture) as u32;
-            texture_ids[4] = textures.add_texture(emissive_texture) as u32;
+            texture_ids[0] = textures.add_texture(base_texture).expect("texture slot") as u32;
+            texture_ids[1] = textures.add_texture(normal_texture).expect("texture slot") as u32;
+            texture_ids[2] = textures.add_texture(roughness_texture).expect("texture slot") as u32;
+            texture_ids[3] = textures.add_texture(occlusion_texture).expect("texture slot") as u32;
+            texture_ids[4] = textures.add_texture(emissive_texture).expect("texture slot") as u32;
         })
         .expect("add bindless textures");
 
@@ -292,8 +292,8 @@ fn main() {
     let mut updated_ids = (texture_ids[0], texture_ids[1]);
     state
         .reserved_mut::<ReservedBindlessTextures, _>("meshi_bindless_textures", |textures| {
-            updated_ids.0 = textures.add_texture(updated_base) as u32;
-            updated_ids.1 = textures.add_texture(updated_normal) as u32;
+            updated_ids.0 = textures.add_texture(updated_base).expect("texture slot") as u32;
+            updated_ids.1 = textures.add_texture(updated_normal).expect("texture slot") as u32;
         })
         .expect("allocate updated textures");
 
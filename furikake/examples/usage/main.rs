@@ -5,7 +5,7 @@ use furikake::recipe::RecipeBook;
 use furikake::*;
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct Vertex {
     position: [f32; 2],
     color: [f32; 3],
@@ -151,7 +151,7 @@ fn main() {
             byte_size: (std::mem::size_of::<Vertex>() * vertices.len()) as u32,
             visibility: MemoryVisibility::Gpu,
             usage: BufferUsage::VERTEX,
-            initial_data: unsafe { Some(vertices.align_to::<u8>().1) },
+            initial_data: Some(bytemuck::cast_slice(&vertices)),
         })
         .expect("create vertex buffer");
 
@@ -161,7 +161,7 @@ fn main() {
             byte_size: (std::mem::size_of::<u32>() * indices.len()) as u32,
             visibility: MemoryVisibility::Gpu,
             usage: BufferUsage::INDEX,
-            initial_data: unsafe { Some(indices.align_to::<u8>().1) },
+            initial_data: Some(bytemuck::cast_slice(&indices)),
         })
         .expect("create index buffer");
 
@@ -169,16 +169,11 @@ fn main() {
     let timing_bind_table = bind_tables[0].expect("timing bind table from recipe book");
 
     // Pipeline setup.
-    let vert_inputs = &shaders[0].metadata.inputs;
-    let position_location = vert_inputs
-        .iter()
-        .find(|var| var.name == "in_pos")
-        .and_then(|var| var.location)
+    let position_location = shaders[0]
+        .location_of_input("in_pos")
         .expect("vertex position location from metadata") as usize;
-    let color_location = vert_inputs
-        .iter()
-        .find(|var| var.name == "in_color")
-        .and_then(|var| var.location)
+    let color_location = shaders[0]
+        .location_of_input("in_color")
         .expect("vertex color location from metadata") as usize;
 
     let pipeline_layout = ctx
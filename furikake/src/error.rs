@@ -8,7 +8,12 @@ pub enum FurikakeError {
     BufferUnmapFailed { source: GPUError },
     MissingReservedBinding { name: String },
     ReservedItemTypeMismatch { name: String },
+    ReservedSetMismatch { name: String, expected: u32, found: u32 },
     ResolverReflection { source: String },
+    RecipeBindingCollision { set: u32, binding: u32 },
+    MultiSetReservation { name: String },
+    BindlessCapacityTooLarge { capacity: u32 },
+    BindlessTexturesFull,
 }
 
 impl FurikakeError {
@@ -36,9 +41,35 @@ impl fmt::Display for FurikakeError {
             FurikakeError::ReservedItemTypeMismatch { name } => {
                 write!(f, "reserved binding `{}` had the wrong type", name)
             }
+            FurikakeError::ReservedSetMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "reserved binding `{}` belongs in set {} but the shader declares it in set {}",
+                name, expected, found
+            ),
             FurikakeError::ResolverReflection { source } => {
                 write!(f, "failed to reflect resolver bindings: {}", source)
             }
+            FurikakeError::RecipeBindingCollision { set, binding } => {
+                write!(f, "set {} already has a recipe for binding {}", set, binding)
+            }
+            FurikakeError::MultiSetReservation { name } => write!(
+                f,
+                "reserved binding `{}` spans several sets and can't be bound as one table",
+                name
+            ),
+            FurikakeError::BindlessCapacityTooLarge { capacity } => write!(
+                f,
+                "bindless capacity {} exceeds the {} slots a texture id can address",
+                capacity,
+                u16::MAX
+            ),
+            FurikakeError::BindlessTexturesFull => {
+                write!(f, "no free bindless texture slots")
+            }
         }
     }
 }
@@ -50,7 +81,12 @@ impl Error for FurikakeError {
             | FurikakeError::BufferUnmapFailed { source } => Some(source),
             FurikakeError::ResolverReflection { .. }
             | FurikakeError::MissingReservedBinding { .. }
-            | FurikakeError::ReservedItemTypeMismatch { .. } => None,
+            | FurikakeError::ReservedItemTypeMismatch { .. }
+            | FurikakeError::ReservedSetMismatch { .. }
+            | FurikakeError::RecipeBindingCollision { .. }
+            | FurikakeError::MultiSetReservation { .. }
+            | FurikakeError::BindlessCapacityTooLarge { .. }
+            | FurikakeError::BindlessTexturesFull => None,
         }
     }
 }
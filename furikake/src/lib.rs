@@ -3,17 +3,20 @@ pub mod recipe;
 pub mod reservations;
 pub mod resolver;
 pub mod types;
+mod upload;
 
 use bento::builder::{BindTableUpdateTarget, CSO, CSOBuilder, PSO, PSOBuilder};
 use dashi::Handle;
+use dashi::builders::BindTableLayoutBuilder;
 use dashi::{
-    BindTableUpdateInfo, BindTableVariableType, CommandStream, Context, ImageView,
-    IndexedBindingInfo, IndexedResource, cmd::Executable,
+    BindTable, BindTableInfo, BindTableLayout, BindTableUpdateInfo, BindTableVariableType,
+    CommandStream, Context, ImageView, IndexedBindingInfo, IndexedResource, ShaderInfo,
+    cmd::Executable,
 };
 
 use error::FurikakeError;
 use reservations::{
-    ReservedItem, ReservedTiming,
+    ReservationConfig, ReservationSnapshot, ReservedItem, ReservedTiming, Retired,
     bindless_animation_keyframes::ReservedBindlessAnimationKeyframes,
     bindless_animation_tracks::ReservedBindlessAnimationTracks,
     bindless_animations::ReservedBindlessAnimations,
@@ -27,26 +30,36 @@ use reservations::{
     bindless_textures::{ReservedBindlessCubemaps, ReservedBindlessTextures},
     bindless_transformations::ReservedBindlessTransformations,
     bindless_vertices::ReservedBindlessVertices,
+    bone_palettes::ReservedBonePalettes,
+    instances::ReservedInstanceData,
+    light_clusters::ReservedLightClusters,
     particles::ReservedParticles,
     per_obj_joints::ReservedPerObjJoints,
 };
-use std::{collections::HashMap, ptr::NonNull};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    ptr::NonNull,
+};
 use tare::transient::BindlessTextureRegistry;
 use types::{
-    AnimationClip, AnimationKeyframe, AnimationState, AnimationTrack, JointTransform,
-    SkeletonHeader,
+    AnimationClip, AnimationKeyframe, AnimationState, AnimationTrack, Camera, JointTransform,
+    Material, SkeletonHeader, Transformation,
 };
 
 pub use resolver::*;
+pub use upload::upload_textures;
 
 pub struct ReservedMetadata {
     pub name: &'static str,
     pub kind: BindTableVariableType,
+    /// Descriptor set shaders must declare the binding in.
+    pub set: u32,
 }
 
 pub trait GPUState {
-    fn reserved_names() -> &'static [&'static str];
-    fn reserved_metadata() -> &'static [ReservedMetadata];
+    fn reserved_names(&self) -> &[&'static str];
+    fn reserved_metadata(&self) -> &[ReservedMetadata];
     fn binding(&self, key: &str) -> Result<&dyn ReservedItem, FurikakeError>;
 }
 
@@ -70,16 +83,18 @@ impl PSOBuilderFurikakeExt for PSOBuilder {
         state: &T,
         key: &str,
     ) -> Result<Self, FurikakeError> {
-        let reserved = state.binding(key)?.binding();
-        let reservations::ReservedBinding::TableBinding { resources, .. } = reserved;
-        Ok(self.add_table_variable_with_resources(key, resources))
+        let mut builder = self;
+        for (name, resources) in state.binding(key)?.binding().table_variables(key) {
+            builder = builder.add_table_variable_with_resources(&name, resources);
+        }
+        Ok(builder)
     }
 
     fn add_reserved_table_variables<T: GPUState>(
         mut self,
         state: &T,
     ) -> Result<Self, FurikakeError> {
-        for key in T::reserved_names() {
+        for key in state.reserved_names() {
             self = self.add_reserved_table_variable(state, key)?;
         }
         Ok(self)
@@ -92,16 +107,18 @@ impl PSOBuilderFurikakeExt for CSOBuilder {
         state: &T,
         key: &str,
     ) -> Result<Self, FurikakeError> {
-        let reserved = state.binding(key)?.binding();
-        let reservations::ReservedBinding::TableBinding { resources, .. } = reserved;
-        Ok(self.add_table_variable_with_resources(key, resources))
+        let mut builder = self;
+        for (name, resources) in state.binding(key)?.binding().table_variables(key) {
+            builder = builder.add_table_variable_with_resources(&name, resources);
+        }
+        Ok(builder)
     }
 
     fn add_reserved_table_variables<T: GPUState>(
         mut self,
         state: &T,
     ) -> Result<Self, FurikakeError> {
-        for key in T::reserved_names() {
+        for key in state.reserved_names() {
             self = self.add_reserved_table_variable(state, key)?;
         }
         Ok(self)
@@ -136,10 +153,46 @@ pub struct DefaultState {
     reserved: HashMap<String, Box<dyn ReservedItem>>,
 }
 
+/// State whose reservations are registered at runtime through `CustomStateBuilder`, for
+/// applications that bind their own uniforms and storage buffers alongside the built-in sets.
+#[derive(Default)]
+pub struct CustomState {
+    names: Vec<&'static str>,
+    metadata: Vec<ReservedMetadata>,
+    reserved: HashMap<String, Box<dyn ReservedItem>>,
+}
+
+#[derive(Default)]
+pub struct CustomStateBuilder {
+    state: CustomState,
+}
+
 pub struct BindlessState {
     ctx: NonNull<Context>,
     reserved: HashMap<String, Box<dyn ReservedItem>>,
     bind_table_subscriptions: HashMap<String, Vec<BindTableUpdateTarget>>,
+    reserved_tables: [Option<CachedReservedTable>; BINDLESS_STATE_NAMES.len()],
+    retired_layouts: Retired<Handle<BindTableLayout>>,
+    retired_tables: Retired<Handle<BindTable>>,
+}
+
+/// Host-side copy of a `BindlessState`'s scene data, produced by `export_state`.
+///
+/// Texture ids are recorded for reference only: images are GPU handles, so the caller must
+/// re-register them after `import_state`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct BindlessSnapshot {
+    pub cameras: ReservationSnapshot<Camera>,
+    pub transformations: ReservationSnapshot<Transformation>,
+    pub materials: ReservationSnapshot<Material>,
+    pub texture_ids: BTreeSet<u16>,
+}
+
+#[derive(Clone, Copy)]
+struct CachedReservedTable {
+    target: BindTableUpdateTarget,
+    layout: Handle<BindTableLayout>,
+    epoch: u64,
 }
 
 #[cfg(test)]
@@ -179,6 +232,151 @@ mod tests {
         assert!(mapped[0].frame_time_ms >= 1000.0);
     }
 
+    #[test]
+    fn shared_visibility_makes_reservations_host_mappable() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = BindlessState::with_config(
+            &mut ctx,
+            ReservationConfig {
+                visibility: MemoryVisibility::CpuAndGpu,
+            },
+        );
+
+        state.update().expect("update shared reservations");
+
+        let timing = state
+            .reserved::<ReservedTiming>("meshi_timing")
+            .expect("timing reference")
+            .buffer();
+        assert!(timing.is_shared());
+        assert_eq!(timing.host().handle, timing.device().handle);
+
+        // The shared buffer stays persistently mapped; read it through that mapping.
+        let mapped = timing.as_slice::<TimingData>();
+        assert!(!mapped.is_empty());
+        assert!(mapped[0].frame_time_ms >= 0.0);
+    }
+
+    #[test]
+    fn caches_reserved_tables_between_calls() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = BindlessState::new(&mut ctx);
+
+        let first = state.reserved_tables(&mut ctx).expect("cook reserved tables");
+        let second = state.reserved_tables(&mut ctx).expect("reuse reserved tables");
+
+        assert!(first.iter().all(|table| table.is_some()));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn retires_re_cooked_reserved_tables_after_frames_in_flight() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = BindlessState::new(&mut ctx);
+
+        let first = state.reserved_tables(&mut ctx).expect("cook reserved tables");
+        state.resize(4096, 0, 0).expect("grow cameras");
+        let second = state.reserved_tables(&mut ctx).expect("re-cook reserved tables");
+        assert_ne!(first[1], second[1]);
+        assert!(!state.retired_tables.is_empty());
+        assert!(!state.retired_layouts.is_empty());
+
+        for _ in 0..3 {
+            state.update().expect("update reservations");
+        }
+        assert!(state.retired_tables.is_empty());
+        assert!(state.retired_layouts.is_empty());
+    }
+
+    #[test]
+    fn records_one_stream_per_mutated_reservation() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = BindlessState::new(&mut ctx);
+
+        // Timing and vertices upload every frame; everything else only when mutated.
+        let baseline = state.record_updates().expect("record baseline updates");
+
+        state
+            .reserved_mut::<ReservedBindlessMaterials, _>("meshi_bindless_materials", |materials| {
+                let handle = materials.add_material();
+                materials.material_mut(handle).material_flags = 1;
+            })
+            .expect("mutate materials");
+        state
+            .reserved_mut::<ReservedBindlessLights, _>("meshi_bindless_lights", |lights| {
+                let handle = lights.add_light();
+                lights.light_mut(handle).extra.x = 1.0;
+            })
+            .expect("mutate lights");
+
+        let streams = state.record_updates().expect("record mutated updates");
+        assert_eq!(streams.len(), baseline.len() + 2);
+
+        let flushed = state.record_updates().expect("record flushed updates");
+        assert_eq!(flushed.len(), baseline.len());
+    }
+
+    #[test]
+    fn round_trips_exported_scene_state() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = BindlessState::new(&mut ctx);
+
+        let mut camera = None;
+        state
+            .reserved_mut::<ReservedBindlessCamera, _>("meshi_bindless_cameras", |cameras| {
+                let handle = cameras.add_camera();
+                cameras
+                    .camera_mut(handle)
+                    .set_position(glam::Vec3::new(1.0, 2.0, 3.0));
+                camera = Some(handle);
+            })
+            .expect("add camera");
+        let camera = camera.expect("camera handle");
+        let mut material = None;
+        state
+            .reserved_mut::<ReservedBindlessMaterials, _>("meshi_bindless_materials", |materials| {
+                let handle = materials.add_material();
+                materials.material_mut(handle).material_flags = 7;
+                material = Some(handle);
+            })
+            .expect("add material");
+        let material = material.expect("material handle");
+
+        let snapshot = state.export_state().expect("export snapshot");
+
+        state
+            .reserved_mut::<ReservedBindlessCamera, _>("meshi_bindless_cameras", |cameras| {
+                cameras.camera_mut(camera).set_position(glam::Vec3::ZERO);
+                cameras.remove_camera(camera);
+            })
+            .expect("clear camera");
+        state
+            .reserved_mut::<ReservedBindlessMaterials, _>("meshi_bindless_materials", |materials| {
+                materials.material_mut(material).material_flags = 0;
+                materials.remove_material(material);
+            })
+            .expect("clear material");
+
+        let json = serde_json::to_string(&snapshot).expect("serialize snapshot");
+        let snapshot: BindlessSnapshot = serde_json::from_str(&json).expect("parse snapshot");
+        state.import_state(&snapshot).expect("import snapshot");
+
+        let cameras = state
+            .reserved::<ReservedBindlessCamera>("meshi_bindless_cameras")
+            .expect("cameras");
+        assert_eq!(
+            cameras.camera(camera).position(),
+            glam::Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_ne!(cameras.snapshot().available.last(), Some(&camera.slot));
+
+        let materials = state
+            .reserved::<ReservedBindlessMaterials>("meshi_bindless_materials")
+            .expect("materials");
+        assert_eq!(materials.material(material).material_flags, 7);
+        assert_ne!(materials.snapshot().available.last(), Some(&material.slot));
+    }
+
     #[test]
     fn errors_on_type_mismatch() {
         let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
@@ -193,6 +391,85 @@ mod tests {
             other => panic!("expected type mismatch error, got {other:?}"),
         }
     }
+
+    #[test]
+    fn reserved_items_report_their_descriptor_type() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = BindlessState::new(&mut ctx);
+
+        let timing = state.binding("meshi_timing").expect("timing");
+        assert_eq!(timing.variable_type(), BindTableVariableType::Uniform);
+        let cameras = state.binding("meshi_bindless_cameras").expect("cameras");
+        assert_eq!(cameras.variable_type(), BindTableVariableType::Storage);
+
+        for meta in state.reserved_metadata() {
+            let item = state.binding(meta.name).expect("reserved item");
+            assert_eq!(item.variable_type(), meta.kind, "{}", meta.name);
+        }
+    }
+
+    #[test]
+    fn resolves_shaders_against_custom_reservations() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = CustomState::builder()
+            .register(
+                ReservedMetadata {
+                    name: "scene_params",
+                    kind: BindTableVariableType::Uniform,
+                    set: 1,
+                },
+                ReservedTiming::new(&mut ctx),
+            )
+            .build();
+
+        assert_eq!(state.reserved_names(), ["scene_params"]);
+        state.update().expect("update custom state");
+        state
+            .reserved_mut::<ReservedTiming, _>("scene_params", |timing| {
+                timing.set_last_time(Instant::now());
+            })
+            .expect("mutate custom reservation");
+
+        let shader = bento::CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: dashi::ShaderType::Compute,
+            variables: vec![bento::ShaderVariable {
+                name: "scene_params".to_string(),
+                set: 1,
+                kind: dashi::BindTableVariable {
+                    var_type: BindTableVariableType::Uniform,
+                    binding: 3,
+                    count: 1,
+                },
+                spec_count: None,
+                unbounded: false,
+                texel_buffer: None,
+            }],
+            metadata: bento::ShaderMetadata {
+                entry_points: Vec::new(),
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                workgroup_size: None,
+                workgroup_size_specializable: false,
+                vertex: Default::default(),
+                may_discard: false,
+                uses_atomics: false,
+                push_constants: None,
+            },
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let resolver = Resolver::new(&state, &shader).expect("resolve custom reservation");
+        let resolved = resolver.resolved();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].name, "scene_params");
+        assert!(resolved[0].exists);
+        assert_eq!(resolved[0].set, 1);
+        assert_eq!(resolved[0].binding.binding, 3);
+    }
 }
 
 ///////////////////////////////////////////////////////////
@@ -205,14 +482,15 @@ const DEFAULT_STATE_NAMES: [&str; 1] = ["meshi_timing"];
 const DEFAULT_METADATA: [ReservedMetadata; 1] = [ReservedMetadata {
     name: "meshi_timing",
     kind: BindTableVariableType::Uniform,
+    set: 0,
 }];
 
 impl GPUState for DefaultState {
-    fn reserved_names() -> &'static [&'static str] {
+    fn reserved_names(&self) -> &[&'static str] {
         DEFAULT_STATE_NAMES.as_slice()
     }
 
-    fn reserved_metadata() -> &'static [ReservedMetadata] {
+    fn reserved_metadata(&self) -> &[ReservedMetadata] {
         DEFAULT_METADATA.as_slice()
     }
 
@@ -296,7 +574,117 @@ impl DefaultState {
 ///////////////////////////////////////////////////////////
 ///
 
-const BINDLESS_STATE_NAMES: [&str; 18] = [
+impl GPUState for CustomState {
+    fn reserved_names(&self) -> &[&'static str] {
+        self.names.as_slice()
+    }
+
+    fn reserved_metadata(&self) -> &[ReservedMetadata] {
+        self.metadata.as_slice()
+    }
+
+    fn binding(&self, key: &str) -> Result<&dyn ReservedItem, FurikakeError> {
+        <CustomState>::binding(self, key)
+    }
+}
+
+impl CustomStateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `item` under `metadata.name`. Registering a name twice replaces the earlier
+    /// item and metadata.
+    pub fn register(
+        mut self,
+        metadata: ReservedMetadata,
+        item: impl ReservedItem + 'static,
+    ) -> Self {
+        let state = &mut self.state;
+        if let Some(index) = state.names.iter().position(|name| *name == metadata.name) {
+            state.names.remove(index);
+            state.metadata.remove(index);
+        }
+
+        state.names.push(metadata.name);
+        state.reserved.insert(metadata.name.to_string(), Box::new(item));
+        state.metadata.push(metadata);
+        self
+    }
+
+    pub fn build(self) -> CustomState {
+        self.state
+    }
+}
+
+impl CustomState {
+    pub fn builder() -> CustomStateBuilder {
+        CustomStateBuilder::new()
+    }
+
+    pub fn binding(&self, key: &str) -> Result<&dyn ReservedItem, FurikakeError> {
+        if let Some(b) = self.reserved.get(key) {
+            return Ok(b.as_ref());
+        }
+
+        Err(FurikakeError::MissingReservedBinding {
+            name: key.to_string(),
+        })
+    }
+
+    pub fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        let mut cmd = CommandStream::new().begin();
+        for iter in &mut self.reserved {
+            cmd = cmd.combine(iter.1.update()?);
+        }
+        Ok(cmd.end())
+    }
+
+    pub fn reserved_mut<T: 'static, F: FnOnce(&mut T)>(
+        &mut self,
+        key: &str,
+        mutate: F,
+    ) -> Result<(), FurikakeError> {
+        let item = self
+            .reserved
+            .get_mut(key)
+            .ok_or(FurikakeError::MissingReservedBinding {
+                name: key.to_string(),
+            })?;
+
+        let typed = item.as_any_mut().downcast_mut::<T>().ok_or(
+            FurikakeError::ReservedItemTypeMismatch {
+                name: key.to_string(),
+            },
+        )?;
+
+        mutate(typed);
+        Ok(())
+    }
+
+    pub fn reserved<T: 'static>(&self, key: &str) -> Result<&T, FurikakeError> {
+        let item = self
+            .reserved
+            .get(key)
+            .ok_or(FurikakeError::MissingReservedBinding {
+                name: key.to_string(),
+            })?;
+
+        item.as_any()
+            .downcast_ref::<T>()
+            .ok_or(FurikakeError::ReservedItemTypeMismatch {
+                name: key.to_string(),
+            })
+    }
+}
+
+///////////////////////////////////////////////////////////
+///////////////////////////////////////////////////////////
+///////////////////////////////////////////////////////////
+///////////////////////////////////////////////////////////
+///
+
+const BINDLESS_STATE_NAMES: [&str; 21] = [
     "meshi_timing",
     "meshi_bindless_cameras",
     "meshi_bindless_textures",
@@ -315,88 +703,124 @@ const BINDLESS_STATE_NAMES: [&str; 18] = [
     "meshi_bindless_indices",
     "meshi_particles",
     "meshi_per_obj_joints",
+    "meshi_instances",
+    "meshi_bone_palettes",
+    "meshi_light_clusters",
 ];
-const BINDLESS_METADATA: [ReservedMetadata; 18] = [
+const BINDLESS_METADATA: [ReservedMetadata; 21] = [
     ReservedMetadata {
         name: "meshi_timing",
         kind: BindTableVariableType::Uniform,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_cameras",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_textures",
         kind: BindTableVariableType::Image,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_cubemaps",
         kind: BindTableVariableType::Image,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_samplers",
         kind: BindTableVariableType::Sampler,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_transformations",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_materials",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_lights",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_skeletons",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_joints",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_animations",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_animation_tracks",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_animation_keyframes",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_skinning",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_vertices",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_bindless_indices",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_particles",
         kind: BindTableVariableType::Storage,
+        set: 0,
     },
     ReservedMetadata {
         name: "meshi_per_obj_joints",
         kind: BindTableVariableType::Storage,
+        set: 0,
+    },
+    ReservedMetadata {
+        name: "meshi_instances",
+        kind: BindTableVariableType::Storage,
+        set: 0,
+    },
+    ReservedMetadata {
+        name: "meshi_bone_palettes",
+        kind: BindTableVariableType::Storage,
+        set: 0,
+    },
+    ReservedMetadata {
+        name: "meshi_light_clusters",
+        kind: BindTableVariableType::Storage,
+        set: 0,
     },
 ];
 
 impl GPUState for BindlessState {
-    fn reserved_names() -> &'static [&'static str] {
+    fn reserved_names(&self) -> &[&'static str] {
         BINDLESS_STATE_NAMES.as_slice()
     }
 
-    fn reserved_metadata() -> &'static [ReservedMetadata] {
+    fn reserved_metadata(&self) -> &[ReservedMetadata] {
         BINDLESS_METADATA.as_slice()
     }
 
@@ -407,13 +831,21 @@ impl GPUState for BindlessState {
 
 impl BindlessState {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, ReservationConfig::default())
+    }
+
+    /// Creates the state with every reservation's buffers allocated according to `config`.
+    pub fn with_config(ctx: &mut Context, config: ReservationConfig) -> Self {
         let mut reserved: HashMap<String, Box<dyn ReservedItem>> = HashMap::new();
 
         let names = BINDLESS_STATE_NAMES;
-        reserved.insert(names[0].to_string(), Box::new(ReservedTiming::new(ctx)));
+        reserved.insert(
+            names[0].to_string(),
+            Box::new(ReservedTiming::with_config(ctx, &config)),
+        );
         reserved.insert(
             names[1].to_string(),
-            Box::new(ReservedBindlessCamera::new(ctx)),
+            Box::new(ReservedBindlessCamera::with_config(ctx, &config)),
         );
         let textures = ReservedBindlessTextures::new(ctx);
         let cubemaps = textures.cubemaps();
@@ -423,58 +855,76 @@ impl BindlessState {
         reserved.insert(names[4].to_string(), Box::new(samplers));
         reserved.insert(
             names[5].to_string(),
-            Box::new(ReservedBindlessTransformations::new(ctx)),
+            Box::new(ReservedBindlessTransformations::with_config(ctx, &config)),
         );
         reserved.insert(
             names[6].to_string(),
-            Box::new(ReservedBindlessMaterials::new(ctx)),
+            Box::new(ReservedBindlessMaterials::with_config(ctx, &config)),
         );
         reserved.insert(
             names[7].to_string(),
-            Box::new(ReservedBindlessLights::new(ctx)),
+            Box::new(ReservedBindlessLights::with_config(ctx, &config)),
         );
         reserved.insert(
             names[8].to_string(),
-            Box::new(ReservedBindlessSkeletons::new(ctx)),
+            Box::new(ReservedBindlessSkeletons::with_config(ctx, &config)),
         );
         reserved.insert(
             names[9].to_string(),
-            Box::new(ReservedBindlessJoints::new(ctx)),
+            Box::new(ReservedBindlessJoints::with_config(ctx, &config)),
         );
         reserved.insert(
             names[10].to_string(),
-            Box::new(ReservedBindlessAnimations::new(ctx)),
+            Box::new(ReservedBindlessAnimations::with_config(ctx, &config)),
         );
         reserved.insert(
             names[11].to_string(),
-            Box::new(ReservedBindlessAnimationTracks::new(ctx)),
+            Box::new(ReservedBindlessAnimationTracks::with_config(ctx, &config)),
         );
         reserved.insert(
             names[12].to_string(),
-            Box::new(ReservedBindlessAnimationKeyframes::new(ctx)),
+            Box::new(ReservedBindlessAnimationKeyframes::with_config(ctx, &config)),
         );
         reserved.insert(
             names[13].to_string(),
-            Box::new(ReservedBindlessSkinning::new(ctx)),
+            Box::new(ReservedBindlessSkinning::with_config(ctx, &config)),
         );
         reserved.insert(
             names[14].to_string(),
-            Box::new(ReservedBindlessVertices::new(ctx)),
+            Box::new(ReservedBindlessVertices::with_config(ctx, &config)),
         );
         reserved.insert(
             names[15].to_string(),
-            Box::new(ReservedBindlessIndices::new(ctx)),
+            Box::new(ReservedBindlessIndices::with_config(ctx, &config)),
+        );
+        reserved.insert(
+            names[16].to_string(),
+            Box::new(ReservedParticles::with_config(ctx, &config)),
         );
-        reserved.insert(names[16].to_string(), Box::new(ReservedParticles::new(ctx)));
         reserved.insert(
             names[17].to_string(),
-            Box::new(ReservedPerObjJoints::new(ctx)),
+            Box::new(ReservedPerObjJoints::with_config(ctx, &config)),
+        );
+        reserved.insert(
+            names[18].to_string(),
+            Box::new(ReservedInstanceData::with_config(ctx, &config)),
+        );
+        reserved.insert(
+            names[19].to_string(),
+            Box::new(ReservedBonePalettes::with_config(ctx, &config)),
+        );
+        reserved.insert(
+            names[20].to_string(),
+            Box::new(ReservedLightClusters::with_config(ctx, &config)),
         );
 
         Self {
             reserved,
             ctx: NonNull::from_ref(ctx),
             bind_table_subscriptions: HashMap::new(),
+            reserved_tables: [None; BINDLESS_STATE_NAMES.len()],
+            retired_layouts: Retired::default(),
+            retired_tables: Retired::default(),
         }
     }
 
@@ -489,6 +939,7 @@ impl BindlessState {
     }
 
     pub fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        self.advance_retired();
         let mut cmd = CommandStream::new().begin();
         for iter in &mut self.reserved {
             cmd = cmd.combine(iter.1.update()?);
@@ -496,12 +947,84 @@ impl BindlessState {
         Ok(cmd.end())
     }
 
+    /// Records the uploads of every reservation with pending changes, one stream per
+    /// reservation, without submitting them.
+    ///
+    /// The caller appends the streams to its own command buffer so all reservation uploads go
+    /// out in one submission.
+    pub fn record_updates(&mut self) -> Result<Vec<CommandStream<Executable>>, FurikakeError> {
+        self.advance_retired();
+        let mut streams = Vec::new();
+        for item in self.reserved.values_mut() {
+            if item.has_pending_update() {
+                streams.push(item.update()?);
+            }
+        }
+        Ok(streams)
+    }
+
+    /// Grows the camera, transformation and material buffers to hold at least the given number
+    /// of elements, keeping their contents and handles. Registered PSO/CSO tables are pointed at
+    /// the new buffers right away; tables from `reserved_tables` are re-cooked through the
+    /// reservations' epochs.
+    pub fn resize(
+        &mut self,
+        cameras: usize,
+        transformations: usize,
+        materials: usize,
+    ) -> Result<(), FurikakeError> {
+        let names = BINDLESS_STATE_NAMES;
+        self.reserved_mut::<ReservedBindlessCamera, _>(names[1], |reserved| {
+            reserved.grow(cameras)
+        })?;
+        self.reserved_mut::<ReservedBindlessTransformations, _>(names[5], |reserved| {
+            reserved.grow(transformations)
+        })?;
+        self.reserved_mut::<ReservedBindlessMaterials, _>(names[6], |reserved| {
+            reserved.grow(materials)
+        })
+    }
+
+    /// Copies the camera, transformation and material arrays and the live texture ids.
+    pub fn export_state(&self) -> Result<BindlessSnapshot, FurikakeError> {
+        let names = BINDLESS_STATE_NAMES;
+        Ok(BindlessSnapshot {
+            cameras: self
+                .reserved::<ReservedBindlessCamera>(names[1])?
+                .snapshot(),
+            transformations: self
+                .reserved::<ReservedBindlessTransformations>(names[5])?
+                .snapshot(),
+            materials: self
+                .reserved::<ReservedBindlessMaterials>(names[6])?
+                .snapshot(),
+            texture_ids: self
+                .reserved::<ReservedBindlessTextures>(names[2])?
+                .texture_ids(),
+        })
+    }
+
+    /// Restores the arrays captured by `export_state`; they upload on the next `update`.
+    /// Textures are not restored and must be registered again by the caller.
+    pub fn import_state(&mut self, snapshot: &BindlessSnapshot) -> Result<(), FurikakeError> {
+        let names = BINDLESS_STATE_NAMES;
+        self.reserved_mut::<ReservedBindlessCamera, _>(names[1], |cameras| {
+            cameras.restore(&snapshot.cameras)
+        })?;
+        self.reserved_mut::<ReservedBindlessTransformations, _>(names[5], |transforms| {
+            transforms.restore(&snapshot.transformations)
+        })?;
+        self.reserved_mut::<ReservedBindlessMaterials, _>(names[6], |materials| {
+            materials.restore(&snapshot.materials)
+        })
+    }
+
     pub fn register_pso_tables(&mut self, pso: &PSO) {
         if pso.ctx.as_ptr() != self.ctx.as_ptr() {
             return;
         }
 
-        for key in Self::reserved_names() {
+        for key in BINDLESS_STATE_NAMES {
             if let Some(target) = pso.table_binding(key) {
                 self.register_table_target(key, target);
             }
@@ -515,7 +1038,7 @@ impl BindlessState {
             return;
         }
 
-        for key in Self::reserved_names() {
+        for key in BINDLESS_STATE_NAMES {
             if let Some(target) = cso.table_binding(key) {
                 self.register_table_target(key, target);
             }
@@ -524,6 +1047,82 @@ impl BindlessState {
         self.backfill_bindless_textures();
     }
 
+    /// Returns one bind table per reservation, indexed like `reserved_names()`.
+    ///
+    /// Tables are cooked on the first call and cached on the state; later calls hand back the
+    /// cached handles and only re-cook reservations whose epoch changed since they were cooked.
+    /// Cached tables are subscribed to slot updates like any registered PSO/CSO table. Re-cooked
+    /// tables and their layouts are destroyed once no frame in flight can still use them.
+    pub fn reserved_tables(
+        &mut self,
+        ctx: &mut Context,
+    ) -> Result<[Option<Handle<BindTable>>; BINDLESS_STATE_NAMES.len()], FurikakeError> {
+        for (index, meta) in BINDLESS_METADATA.iter().enumerate() {
+            let epoch = self.binding(meta.name)?.epoch();
+            if let Some(cached) = self.reserved_tables[index] {
+                if cached.epoch == epoch {
+                    continue;
+                }
+
+                self.unregister_table(meta.name, cached.target);
+                self.retired_tables.retire(cached.target.table);
+                self.retired_layouts.retire(cached.layout);
+            }
+
+            let item = self.binding(meta.name)?;
+            let (binding, resources) = item.binding().single().ok_or_else(|| {
+                FurikakeError::MultiSetReservation {
+                    name: meta.name.to_string(),
+                }
+            })?;
+            let variables = [reservations::reserved_layout_variable(item, meta.kind)?];
+
+            let layout = BindTableLayoutBuilder::new("[FURIKAKE] Reserved BTL")
+                .shader(ShaderInfo {
+                    shader_type: dashi::ShaderType::All,
+                    variables: &variables,
+                })
+                .build(ctx)
+                .map_err(FurikakeError::from)?;
+
+            let table = ctx
+                .make_bind_table(&BindTableInfo {
+                    debug_name: "[FURIKAKE] Reserved Bind Table",
+                    layout,
+                    bindings: &[IndexedBindingInfo {
+                        resources: &resources,
+                        binding,
+                    }],
+                    set: 0,
+                })
+                .map_err(FurikakeError::from)?;
+
+            let target = BindTableUpdateTarget {
+                table,
+                binding,
+                size: variables[0].count,
+            };
+            self.register_table_target(meta.name, target);
+            self.reserved_tables[index] = Some(CachedReservedTable {
+                target,
+                layout,
+                epoch,
+            });
+        }
+
+        Ok(self
+            .reserved_tables
+            .map(|cached| cached.map(|cached| cached.target.table)))
+    }
+
+    // Ages re-cooked reserved tables by one frame; `update` and `record_updates` run once per
+    // frame.
+    fn advance_retired(&mut self) {
+        let ctx = unsafe { self.ctx.as_mut() };
+        self.retired_tables.advance(ctx);
+        self.retired_layouts.advance(ctx);
+    }
+
     pub fn unregister_table(&mut self, key: &str, target: BindTableUpdateTarget) {
         let Some(targets) = self.bind_table_subscriptions.get_mut(key) else {
             return;
@@ -604,6 +1203,7 @@ impl BindlessState {
                 name: key.to_string(),
             })?;
 
+        let epoch = item.epoch();
         let typed = item.as_any_mut().downcast_mut::<T>().ok_or(
             FurikakeError::ReservedItemTypeMismatch {
                 name: key.to_string(),
@@ -611,6 +1211,22 @@ impl BindlessState {
         )?;
 
         mutate(typed);
+        if item.epoch() != epoch {
+            self.rebind_subscriptions(key)?;
+        }
+        Ok(())
+    }
+
+    // Points every table subscribed to `key` at the reservation's current buffer. Runs as soon as
+    // a mutation swaps the buffer, while the old one is still waiting out frames in flight.
+    fn rebind_subscriptions(&mut self, key: &str) -> Result<(), FurikakeError> {
+        let Some((_, resources)) = self.binding(key)?.binding().single() else {
+            return Ok(());
+        };
+
+        for resource in &resources {
+            self.update_tables(key, resource);
+        }
         Ok(())
     }
 
@@ -631,15 +1247,16 @@ impl BindlessState {
 }
 
 impl BindlessTextureRegistry for BindlessState {
-    fn add_texture(&mut self, view: ImageView) -> u16 {
+    fn add_texture(&mut self, view: ImageView) -> Option<u16> {
         let mut id = None;
         let mut image_resource = None;
         let mut sampler_resource = None;
         self.reserved_mut::<ReservedBindlessTextures, _>("meshi_bindless_textures", |textures| {
-            let next_id = textures.add_texture(view);
-            image_resource = textures.image_resource(next_id);
-            sampler_resource = textures.sampler_resource(next_id);
-            id = Some(next_id);
+            id = textures.add_texture(view);
+            if let Some(next_id) = id {
+                image_resource = textures.image_resource(next_id);
+                sampler_resource = textures.sampler_resource(next_id);
+            }
         })
         .expect("register bindless texture in furikake");
 
@@ -651,7 +1268,7 @@ impl BindlessTextureRegistry for BindlessState {
             self.update_tables("meshi_bindless_samplers", resource);
         }
 
-        id.expect("bindless texture id")
+        id
     }
 
     fn remove_texture(&mut self, id: u16) {
@@ -672,13 +1289,12 @@ impl BindlessTextureRegistry for BindlessState {
             self.update_tables("meshi_bindless_samplers", resource);
         }
     }
-    fn add_cubemap(&mut self, view: ImageView) -> u16 {
+    fn add_cubemap(&mut self, view: ImageView) -> Option<u16> {
         let mut id = None;
         let mut image_resource = None;
         self.reserved_mut::<ReservedBindlessCubemaps, _>("meshi_bindless_cubemaps", |cubemaps| {
-            let next_id = cubemaps.add_texture(view);
-            image_resource = cubemaps.image_resource(next_id);
-            id = Some(next_id);
+            id = cubemaps.add_texture(view);
+            image_resource = id.and_then(|next_id| cubemaps.image_resource(next_id));
         })
         .expect("register bindless cubemap in furikake");
 
@@ -686,7 +1302,7 @@ impl BindlessTextureRegistry for BindlessState {
             self.update_tables("meshi_bindless_cubemaps", resource);
         }
 
-        id.expect("bindless cubemap id")
+        id
     }
 
     fn remove_cubemap(&mut self, id: u16) {
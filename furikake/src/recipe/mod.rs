@@ -5,8 +5,14 @@ use dashi::{
     IndexedBindingInfo, IndexedResource, ShaderInfo,
 };
 use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
-use crate::{GPUState, error::FurikakeError, reservations::ReservedBinding, resolver::Resolver};
+use crate::{
+    GPUState,
+    error::FurikakeError,
+    reservations::{ReservedBinding, ReservedItem, Retired},
+    resolver::Resolver,
+};
 
 #[derive(Debug, Clone)]
 pub struct IndexedBindingRecipe {
@@ -20,8 +26,107 @@ pub struct BindTableRecipe {
     pub layout: Handle<BindTableLayout>,
 }
 
+type LayoutKey = Vec<(dashi::ShaderType, Vec<BindTableVariable>)>;
+
+// Bind table layouts shared by every recipe book built on one context, keyed by their sorted
+// contents.
+//
+// The cache owns its layouts and counts the recipes using each, so a layout one book replaces
+// stays valid for every other book using it. Layouts no recipe uses anymore, and tables books
+// replace on `refresh`, are destroyed a few `RecipeBook::advance_layout_cache` calls later, once
+// frames in flight are done with them.
+#[derive(Default)]
+struct LayoutCache {
+    layouts: Vec<(LayoutKey, Handle<BindTableLayout>, usize)>,
+    retired_layouts: Retired<Handle<BindTableLayout>>,
+    retired_tables: Retired<Handle<BindTable>>,
+}
+
+// Runs `f` on the layout cache of the context at `ctx`, creating it on first use. Caches are
+// keyed by the context's address, so `RecipeBook::clear_layout_cache` must run before a context
+// is dropped.
+fn with_layout_cache<R>(
+    ctx: &mut Context,
+    f: impl FnOnce(&mut LayoutCache, &mut Context) -> R,
+) -> R {
+    static CACHES: OnceLock<Mutex<Vec<(usize, LayoutCache)>>> = OnceLock::new();
+    let mut caches = CACHES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let address = ctx as *const Context as usize;
+    let index = match caches.iter().position(|(cached, _)| *cached == address) {
+        Some(index) => index,
+        None => {
+            caches.push((address, LayoutCache::default()));
+            caches.len() - 1
+        }
+    };
+    f(&mut caches[index].1, ctx)
+}
+
+impl LayoutCache {
+    fn destroy(mut self, ctx: &mut Context) {
+        for (_, layout, _) in self.layouts.drain(..) {
+            ctx.destroy_bind_table_layout(layout);
+        }
+        self.retired_layouts.destroy_all(ctx);
+        self.retired_tables.destroy_all(ctx);
+    }
+
+    // Drops one recipe's use of `layout`, retiring it once no recipe uses it.
+    fn release(&mut self, layout: Handle<BindTableLayout>) {
+        let Some(index) = self.layouts.iter().position(|(_, cached, _)| *cached == layout) else {
+            return;
+        };
+
+        let users = &mut self.layouts[index].2;
+        *users = users.saturating_sub(1);
+        if *users == 0 {
+            let (_, layout, _) = self.layouts.remove(index);
+            self.retired_layouts.retire(layout);
+        }
+    }
+
+    fn get_or_build(
+        &mut self,
+        ctx: &mut Context,
+        shader_vars: Option<&Vec<(dashi::ShaderType, Vec<BindTableVariable>)>>,
+    ) -> Result<Handle<BindTableLayout>, FurikakeError> {
+        let mut key: LayoutKey = shader_vars.cloned().unwrap_or_default();
+        for (_, vars) in &mut key {
+            vars.sort_by_key(|var| var.binding);
+        }
+        key.sort_by_key(|(stage, _)| *stage as u32);
+
+        let cached = self.layouts.iter_mut().find(|(cached, ..)| *cached == key);
+        if let Some((_, layout, users)) = cached {
+            *users += 1;
+            return Ok(*layout);
+        }
+
+        let mut builder = BindTableLayoutBuilder::new("[FURIKAKE] Recipe BTL");
+        for (stage, vars) in &key {
+            builder = builder.shader(ShaderInfo {
+                shader_type: *stage,
+                variables: vars.as_slice(),
+            });
+        }
+
+        let layout = builder.build(ctx).map_err(FurikakeError::from)?;
+        self.layouts.push((key, layout, 1));
+        Ok(layout)
+    }
+}
+
 pub struct RecipeBook {
     recipes: Vec<BindTableRecipe>,
+    layout_vars: HashMap<u32, Vec<(dashi::ShaderType, Vec<BindTableVariable>)>>,
+    /// Epoch of every reservation the recipes snapshot, as of the last build or refresh.
+    epochs: HashMap<String, u64>,
+    /// Table `refresh` last cooked for each set, retired when a later refresh replaces it.
+    refreshed: HashMap<u32, Handle<BindTable>>,
 }
 
 impl BindTableRecipe {
@@ -56,21 +161,80 @@ impl BindTableRecipe {
         .map_err(FurikakeError::from)
     }
 }
+// Resources backing the shader variable `name` and the descriptor count its binding declares.
+// A multi-set reservation only backs its own slot names, each sized by its own resources.
+fn reserved_slot(
+    item: &dyn ReservedItem,
+    name: &str,
+) -> Result<(Vec<IndexedResource>, u32), FurikakeError> {
+    match item.binding() {
+        ReservedBinding::TableBinding { resources, .. } => Ok((resources, item.descriptor_count())),
+        ReservedBinding::MultiTableBinding(slots) => slots
+            .into_iter()
+            .find(|slot| slot.name == name)
+            .map(|slot| {
+                let count = slot.resources.len() as u32;
+                (slot.resources, count)
+            })
+            .ok_or_else(|| FurikakeError::MissingReservedBinding {
+                name: name.to_string(),
+            }),
+    }
+}
+
 impl RecipeBook {
+    /// Builds a book from `shaders`. Sets whose layout contents match a layout already built on
+    /// `ctx` reuse that layout; see `clear_layout_cache`.
     pub fn new<T: GPUState>(
         ctx: &mut Context,
         state: &T,
         shaders: &[CompilationResult],
+    ) -> Result<Self, FurikakeError> {
+        let shaders: Vec<&CompilationResult> = shaders.iter().collect();
+        Self::build(ctx, state, &shaders, true)
+    }
+
+    /// Builds a book from shaders the caller already resolved against `state`, skipping the
+    /// `Resolver` pass `new` runs for every shader.
+    ///
+    /// The resolvers are trusted as-is; pairing a shader with a resolver for a different shader
+    /// or state is not detected here and surfaces when the tables are cooked or bound.
+    pub fn from_resolved<T: GPUState>(
+        ctx: &mut Context,
+        state: &T,
+        resolved: &[(&CompilationResult, &Resolver)],
+    ) -> Result<Self, FurikakeError> {
+        let shaders: Vec<&CompilationResult> = resolved.iter().map(|(shader, _)| *shader).collect();
+        Self::build(ctx, state, &shaders, false)
+    }
+
+    fn build<T: GPUState>(
+        ctx: &mut Context,
+        state: &T,
+        shaders: &[&CompilationResult],
+        resolve: bool,
     ) -> Result<Self, FurikakeError> {
         let mut table_layout_vars: HashMap<u32, Vec<(dashi::ShaderType, Vec<BindTableVariable>)>> =
             HashMap::new();
         let mut table_recipes: HashMap<u32, HashMap<String, IndexedBindingRecipe>> = HashMap::new();
+        let mut epochs = HashMap::new();
 
         for shader in shaders {
-            Resolver::new(state, shader)?;
+            // A shader without bindings has nothing to resolve and contributes no recipes.
+            if resolve && !shader.variables.is_empty() {
+                Resolver::new(state, shader)?;
+            }
 
             for var in &shader.variables {
-                let reserved = state.binding(&var.name)?.binding();
+                let item = state.binding(&var.name)?;
+                let (resources, count) = reserved_slot(item, &var.name)?;
+                epochs.insert(var.name.clone(), item.epoch());
+
+                // Runtime-sized arrays take their length from the reservation backing them.
+                let mut var = var.clone();
+                if var.unbounded {
+                    var.kind.count = count;
+                }
                 let shader_vars = table_layout_vars.entry(var.set).or_default();
 
                 if let Some((_stage, vars)) = shader_vars
@@ -82,15 +246,13 @@ impl RecipeBook {
                     shader_vars.push((shader.stage, vec![var.kind.clone()]));
                 }
 
-                let ReservedBinding::TableBinding { resources, .. } = reserved;
-
                 table_recipes
                     .entry(var.set)
                     .or_default()
                     .entry(var.name.clone())
                     .or_insert_with(|| IndexedBindingRecipe {
                         bindings: Some(resources),
-                        var: var.clone(),
+                        var,
                     });
             }
         }
@@ -100,21 +262,8 @@ impl RecipeBook {
         let mut bt_sets: Vec<u32> = table_recipes.keys().copied().collect();
         bt_sets.sort_unstable();
         for set in bt_sets {
-            let mut shader_vars = table_layout_vars.remove(&set).unwrap_or_default();
-            let shader_info: Vec<ShaderInfo<'_>> = shader_vars
-                .iter_mut()
-                .map(|(stage, vars)| ShaderInfo {
-                    shader_type: *stage,
-                    variables: vars.as_slice(),
-                })
-                .collect();
-
-            let mut builder = BindTableLayoutBuilder::new("[FURIKAKE] Recipe BTL");
-            for info in shader_info {
-                builder = builder.shader(info);
-            }
-
-            let layout = builder.build(ctx).map_err(FurikakeError::from)?;
+            let vars = table_layout_vars.get(&set);
+            let layout = with_layout_cache(ctx, |layouts, ctx| layouts.get_or_build(ctx, vars))?;
 
             let mut bindings: Vec<IndexedBindingRecipe> = table_recipes
                 .remove(&set)
@@ -123,29 +272,214 @@ impl RecipeBook {
                 .collect();
             bindings.sort_by_key(|b| b.var.kind.binding);
 
+            #[cfg(feature = "log")]
+            log::debug!(
+                target: "furikake",
+                "built bind table layout for set {set} ({} bindings)",
+                bindings.len()
+            );
             recipes.push(BindTableRecipe { bindings, layout });
         }
 
         Ok(Self {
             recipes: recipes.into_iter().collect(),
+            layout_vars: table_layout_vars,
+            epochs,
+            refreshed: HashMap::new(),
         })
     }
 
+    /// Whether any reservation the recipes snapshot changed shape since the book was built or
+    /// last refreshed, so cooking now would bind outdated resources.
+    pub fn is_stale<T: GPUState>(&self, state: &T) -> bool {
+        self.epochs
+            .iter()
+            .any(|(name, epoch)| !matches!(state.binding(name), Ok(item) if item.epoch() == *epoch))
+    }
+
+    /// Re-snapshots every reservation whose epoch moved and re-cooks the recipes that use them.
+    ///
+    /// Returns the freshly cooked table for each affected set, in set order; untouched sets keep
+    /// whatever tables were cooked from them before. Sets with a runtime-sized array whose
+    /// reservation changed length get a new layout, and the old one is released to the context's
+    /// layout cache. The book owns the tables it returns: each is retired once a later refresh
+    /// replaces it. Tables the caller cooked from `recipes` stay theirs; hand replaced ones to
+    /// `retire_table`. Bindings added with `merge_binding` aren't tracked and are never
+    /// refreshed.
+    pub fn refresh<T: GPUState>(
+        &mut self,
+        ctx: &mut Context,
+        state: &T,
+    ) -> Result<Vec<(u32, Handle<BindTable>)>, FurikakeError> {
+        let mut cooked = Vec::new();
+        for recipe in &mut self.recipes {
+            let mut changed = false;
+            let mut resized = false;
+            for binding in &mut recipe.bindings {
+                let Some(epoch) = self.epochs.get(&binding.var.name) else {
+                    continue;
+                };
+                let item = state.binding(&binding.var.name)?;
+                if item.epoch() == *epoch {
+                    continue;
+                }
+
+                let (resources, count) = reserved_slot(item, &binding.var.name)?;
+                binding.bindings = Some(resources);
+                changed = true;
+
+                if binding.var.unbounded && binding.var.kind.count != count {
+                    binding.var.kind.count = count;
+                    resized = true;
+                    let stages = self.layout_vars.entry(binding.var.set).or_default();
+                    for kind in stages.iter_mut().flat_map(|(_, vars)| vars.iter_mut()) {
+                        if kind.binding == binding.var.kind.binding {
+                            kind.count = count;
+                        }
+                    }
+                }
+            }
+
+            if changed {
+                let set = recipe.bindings.first().map(|b| b.var.set).unwrap_or_default();
+                if resized {
+                    let vars = self.layout_vars.get(&set);
+                    let previous = recipe.layout;
+                    recipe.layout = with_layout_cache(ctx, |layouts, ctx| {
+                        let layout = layouts.get_or_build(ctx, vars)?;
+                        layouts.release(previous);
+                        Ok::<_, FurikakeError>(layout)
+                    })?;
+                }
+                let table = recipe.clone().cook(ctx)?;
+                if let Some(previous) = self.refreshed.insert(set, table) {
+                    Self::retire_table(ctx, previous);
+                }
+                cooked.push((set, table));
+            }
+        }
+
+        // A reservation can feed several sets, so epochs only advance once every set saw it.
+        for (name, epoch) in &mut self.epochs {
+            *epoch = state.binding(name)?.epoch();
+        }
+
+        Ok(cooked)
+    }
+
+    /// Adds an externally built binding to the recipe for `set` and rebuilds that set's layout.
+    /// The set's previous layout is released to the context's layout cache.
+    ///
+    /// Merged bindings are visible to all shader stages. If the book has no recipe for `set` yet,
+    /// a new one is created. Fails if the set already has a recipe for the same binding number.
+    pub fn merge_binding(
+        &mut self,
+        set: u32,
+        mut recipe: IndexedBindingRecipe,
+        ctx: &mut Context,
+    ) -> Result<(), FurikakeError> {
+        recipe.var.set = set;
+        let binding = recipe.var.kind.binding;
+
+        let existing = self
+            .recipes
+            .iter()
+            .position(|r| r.bindings.first().map(|b| b.var.set) == Some(set));
+
+        if let Some(index) = existing {
+            if self.recipes[index]
+                .bindings
+                .iter()
+                .any(|b| b.var.kind.binding == binding)
+            {
+                return Err(FurikakeError::RecipeBindingCollision { set, binding });
+            }
+        }
+
+        let mut shader_vars = self.layout_vars.get(&set).cloned().unwrap_or_default();
+        if let Some((_stage, vars)) = shader_vars
+            .iter_mut()
+            .find(|(stage, _)| *stage == dashi::ShaderType::All)
+        {
+            vars.push(recipe.var.kind.clone());
+        } else {
+            shader_vars.push((dashi::ShaderType::All, vec![recipe.var.kind.clone()]));
+        }
+
+        let layout =
+            with_layout_cache(ctx, |layouts, ctx| layouts.get_or_build(ctx, Some(&shader_vars)))?;
+        self.layout_vars.insert(set, shader_vars);
+
+        match existing {
+            Some(index) => {
+                let table = &mut self.recipes[index];
+                table.bindings.push(recipe);
+                table.bindings.sort_by_key(|b| b.var.kind.binding);
+                let previous = std::mem::replace(&mut table.layout, layout);
+                with_layout_cache(ctx, |layouts, _| layouts.release(previous));
+            }
+            None => {
+                self.recipes.push(BindTableRecipe {
+                    bindings: vec![recipe],
+                    layout,
+                });
+                self.recipes
+                    .sort_by_key(|r| r.bindings.first().map(|b| b.var.set).unwrap_or_default());
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn recipes(&self) -> Vec<BindTableRecipe> {
         self.recipes.clone()
     }
+
+    /// Whether the book holds no recipes, as when every shader it was built from has no
+    /// descriptor bindings.
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+
+    /// Ages the layouts and tables books on `ctx` replaced by one frame and destroys those no
+    /// frame in flight can still use. Call once per frame.
+    pub fn advance_layout_cache(ctx: &mut Context) {
+        with_layout_cache(ctx, |layouts, ctx| {
+            layouts.retired_layouts.advance(ctx);
+            layouts.retired_tables.advance(ctx);
+        });
+    }
+
+    /// Hands `table` over to be destroyed once frames in flight are done with it, e.g. one the
+    /// caller cooked from a recipe `refresh` has since re-cooked.
+    pub fn retire_table(ctx: &mut Context, table: Handle<BindTable>) {
+        with_layout_cache(ctx, |layouts, _| layouts.retired_tables.retire(table));
+    }
+
+    /// Destroys every layout cached for `ctx` and everything still waiting to retire.
+    ///
+    /// Layouts are shared between books built on the same context, so call this only once no
+    /// book built on `ctx` will be cooked again, and before the context itself is dropped.
+    pub fn clear_layout_cache(ctx: &mut Context) {
+        let cache = with_layout_cache(ctx, |layouts, _| std::mem::take(layouts));
+        cache.destroy(ctx);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::reservations::{ReservedBinding, ReservedItem};
+    use crate::reservations::ReservedTableBinding;
     use crate::{DefaultState, GPUState, ReservedMetadata};
     use dashi::cmd::Executable;
     use dashi::{
         BindTableVariableType, BufferInfo, BufferView, CommandStream, ContextInfo, MemoryVisibility, ShaderResource, ShaderType
     };
 
+    fn cached_layouts(ctx: &mut Context) -> usize {
+        with_layout_cache(ctx, |layouts, _| layouts.layouts.len())
+    }
+
     fn make_shader_variable(
         name: &str,
         set: u32,
@@ -160,6 +494,9 @@ mod tests {
                 binding,
                 count: 1,
             },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
         }
     }
 
@@ -169,7 +506,11 @@ mod tests {
             inputs: Vec::new(),
             outputs: Vec::new(),
             workgroup_size: None,
+            workgroup_size_specializable: false,
             vertex: Default::default(),
+            may_discard: false,
+            uses_atomics: false,
+            push_constants: None,
         }
     }
 
@@ -191,6 +532,7 @@ mod tests {
             )],
             metadata: empty_metadata(),
             spirv: Vec::new(),
+            warnings: Vec::new(),
         };
 
         let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
@@ -201,6 +543,136 @@ mod tests {
         let mut recipe = recipes.pop().unwrap();
         let handle = recipe.cook(&mut ctx).expect("cook bind table");
         assert!(handle.valid());
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn builds_empty_book_from_shader_without_bindings() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = DefaultState::new(&mut ctx);
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Compute,
+            variables: Vec::new(),
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
+        assert!(book.recipes().is_empty());
+        assert!(book.is_empty());
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn merges_binding_into_existing_set_and_cooks() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = DefaultState::new(&mut ctx);
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Vertex,
+            variables: vec![make_shader_variable(
+                "meshi_timing",
+                0,
+                BindTableVariableType::Uniform,
+                0,
+            )],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let mut book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
+        let extra = BindlessItem::new(&mut ctx, 0);
+        book.merge_binding(
+            0,
+            IndexedBindingRecipe {
+                bindings: Some(extra.resources.clone()),
+                var: make_shader_variable("engine_global", 0, BindTableVariableType::Storage, 1),
+            },
+            &mut ctx,
+        )
+        .expect("merge binding");
+
+        let mut recipes = book.recipes();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].bindings.len(), 2);
+
+        let collision = book.merge_binding(
+            0,
+            IndexedBindingRecipe {
+                bindings: Some(extra.resources.clone()),
+                var: make_shader_variable("engine_global", 0, BindTableVariableType::Storage, 1),
+            },
+            &mut ctx,
+        );
+        assert!(matches!(
+            collision,
+            Err(FurikakeError::RecipeBindingCollision { set: 0, binding: 1 })
+        ));
+
+        let mut recipe = recipes.pop().unwrap();
+        let handle = recipe.cook(&mut ctx).expect("cook bind table");
+        assert!(handle.valid());
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn from_resolved_matches_new() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = DefaultState::new(&mut ctx);
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Vertex,
+            variables: vec![make_shader_variable(
+                "meshi_timing",
+                0,
+                BindTableVariableType::Uniform,
+                0,
+            )],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let resolver = Resolver::new(&state, &shader).expect("resolve shader");
+
+        let summarize = |book: &RecipeBook| -> Vec<Vec<(bento::ShaderVariable, Option<usize>)>> {
+            book.recipes()
+                .iter()
+                .map(|recipe| {
+                    recipe
+                        .bindings
+                        .iter()
+                        .map(|b| (b.var.clone(), b.bindings.as_ref().map(Vec::len)))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let reflected = RecipeBook::new(&mut ctx, &state, std::slice::from_ref(&shader))
+            .expect("build recipes");
+        let resolved = RecipeBook::from_resolved(&mut ctx, &state, &[(&shader, &resolver)])
+            .expect("build recipes from resolved shaders");
+
+        assert_eq!(summarize(&resolved), summarize(&reflected));
+
+        let mut recipe = resolved.recipes().pop().unwrap();
+        assert!(recipe.cook(&mut ctx).expect("cook bind table").valid());
+
+        RecipeBook::clear_layout_cache(&mut ctx);
     }
 
     struct BindlessItem {
@@ -243,6 +715,10 @@ mod tests {
             }
         }
 
+        fn variable_type(&self) -> BindTableVariableType {
+            BindTableVariableType::Storage
+        }
+
         fn as_any(&self) -> &dyn std::any::Any {
             self
         }
@@ -265,14 +741,15 @@ mod tests {
     }
 
     impl GPUState for BindlessState {
-        fn reserved_names() -> &'static [&'static str] {
+        fn reserved_names(&self) -> &[&'static str] {
             &["bindless_test"]
         }
 
-        fn reserved_metadata() -> &'static [ReservedMetadata] {
+        fn reserved_metadata(&self) -> &[ReservedMetadata] {
             &[ReservedMetadata {
                 name: "bindless_test",
                 kind: BindTableVariableType::Storage,
+                set: 0,
             }]
         }
 
@@ -299,6 +776,7 @@ mod tests {
             )],
             metadata: empty_metadata(),
             spirv: Vec::new(),
+            warnings: Vec::new(),
         };
 
         let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
@@ -309,5 +787,345 @@ mod tests {
         let mut recipe = recipes.pop().unwrap();
         let handle = recipe.cook(&mut ctx).expect("cook bind table");
         assert!(handle.valid());
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn refreshes_recipes_after_reservation_grows() {
+        use crate::reservations::bindless_textures::ReservedBindlessTextures;
+
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut state = crate::BindlessState::new(&mut ctx);
+        let name = "meshi_bindless_textures";
+
+        // Declare room for one extension block so the grown reservation still fits the layout.
+        let initial = state.binding(name).expect("textures").descriptor_count();
+        let mut textures = make_shader_variable(name, 0, BindTableVariableType::Image, 0);
+        textures.kind.count = initial + 128;
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Fragment,
+            variables: vec![textures],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let mut book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
+        assert!(!book.is_stale(&state));
+
+        let image = ctx
+            .make_image(&dashi::ImageInfo {
+                debug_name: "recipe_refresh_texture",
+                dim: [1, 1, 1],
+                format: dashi::Format::RGBA8,
+                initial_data: Some(&[0, 0, 255, 255]),
+                ..Default::default()
+            })
+            .expect("create texture");
+        let view = dashi::ImageView {
+            img: image,
+            ..Default::default()
+        };
+        state
+            .reserved_mut::<ReservedBindlessTextures, _>(name, |textures| {
+                for _ in 0..=initial {
+                    textures.add_texture(view);
+                }
+            })
+            .expect("extend textures");
+        assert!(book.is_stale(&state));
+
+        let cooked = book.refresh(&mut ctx, &state).expect("refresh recipes");
+        assert_eq!(cooked.len(), 1);
+        assert_eq!(cooked[0].0, 0);
+        assert!(cooked[0].1.valid());
+        assert!(!book.is_stale(&state));
+
+        let refreshed = book.recipes();
+        let resources = refreshed[0].bindings[0].bindings.as_ref().expect("snapshot");
+        assert!(resources.len() > initial as usize);
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn sizes_unbounded_arrays_from_reservation() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = crate::BindlessState::new(&mut ctx);
+        let name = "meshi_bindless_textures";
+
+        let mut textures = make_shader_variable(name, 0, BindTableVariableType::Image, 0);
+        textures.kind.count = 0;
+        textures.unbounded = true;
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Fragment,
+            variables: vec![textures],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
+        let expected = state.binding(name).expect("textures").descriptor_count();
+        assert!(expected > 0);
+        assert_eq!(book.recipes()[0].bindings[0].var.kind.count, expected);
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    struct SplitItem {
+        slots: Vec<ReservedTableBinding>,
+    }
+
+    impl ReservedItem for SplitItem {
+        fn name(&self) -> String {
+            "split_test".to_string()
+        }
+
+        fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+            Ok(CommandStream::new().begin().end())
+        }
+
+        fn binding(&self) -> ReservedBinding {
+            ReservedBinding::MultiTableBinding(self.slots.clone())
+        }
+
+        fn variable_type(&self) -> BindTableVariableType {
+            BindTableVariableType::Storage
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+            self
+        }
+    }
+
+    struct SplitState {
+        item: SplitItem,
+    }
+
+    impl GPUState for SplitState {
+        fn reserved_names(&self) -> &[&'static str] {
+            &["split_test"]
+        }
+
+        fn reserved_metadata(&self) -> &[ReservedMetadata] {
+            &[ReservedMetadata {
+                name: "split_test",
+                kind: BindTableVariableType::Storage,
+                set: 0,
+            }]
+        }
+
+        fn binding(&self, _key: &str) -> Result<&dyn ReservedItem, FurikakeError> {
+            Ok(&self.item)
+        }
+    }
+
+    #[test]
+    fn fans_multi_set_reservations_out_into_per_set_recipes() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let first = BindlessItem::new(&mut ctx, 0);
+        let mut second = BindlessItem::new(&mut ctx, 0);
+        second.resources.extend(BindlessItem::new(&mut ctx, 1).resources);
+        let state = SplitState {
+            item: SplitItem {
+                slots: vec![
+                    ReservedTableBinding {
+                        name: "split_first".to_string(),
+                        set: 0,
+                        binding: 0,
+                        resources: first.resources,
+                    },
+                    ReservedTableBinding {
+                        name: "split_second".to_string(),
+                        set: 1,
+                        binding: 2,
+                        resources: second.resources,
+                    },
+                ],
+            },
+        };
+
+        let mut in_set_one =
+            make_shader_variable("split_second", 1, BindTableVariableType::Storage, 2);
+        in_set_one.kind.count = 2;
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Compute,
+            variables: vec![
+                make_shader_variable("split_first", 0, BindTableVariableType::Storage, 0),
+                in_set_one,
+            ],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let book = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build recipes");
+        let recipes = book.recipes();
+        assert_eq!(recipes.len(), 2);
+
+        let slots: Vec<(u32, u32, usize)> = recipes
+            .iter()
+            .flat_map(|recipe| &recipe.bindings)
+            .map(|b| (b.var.set, b.var.kind.binding, b.bindings.as_ref().map_or(0, Vec::len)))
+            .collect();
+        assert_eq!(slots, vec![(0, 0, 1), (1, 2, 2)]);
+
+        for mut recipe in recipes {
+            assert!(recipe.cook(&mut ctx).expect("cook bind table").valid());
+        }
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn rejects_multi_set_slots_declared_in_the_wrong_set() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let first = BindlessItem::new(&mut ctx, 0);
+        let second = BindlessItem::new(&mut ctx, 1);
+        let state = SplitState {
+            item: SplitItem {
+                slots: vec![
+                    ReservedTableBinding {
+                        name: "split_first".to_string(),
+                        set: 0,
+                        binding: 0,
+                        resources: first.resources,
+                    },
+                    ReservedTableBinding {
+                        name: "split_second".to_string(),
+                        set: 1,
+                        binding: 2,
+                        resources: second.resources,
+                    },
+                ],
+            },
+        };
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Compute,
+            variables: vec![
+                make_shader_variable("split_first", 0, BindTableVariableType::Storage, 0),
+                make_shader_variable("split_second", 0, BindTableVariableType::Storage, 2),
+            ],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let err = RecipeBook::new(&mut ctx, &state, &[shader])
+            .err()
+            .expect("slot in the wrong set");
+        assert!(matches!(
+            err,
+            FurikakeError::ReservedSetMismatch { ref name, expected: 1, found: 0 }
+                if name == "split_second"
+        ));
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+    }
+
+    #[test]
+    fn reuses_layouts_across_books_built_from_the_same_shader() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = DefaultState::new(&mut ctx);
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Vertex,
+            variables: vec![make_shader_variable(
+                "meshi_timing",
+                0,
+                BindTableVariableType::Uniform,
+                0,
+            )],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let first = RecipeBook::new(&mut ctx, &state, std::slice::from_ref(&shader))
+            .expect("build first book");
+        let second = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build second book");
+        assert_eq!(first.recipes()[0].layout, second.recipes()[0].layout);
+        assert_eq!(cached_layouts(&mut ctx), 1);
+
+        RecipeBook::clear_layout_cache(&mut ctx);
+        assert_eq!(cached_layouts(&mut ctx), 0);
+    }
+
+    #[test]
+    fn retires_layouts_once_no_book_uses_them() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let state = DefaultState::new(&mut ctx);
+
+        let shader = CompilationResult {
+            name: None,
+            file: None,
+            lang: bento::ShaderLang::Glsl,
+            stage: ShaderType::Vertex,
+            variables: vec![make_shader_variable(
+                "meshi_timing",
+                0,
+                BindTableVariableType::Uniform,
+                0,
+            )],
+            metadata: empty_metadata(),
+            spirv: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let mut first = RecipeBook::new(&mut ctx, &state, std::slice::from_ref(&shader))
+            .expect("build first book");
+        let mut second = RecipeBook::new(&mut ctx, &state, &[shader]).expect("build second book");
+        let shared = second.recipes()[0].layout;
+
+        let extra = BindlessItem::new(&mut ctx, 0);
+        let merged = || IndexedBindingRecipe {
+            bindings: Some(extra.resources.clone()),
+            var: make_shader_variable("engine_global", 0, BindTableVariableType::Storage, 1),
+        };
+        first
+            .merge_binding(0, merged(), &mut ctx)
+            .expect("merge into first book");
+        // The second book still uses the original layout.
+        assert_eq!(cached_layouts(&mut ctx), 2);
+        assert_eq!(second.recipes()[0].layout, shared);
+
+        second
+            .merge_binding(0, merged(), &mut ctx)
+            .expect("merge into second book");
+        assert_eq!(cached_layouts(&mut ctx), 1);
+        assert_eq!(first.recipes()[0].layout, second.recipes()[0].layout);
+        let retiring = |ctx: &mut Context| {
+            with_layout_cache(ctx, |layouts, _| !layouts.retired_layouts.is_empty())
+        };
+        assert!(retiring(&mut ctx));
+
+        for _ in 0..3 {
+            RecipeBook::advance_layout_cache(&mut ctx);
+        }
+        assert!(!retiring(&mut ctx));
+
+        RecipeBook::clear_layout_cache(&mut ctx);
     }
 }
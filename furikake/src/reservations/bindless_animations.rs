@@ -3,14 +3,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, CommandStream, Context, Handle, IndexedBindingInfo,
-    IndexedResource, ShaderResource,
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::AnimationClip};
 
-use super::{table_binding_from_indexed, DirtyRange, ReservedBinding, ReservedItem};
+use super::{
+    table_binding_from_indexed, DirtyRange, ReservationConfig, ReservedBinding, ReservedItem,
+};
 
 pub struct ReservedBindlessAnimations {
     ctx: NonNull<Context>,
@@ -21,10 +23,14 @@ pub struct ReservedBindlessAnimations {
 
 impl ReservedBindlessAnimations {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_CLIPS: usize = 2048;
 
         let available_clips: Vec<u16> = (0..START_CLIPS as u16).collect();
-        let clips = StagedBuffer::new(
+        let clips = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Animation Clip Buffer",
@@ -98,6 +104,14 @@ impl ReservedItem for ReservedBindlessAnimations {
         })
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -3,28 +3,37 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    BufferInfo, BufferUsage, BufferView, CommandStream, Context, Handle, IndexedBindingInfo,
-    IndexedResource, ShaderResource, cmd::Executable,
+    BindTableVariableType, BufferInfo, BufferUsage, BufferView, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource, cmd::Executable,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::Camera};
 
-use super::{DirtyRange, ReservedBinding, ReservedItem, table_binding_from_indexed};
+use super::{
+    DirtyRange, ReservationConfig, ReservationSnapshot, ReservedBinding, ReservedItem,
+    RetiredBuffers, grow_slots, table_binding_from_indexed,
+};
 
 pub struct ReservedBindlessCamera {
     ctx: NonNull<Context>,
     data: StagedBuffer,
     available: Vec<u16>,
     dirty: DirtyRange,
+    retired: RetiredBuffers,
+    epoch: u64,
 }
 
 impl ReservedBindlessCamera {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_SIZE: usize = 2048;
 
         let available: Vec<u16> = (0..START_SIZE as u16).collect();
-        let data = StagedBuffer::new(
+        let data = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Camera Buffer",
@@ -40,16 +49,31 @@ impl ReservedBindlessCamera {
             data,
             available,
             dirty: DirtyRange::default(),
+            retired: RetiredBuffers::default(),
+            epoch: 0,
         }
     }
 
+    /// Frees `camera`'s slot for the next `add_camera` and resets it to `Camera::default()`.
+    /// Invalid or already freed handles are ignored.
     pub fn remove_camera(&mut self, camera: Handle<Camera>) {
-        if camera.valid() {
-            self.available.push(camera.slot);
+        let slots = self.data.as_slice::<Camera>().len();
+        if !camera.valid()
+            || camera.slot as usize >= slots
+            || self.available.contains(&camera.slot)
+        {
+            return;
         }
+
+        *self.camera_mut(camera) = Camera::default();
+        self.available.push(camera.slot);
     }
 
     pub fn add_camera(&mut self) -> Handle<Camera> {
+        if self.available.is_empty() {
+            self.grow(self.capacity() * 2);
+        }
+
         if let Some(id) = self.available.pop() {
             return Handle::new(id, 0);
         }
@@ -57,6 +81,27 @@ impl ReservedBindlessCamera {
         return Handle::new(0, 0);
     }
 
+    /// Number of slots the buffers currently hold.
+    pub fn capacity(&self) -> usize {
+        self.data.as_slice::<Camera>().len()
+    }
+
+    /// Reallocates the buffers with room for `capacity` cameras, keeping contents and
+    /// handles; the old buffers are destroyed once frames in flight stop reading them. See
+    /// `grow_slots`. `add_camera` grows by doubling when it runs out of slots.
+    pub fn grow(&mut self, capacity: usize) {
+        grow_slots::<Camera>(
+            unsafe { self.ctx.as_mut() },
+            "[FURIKAKE] Camera Buffer",
+            capacity,
+            &mut self.data,
+            &mut self.available,
+            &mut self.dirty,
+            &mut self.retired,
+            &mut self.epoch,
+        );
+    }
+
     pub fn push_camera(&mut self, camera: Camera) -> Handle<Camera> {
         let handle = self.add_camera();
         if handle.valid() {
@@ -73,6 +118,17 @@ impl ReservedBindlessCamera {
         self.dirty.mark_elements::<Camera>(handle.slot as usize, 1);
         &mut self.data.as_slice_mut()[handle.slot as usize]
     }
+
+    /// Copies the host-side array and free slot list.
+    pub fn snapshot(&self) -> ReservationSnapshot<Camera> {
+        ReservationSnapshot::capture(&self.data, &self.available)
+    }
+
+    /// Overwrites the host-side array and free slot list with `snapshot` and marks the
+    /// restored range for upload on the next `update`.
+    pub fn restore(&mut self, snapshot: &ReservationSnapshot<Camera>) {
+        snapshot.restore_into(&mut self.data, &mut self.available, &mut self.dirty);
+    }
 }
 
 impl ReservedItem for ReservedBindlessCamera {
@@ -81,6 +137,7 @@ impl ReservedItem for ReservedBindlessCamera {
     }
 
     fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        self.retired.advance(unsafe { self.ctx.as_mut() });
         let mut cmd = CommandStream::new().begin();
         if let Some((start, end)) = self.dirty.take() {
             cmd = cmd.combine(self.data.sync_up_range(start, end - start).end());
@@ -98,6 +155,18 @@ impl ReservedItem for ReservedBindlessCamera {
         });
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty() || !self.retired.is_empty()
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -128,6 +197,29 @@ mod tests {
         assert_eq!(first.slot, reused.slot);
     }
 
+    #[test]
+    fn freed_camera_slots_come_back_with_default_data() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut cameras = ReservedBindlessCamera::new(&mut ctx);
+
+        let handle = cameras.add_camera();
+        cameras
+            .camera_mut(handle)
+            .set_position(Vec3::new(4.0, 5.0, 6.0));
+
+        cameras.remove_camera(handle);
+        cameras.remove_camera(handle);
+        let reused = cameras.add_camera();
+        let fresh = cameras.add_camera();
+
+        assert_eq!(reused.slot, handle.slot);
+        assert_ne!(fresh.slot, handle.slot, "double free handed the slot out twice");
+        assert_eq!(
+            cameras.camera(reused).position(),
+            Camera::default().position()
+        );
+    }
+
     #[test]
     fn mutates_host_camera_data() {
         let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
@@ -144,4 +236,38 @@ mod tests {
         assert_eq!(cam.position(), Vec3::new(1.0, 2.0, 3.0));
         assert_eq!(cam.rotation(), Quat::from_rotation_y(1.0));
     }
+
+    #[test]
+    fn grows_past_initial_capacity_keeping_camera_data() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut cameras = ReservedBindlessCamera::new(&mut ctx);
+        let initial = cameras.capacity();
+        let epoch = cameras.epoch();
+
+        let first = cameras.add_camera();
+        cameras
+            .camera_mut(first)
+            .set_position(Vec3::new(7.0, 8.0, 9.0));
+        let handles: Vec<Handle<Camera>> = (0..initial).map(|_| cameras.add_camera()).collect();
+
+        assert!(cameras.capacity() > initial);
+        assert!(cameras.epoch() > epoch);
+        assert!(handles.iter().all(|handle| handle.valid()));
+        assert_eq!(cameras.camera(first).position(), Vec3::new(7.0, 8.0, 9.0));
+
+        let ReservedBinding::TableBinding { resources, .. } = cameras.binding() else {
+            panic!("cameras bind a single table slot");
+        };
+        let ShaderResource::StorageBuffer(view) = &resources[0].resource else {
+            panic!("cameras bind a storage buffer");
+        };
+        assert_eq!(view.handle, cameras.data.device().handle);
+
+        // The replaced buffers are destroyed once enough updates have gone by.
+        for _ in 0..4 {
+            cameras.update().expect("upload grown cameras");
+        }
+        assert!(cameras.retired.is_empty());
+        assert_eq!(cameras.camera(first).position(), Vec3::new(7.0, 8.0, 9.0));
+    }
 }
@@ -3,14 +3,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, CommandStream, Context, IndexedBindingInfo,
-    IndexedResource, ShaderResource,
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context,
+    IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::error::FurikakeError;
 
-use super::{table_binding_from_indexed, DirtyRange, ReservedBinding, ReservedItem};
+use super::{
+    table_binding_from_indexed, DirtyRange, ReservationConfig, ReservedBinding, ReservedItem,
+};
 
 const INDEX_BUFFER_BYTES: u32 = 32 * 1024 * 1024;
 
@@ -23,7 +25,11 @@ pub struct ReservedBindlessIndices {
 
 impl ReservedBindlessIndices {
     pub fn new(ctx: &mut Context) -> Self {
-        let indices = StagedBuffer::new(
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
+        let indices = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Index Buffer",
@@ -91,6 +97,14 @@ impl ReservedItem for ReservedBindlessIndices {
         })
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
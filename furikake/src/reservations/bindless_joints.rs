@@ -3,14 +3,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    BufferInfo, BufferUsage, CommandStream, Context, Handle, IndexedBindingInfo, IndexedResource,
-    ShaderResource, cmd::Executable,
+    BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource, cmd::Executable,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::JointTransform};
 
-use super::{DirtyRange, ReservedBinding, ReservedItem, table_binding_from_indexed};
+use super::{
+    DirtyRange, ReservationConfig, ReservedBinding, ReservedItem, table_binding_from_indexed,
+};
 
 pub struct ReservedBindlessJoints {
     ctx: NonNull<Context>,
@@ -21,10 +23,14 @@ pub struct ReservedBindlessJoints {
 
 impl ReservedBindlessJoints {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_JOINTS: usize = 16384;
 
         let available_joints: Vec<u16> = (0..START_JOINTS as u16).collect();
-        let joints = StagedBuffer::new(
+        let joints = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Joint Transform Buffer",
@@ -98,6 +104,14 @@ impl ReservedItem for ReservedBindlessJoints {
         })
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
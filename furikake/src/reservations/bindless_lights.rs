@@ -3,13 +3,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, BufferView, CommandStream, Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, BufferView, CommandStream,
+    Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::Light};
 
-use super::{DirtyRange, ReservedBinding, ReservedItem, table_binding_from_indexed};
+use super::{
+    DirtyRange, ReservationConfig, ReservedBinding, ReservedItem, table_binding_from_indexed,
+};
 
 pub struct ReservedBindlessLights {
     ctx: NonNull<Context>,
@@ -20,11 +23,15 @@ pub struct ReservedBindlessLights {
 
 impl ReservedBindlessLights {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_SIZE: usize = 128;
 
         let available: Vec<u16> = (0..START_SIZE as u16).collect();
         let start = vec![Light::default(); START_SIZE];
-        let data = StagedBuffer::new(
+        let data = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Light Buffer",
@@ -98,6 +105,14 @@ impl ReservedItem for ReservedBindlessLights {
         });
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
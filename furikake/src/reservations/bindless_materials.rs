@@ -3,27 +3,37 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, BufferView, CommandStream, Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, BufferView, CommandStream,
+    Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::Material};
 
-use super::{DirtyRange, ReservedBinding, ReservedItem, table_binding_from_indexed};
+use super::{
+    DirtyRange, ReservationConfig, ReservationSnapshot, ReservedBinding, ReservedItem,
+    RetiredBuffers, grow_slots, table_binding_from_indexed,
+};
 
 pub struct ReservedBindlessMaterials {
     ctx: NonNull<Context>,
     data: StagedBuffer,
     available: Vec<u16>,
     dirty: DirtyRange,
+    retired: RetiredBuffers,
+    epoch: u64,
 }
 
 impl ReservedBindlessMaterials {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_SIZE: usize = 4096;
 
         let available: Vec<u16> = (0..START_SIZE as u16).collect();
-        let data = StagedBuffer::new(
+        let data = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Material Buffer",
@@ -39,16 +49,31 @@ impl ReservedBindlessMaterials {
             data,
             available,
             dirty: DirtyRange::default(),
+            retired: RetiredBuffers::default(),
+            epoch: 0,
         }
     }
 
+    /// Frees `material`'s slot for the next `add_material` and resets it to
+    /// `Material::default()`. Invalid or already freed handles are ignored.
     pub fn remove_material(&mut self, material: Handle<Material>) {
-        if material.valid() && (material.slot as usize) < 512 {
-            self.available.push(material.slot);
+        let slots = self.data.as_slice::<Material>().len();
+        if !material.valid()
+            || material.slot as usize >= slots
+            || self.available.contains(&material.slot)
+        {
+            return;
         }
+
+        *self.material_mut(material) = Material::default();
+        self.available.push(material.slot);
     }
 
     pub fn add_material(&mut self) -> Handle<Material> {
+        if self.available.is_empty() {
+            self.grow(self.capacity() * 2);
+        }
+
         if let Some(id) = self.available.pop() {
             return Handle::new(id, 0);
         }
@@ -56,6 +81,27 @@ impl ReservedBindlessMaterials {
         return Handle::new(0, 0);
     }
 
+    /// Number of slots the buffers currently hold.
+    pub fn capacity(&self) -> usize {
+        self.data.as_slice::<Material>().len()
+    }
+
+    /// Reallocates the buffers with room for `capacity` materials, keeping contents and
+    /// handles; the old buffers are destroyed once frames in flight stop reading them. See
+    /// `grow_slots`. `add_material` grows by doubling when it runs out of slots.
+    pub fn grow(&mut self, capacity: usize) {
+        grow_slots::<Material>(
+            unsafe { self.ctx.as_mut() },
+            "[FURIKAKE] Material Buffer",
+            capacity,
+            &mut self.data,
+            &mut self.available,
+            &mut self.dirty,
+            &mut self.retired,
+            &mut self.epoch,
+        );
+    }
+
     pub fn push_material(&mut self, material: Material) -> Handle<Material> {
         let handle = self.add_material();
         if handle.valid() {
@@ -73,6 +119,17 @@ impl ReservedBindlessMaterials {
             .mark_elements::<Material>(handle.slot as usize, 1);
         &mut self.data.as_slice_mut()[handle.slot as usize]
     }
+
+    /// Copies the host-side array and free slot list.
+    pub fn snapshot(&self) -> ReservationSnapshot<Material> {
+        ReservationSnapshot::capture(&self.data, &self.available)
+    }
+
+    /// Overwrites the host-side array and free slot list with `snapshot` and marks the
+    /// restored range for upload on the next `update`.
+    pub fn restore(&mut self, snapshot: &ReservationSnapshot<Material>) {
+        snapshot.restore_into(&mut self.data, &mut self.available, &mut self.dirty);
+    }
 }
 
 impl ReservedItem for ReservedBindlessMaterials {
@@ -81,6 +138,7 @@ impl ReservedItem for ReservedBindlessMaterials {
     }
 
     fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        self.retired.advance(unsafe { self.ctx.as_mut() });
         let mut cmd = CommandStream::new().begin();
         if let Some((start, end)) = self.dirty.take() {
             cmd = cmd.combine(self.data.sync_up_range(start, end - start).end());
@@ -98,6 +156,18 @@ impl ReservedItem for ReservedBindlessMaterials {
         });
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty() || !self.retired.is_empty()
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1,7 +1,8 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    BufferInfo, Context, DynamicAllocatorInfo, DynamicBuffer, Handle, IndexedResource, ShaderResource,
+    BindTableVariableType, BufferInfo, Context, DynamicAllocatorInfo, DynamicBuffer, Handle,
+    IndexedResource, ShaderResource,
 };
 
 use crate::types::{BindlessPerObj, Camera};
@@ -57,6 +58,10 @@ impl ReservedItem for ReservedBindlessPerObj {
         }
     }
 
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1,16 +1,24 @@
 #![allow(dead_code)]
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashSet},
+    rc::Rc,
+};
 
 use dashi::{
-    CommandStream, Context, Handle, ImageInfo, ImageView, IndexedBindingInfo, IndexedResource,
-    Sampler, SamplerInfo, ShaderResource, cmd::Executable,
+    BindTable, BindTableUpdateInfo, BindTableVariableType, CommandStream, Context, Handle,
+    ImageInfo, ImageView, IndexedBindingInfo, IndexedResource, Sampler, SamplerInfo, ShaderResource,
+    cmd::Executable,
 };
 
 use crate::{error::FurikakeError, types::Texture};
 
 use super::{ReservedBinding, ReservedItem, table_binding_from_indexed};
 
+// Texture ids are u16, so no reservation holds more slots than this.
+const MAX_SLOTS: usize = u16::MAX as usize;
+
 struct DefaultData {
     img: ImageView,
     sampler: Handle<Sampler>,
@@ -22,16 +30,30 @@ struct BindlessTextureData {
     host_texture_data: Vec<Texture>,
     available: Vec<u16>,
     def: DefaultData,
+    epoch: u64,
+    fixed_capacity: Option<u32>,
 }
 
 impl BindlessTextureData {
     fn new(ctx: &mut Context) -> Self {
         const START_SIZE: usize = 8069;
 
-        let mut d_image_data = Vec::with_capacity(START_SIZE);
-        let mut d_sampler_data = Vec::with_capacity(START_SIZE);
-        let mut h_data = Vec::with_capacity(START_SIZE);
-        let available: Vec<u16> = (0..START_SIZE as u16).collect();
+        Self::with_size(ctx, START_SIZE, None)
+    }
+
+    fn with_fixed_capacity(ctx: &mut Context, capacity: u32) -> Result<Self, FurikakeError> {
+        if capacity as usize > MAX_SLOTS {
+            return Err(FurikakeError::BindlessCapacityTooLarge { capacity });
+        }
+
+        Ok(Self::with_size(ctx, capacity as usize, Some(capacity)))
+    }
+
+    fn with_size(ctx: &mut Context, size: usize, fixed_capacity: Option<u32>) -> Self {
+        let mut d_image_data = Vec::with_capacity(size);
+        let mut d_sampler_data = Vec::with_capacity(size);
+        let mut h_data = Vec::with_capacity(size);
+        let available: Vec<u16> = (0..size as u16).collect();
 
         let default_sampler = ctx.make_sampler(&SamplerInfo::default()).unwrap();
         let default_image = ctx
@@ -49,7 +71,7 @@ impl BindlessTextureData {
             ..Default::default()
         };
 
-        for i in 0..START_SIZE {
+        for i in 0..size {
             h_data.push(Texture {
                 img: default_view,
                 sampler: Some(default_sampler),
@@ -74,14 +96,24 @@ impl BindlessTextureData {
                 img: default_view,
                 sampler: default_sampler,
             },
+            epoch: 0,
+            fixed_capacity,
         }
     }
 
+    fn descriptor_count(&self) -> u32 {
+        self.fixed_capacity.unwrap_or(self.device_image_data.len() as u32)
+    }
+
     fn extend(&mut self) {
-        if self.available.is_empty() {
+        // Fixed-capacity arrays never grow, so their layouts never need recreating.
+        if self.available.is_empty() && self.fixed_capacity.is_none() {
             const EXTENSION_SIZE: usize = 128;
             let start = self.host_texture_data.len();
-            let end = start + EXTENSION_SIZE;
+            let end = (start + EXTENSION_SIZE).min(MAX_SLOTS);
+            if start == end {
+                return;
+            }
 
             let default_view = self.def.img.clone();
             let default_sampler = self.def.sampler;
@@ -102,6 +134,8 @@ impl BindlessTextureData {
 
                 self.available.push(i as u16);
             }
+
+            self.epoch += 1;
         }
     }
 }
@@ -125,6 +159,17 @@ impl ReservedBindlessTextures {
         }
     }
 
+    /// Creates a reservation whose device arrays are sized to `capacity` up front and never
+    /// grow. `descriptor_count` always reports `capacity`, regardless of how many textures are
+    /// live. Fails when `capacity` is above `u16::MAX`, the most ids a texture id can address.
+    pub fn with_fixed_capacity(ctx: &mut Context, capacity: u32) -> Result<Self, FurikakeError> {
+        Ok(Self {
+            data: Rc::new(RefCell::new(BindlessTextureData::with_fixed_capacity(
+                ctx, capacity,
+            )?)),
+        })
+    }
+
     pub fn samplers(&self) -> ReservedBindlessSamplers {
         ReservedBindlessSamplers {
             data: Rc::clone(&self.data),
@@ -157,10 +202,40 @@ impl ReservedBindlessTextures {
         self.data.borrow().device_image_data.clone()
     }
 
+    /// Ids currently holding a registered texture.
+    pub fn texture_ids(&self) -> BTreeSet<u16> {
+        let data = self.data.borrow();
+        let free: HashSet<u16> = data.available.iter().copied().collect();
+        (0..data.host_texture_data.len() as u16)
+            .filter(|id| !free.contains(id))
+            .collect()
+    }
+
     pub fn sampler_resources(&self) -> Vec<IndexedResource> {
         self.data.borrow().device_sampler_data.clone()
     }
 
+    /// Writes the current image list into an existing bind table instead of cooking a new one.
+    ///
+    /// `table` must have been created with at least as many descriptors at binding 0 as this
+    /// reservation currently holds; pairing this with `with_fixed_capacity` keeps bindless
+    /// updates free of table recreation.
+    pub fn rebind_into(
+        &self,
+        ctx: &mut Context,
+        table: Handle<BindTable>,
+    ) -> Result<(), FurikakeError> {
+        let data = self.data.borrow();
+        ctx.update_bind_table(&BindTableUpdateInfo {
+            table,
+            bindings: &[IndexedBindingInfo {
+                resources: &data.device_image_data,
+                binding: 0,
+            }],
+        })
+        .map_err(FurikakeError::from)
+    }
+
     pub fn remove_texture(&mut self, texture: u16) {
         let mut data = self.data.borrow_mut();
         let def_img = data.def.img;
@@ -180,7 +255,9 @@ impl ReservedBindlessTextures {
         }
     }
 
-    pub fn add_texture(&mut self, img: ImageView) -> u16 {
+    /// Registers `img` and returns its id, or `None` when every slot is taken: a fixed-capacity
+    /// reservation is full, or a growable one has used every u16 id.
+    pub fn add_texture(&mut self, img: ImageView) -> Option<u16> {
         self.add_texture_with_sampler(img, None)
     }
 
@@ -188,16 +265,13 @@ impl ReservedBindlessTextures {
         &mut self,
         img: ImageView,
         sampler: Option<Handle<Sampler>>,
-    ) -> u16 {
+    ) -> Option<u16> {
         let mut data = self.data.borrow_mut();
         if data.available.is_empty() {
             data.extend();
         }
 
-        let id = data
-            .available
-            .pop()
-            .expect("bindless texture slot after extension");
+        let id = data.available.pop()?;
         let sampler = sampler.unwrap_or(data.def.sampler);
 
         if let Some(host) = data.host_texture_data.get_mut(id as usize) {
@@ -212,7 +286,7 @@ impl ReservedBindlessTextures {
             resource.resource = ShaderResource::Sampler(sampler);
         }
 
-        id
+        Some(id)
     }
 
     pub fn update_sampler(&mut self, texture: u16, sampler: Handle<Sampler>) {
@@ -236,16 +310,14 @@ impl ReservedBindlessCubemaps {
             .cloned()
     }
 
-    pub fn add_texture(&mut self, img: ImageView) -> u16 {
+    /// Registers `img` and returns its id, or `None` when every slot is taken.
+    pub fn add_texture(&mut self, img: ImageView) -> Option<u16> {
         let mut data = self.data.borrow_mut();
         if data.available.is_empty() {
             data.extend();
         }
 
-        let id = data
-            .available
-            .pop()
-            .expect("bindless cubemap slot after extension");
+        let id = data.available.pop()?;
 
         if let Some(host) = data.host_texture_data.get_mut(id as usize) {
             host.img = img;
@@ -256,7 +328,7 @@ impl ReservedBindlessCubemaps {
             resource.resource = ShaderResource::Image(img);
         }
 
-        id
+        Some(id)
     }
 
     pub fn remove_texture(&mut self, texture: u16) {
@@ -292,6 +364,22 @@ impl ReservedItem for ReservedBindlessTextures {
         })
     }
 
+    fn epoch(&self) -> u64 {
+        self.data.borrow().epoch
+    }
+
+    fn descriptor_count(&self) -> u32 {
+        self.data.borrow().descriptor_count()
+    }
+
+    fn has_pending_update(&self) -> bool {
+        false
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Image
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -318,6 +406,22 @@ impl ReservedItem for ReservedBindlessSamplers {
         })
     }
 
+    fn epoch(&self) -> u64 {
+        self.data.borrow().epoch
+    }
+
+    fn descriptor_count(&self) -> u32 {
+        self.data.borrow().descriptor_count()
+    }
+
+    fn has_pending_update(&self) -> bool {
+        false
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Sampler
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -344,6 +448,22 @@ impl ReservedItem for ReservedBindlessCubemaps {
         })
     }
 
+    fn epoch(&self) -> u64 {
+        self.data.borrow().epoch
+    }
+
+    fn descriptor_count(&self) -> u32 {
+        self.data.borrow().descriptor_count()
+    }
+
+    fn has_pending_update(&self) -> bool {
+        false
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Image
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -356,7 +476,12 @@ impl ReservedItem for ReservedBindlessCubemaps {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dashi::{Context, ContextInfo, Format, ImageInfo, SamplerInfo};
+    use crate::reservations::reserved_layout_variable;
+    use dashi::builders::BindTableLayoutBuilder;
+    use dashi::{
+        BindTableInfo, BindTableVariableType, Context, ContextInfo, Format, ImageInfo, SamplerInfo,
+        ShaderInfo, ShaderType,
+    };
 
     fn make_dummy_texture(ctx: &mut Context, name: &str) -> ImageView {
         let image = ctx
@@ -381,7 +506,7 @@ mod tests {
         let mut textures = ReservedBindlessTextures::new(&mut ctx);
         let view = make_dummy_texture(&mut ctx, "bindless_texture_default_sampler");
 
-        let id = textures.add_texture(view);
+        let id = textures.add_texture(view).expect("free texture slot");
 
         let data = textures.data.borrow();
         let host_entry = data.host_texture_data[id as usize];
@@ -415,7 +540,9 @@ mod tests {
             })
             .expect("custom sampler");
 
-        let id = textures.add_texture_with_sampler(view, Some(custom_sampler));
+        let id = textures
+            .add_texture_with_sampler(view, Some(custom_sampler))
+            .expect("free texture slot");
 
         let data = textures.data.borrow();
         let host_entry = data.host_texture_data[id as usize];
@@ -434,7 +561,7 @@ mod tests {
         let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
         let mut textures = ReservedBindlessTextures::new(&mut ctx);
         let view = make_dummy_texture(&mut ctx, "bindless_texture_update_sampler");
-        let id = textures.add_texture(view);
+        let id = textures.add_texture(view).expect("free texture slot");
 
         let replacement_sampler = ctx
             .make_sampler(&SamplerInfo {
@@ -474,4 +601,102 @@ mod tests {
         // One slot is consumed from the extension block.
         assert_eq!(data.available.len(), 127);
     }
+
+    #[test]
+    fn full_fixed_capacity_returns_none() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut textures = ReservedBindlessTextures::with_fixed_capacity(&mut ctx, 2)
+            .expect("fixed-capacity textures");
+        let mut cubemaps = textures.cubemaps();
+        let view = make_dummy_texture(&mut ctx, "bindless_texture_full");
+
+        assert!(textures.add_texture(view).is_some());
+        assert!(cubemaps.add_texture(view).is_some());
+        assert_eq!(textures.add_texture(view), None);
+        assert_eq!(cubemaps.add_texture(view), None);
+        assert_eq!(textures.descriptor_count(), 2);
+    }
+
+    #[test]
+    fn rejects_capacities_beyond_texture_ids() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+
+        assert!(matches!(
+            ReservedBindlessTextures::with_fixed_capacity(&mut ctx, u16::MAX as u32 + 1),
+            Err(FurikakeError::BindlessCapacityTooLarge { capacity: 65536 })
+        ));
+    }
+
+    #[test]
+    fn fixed_capacity_layout_declares_full_array() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut textures = ReservedBindlessTextures::with_fixed_capacity(&mut ctx, 1024)
+            .expect("fixed-capacity textures");
+        let view = make_dummy_texture(&mut ctx, "bindless_texture_fixed_capacity");
+        textures.add_texture(view).expect("free texture slot");
+
+        assert_eq!(textures.descriptor_count(), 1024);
+
+        let variable = reserved_layout_variable(&textures, BindTableVariableType::Image)
+            .expect("single-table reservation");
+        assert_eq!(variable.count, 1024);
+
+        let layout = BindTableLayoutBuilder::new("fixed_capacity_layout")
+            .shader(ShaderInfo {
+                shader_type: ShaderType::All,
+                variables: &[variable],
+            })
+            .build(&mut ctx)
+            .expect("build fixed-capacity layout");
+
+        let resources = textures.image_resources();
+        assert_eq!(resources.len(), 1024);
+        ctx.make_bind_table(&BindTableInfo {
+            debug_name: "fixed_capacity_table",
+            layout,
+            bindings: &[IndexedBindingInfo {
+                resources: &resources,
+                binding: variable.binding,
+            }],
+            set: 0,
+        })
+        .expect("bind table matches fixed-capacity layout");
+    }
+
+    #[test]
+    fn rebinds_into_precooked_table() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut textures = ReservedBindlessTextures::with_fixed_capacity(&mut ctx, 64)
+            .expect("fixed-capacity textures");
+
+        let variable = reserved_layout_variable(&textures, BindTableVariableType::Image)
+            .expect("single-table reservation");
+        let layout = BindTableLayoutBuilder::new("rebind_layout")
+            .shader(ShaderInfo {
+                shader_type: ShaderType::All,
+                variables: &[variable],
+            })
+            .build(&mut ctx)
+            .expect("build rebind layout");
+        let table = ctx
+            .make_bind_table(&BindTableInfo {
+                debug_name: "rebind_table",
+                layout,
+                bindings: &[IndexedBindingInfo {
+                    resources: &textures.image_resources(),
+                    binding: variable.binding,
+                }],
+                set: 0,
+            })
+            .expect("pre-cook bind table");
+
+        for index in 0..4 {
+            let view = make_dummy_texture(&mut ctx, &format!("bindless_texture_rebind_{index}"));
+            textures.add_texture(view).expect("free texture slot");
+        }
+
+        textures
+            .rebind_into(&mut ctx, table)
+            .expect("rebind into pre-cooked table");
+    }
 }
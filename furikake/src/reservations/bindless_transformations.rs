@@ -3,27 +3,37 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, BufferView, CommandStream, Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, BufferView, CommandStream,
+    Context, Handle, IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::Transformation};
 
-use super::{DirtyRange, ReservedBinding, ReservedItem, table_binding_from_indexed};
+use super::{
+    DirtyRange, ReservationConfig, ReservationSnapshot, ReservedBinding, ReservedItem,
+    RetiredBuffers, grow_slots, table_binding_from_indexed,
+};
 
 pub struct ReservedBindlessTransformations {
     ctx: NonNull<Context>,
     data: StagedBuffer,
     available: Vec<u16>,
     dirty: DirtyRange,
+    retired: RetiredBuffers,
+    epoch: u64,
 }
 
 impl ReservedBindlessTransformations {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_SIZE: usize = 16384;
 
         let available: Vec<u16> = (0..START_SIZE as u16).collect();
-        let data = StagedBuffer::new(
+        let data = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Transformation Buffer",
@@ -39,16 +49,31 @@ impl ReservedBindlessTransformations {
             data,
             available,
             dirty: DirtyRange::default(),
+            retired: RetiredBuffers::default(),
+            epoch: 0,
         }
     }
 
+    /// Frees `transform`'s slot for the next `add_transform` and resets it to
+    /// `Transformation::default()`. Invalid or already freed handles are ignored.
     pub fn remove_transform(&mut self, transform: Handle<Transformation>) {
-        if transform.valid() && (transform.slot as usize) < 512 {
-            self.available.push(transform.slot);
+        let slots = self.data.as_slice::<Transformation>().len();
+        if !transform.valid()
+            || transform.slot as usize >= slots
+            || self.available.contains(&transform.slot)
+        {
+            return;
         }
+
+        *self.transform_mut(transform) = Transformation::default();
+        self.available.push(transform.slot);
     }
 
     pub fn add_transform(&mut self) -> Handle<Transformation> {
+        if self.available.is_empty() {
+            self.grow(self.capacity() * 2);
+        }
+
         if let Some(id) = self.available.pop() {
             return Handle::new(id, 0);
         }
@@ -56,6 +81,27 @@ impl ReservedBindlessTransformations {
         return Handle::new(u16::MAX, u16::MAX);
     }
 
+    /// Number of slots the buffers currently hold.
+    pub fn capacity(&self) -> usize {
+        self.data.as_slice::<Transformation>().len()
+    }
+
+    /// Reallocates the buffers with room for `capacity` transformations, keeping contents and
+    /// handles; the old buffers are destroyed once frames in flight stop reading them. See
+    /// `grow_slots`. `add_transform` grows by doubling when it runs out of slots.
+    pub fn grow(&mut self, capacity: usize) {
+        grow_slots::<Transformation>(
+            unsafe { self.ctx.as_mut() },
+            "[FURIKAKE] Transformation Buffer",
+            capacity,
+            &mut self.data,
+            &mut self.available,
+            &mut self.dirty,
+            &mut self.retired,
+            &mut self.epoch,
+        );
+    }
+
     pub fn push_transform(&mut self, transform: Transformation) -> Handle<Transformation> {
         let handle = self.add_transform();
         if handle.valid() {
@@ -73,6 +119,17 @@ impl ReservedBindlessTransformations {
             .mark_elements::<Transformation>(handle.slot as usize, 1);
         &mut self.data.as_slice_mut()[handle.slot as usize]
     }
+
+    /// Copies the host-side array and free slot list.
+    pub fn snapshot(&self) -> ReservationSnapshot<Transformation> {
+        ReservationSnapshot::capture(&self.data, &self.available)
+    }
+
+    /// Overwrites the host-side array and free slot list with `snapshot` and marks the
+    /// restored range for upload on the next `update`.
+    pub fn restore(&mut self, snapshot: &ReservationSnapshot<Transformation>) {
+        snapshot.restore_into(&mut self.data, &mut self.available, &mut self.dirty);
+    }
 }
 
 impl ReservedItem for ReservedBindlessTransformations {
@@ -81,6 +138,7 @@ impl ReservedItem for ReservedBindlessTransformations {
     }
 
     fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        self.retired.advance(unsafe { self.ctx.as_mut() });
         let mut cmd = CommandStream::new().begin();
         if let Some((start, end)) = self.dirty.take() {
             cmd = cmd.combine(self.data.sync_up_range(start, end - start).end());
@@ -98,6 +156,18 @@ impl ReservedItem for ReservedBindlessTransformations {
         });
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty() || !self.retired.is_empty()
+    }
+
+    fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -106,3 +176,26 @@ impl ReservedItem for ReservedBindlessTransformations {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashi::ContextInfo;
+    use glam::{Mat4, Vec3};
+
+    #[test]
+    fn reuses_freed_transform_slots_with_default_data() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut transforms = ReservedBindlessTransformations::new(&mut ctx);
+
+        // Slots are handed out from the top of the buffer, well past the first few hundred.
+        let handle = transforms.add_transform();
+        transforms.transform_mut(handle).transform = Mat4::from_translation(Vec3::ONE);
+
+        transforms.remove_transform(handle);
+        let reused = transforms.add_transform();
+
+        assert_eq!(reused.slot, handle.slot);
+        assert_eq!(transforms.transform(reused).transform, Mat4::default());
+    }
+}
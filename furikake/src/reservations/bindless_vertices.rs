@@ -3,14 +3,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, CommandStream, Context, IndexedBindingInfo,
-    IndexedResource, ShaderResource,
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context,
+    IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::VertexBufferSlot};
 
-use super::{table_binding_from_indexed, DirtyRange, ReservedBinding, ReservedItem};
+use super::{
+    table_binding_from_indexed, DirtyRange, ReservationConfig, ReservedBinding, ReservedItem,
+};
 
 const VERTEX_BUFFER_BYTES: u32 = 64 * 1024 * 1024;
 const VERTEX_BUFFER_SLOT_COUNT: usize = crate::types::VERTEX_BUFFER_SLOT_COUNT;
@@ -29,10 +31,14 @@ pub struct ReservedBindlessVertices {
 
 impl ReservedBindlessVertices {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         let buffers = VERTEX_BUFFER_NAMES
             .iter()
             .map(|name| {
-                StagedBuffer::new(
+                config.staged_buffer(
                     ctx,
                     BufferInfo {
                         debug_name: name,
@@ -122,6 +128,10 @@ impl ReservedItem for ReservedBindlessVertices {
         })
     }
 
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
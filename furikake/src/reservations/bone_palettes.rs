@@ -0,0 +1,183 @@
+#![allow(dead_code)]
+
+use std::ptr::NonNull;
+
+use dashi::{
+    BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource, cmd::Executable,
+};
+use glam::Mat4;
+use tare::utils::StagedBuffer;
+
+use crate::{error::FurikakeError, types::BonePalette};
+
+use super::{
+    DirtyRange, ReservationConfig, ReservedBinding, ReservedItem, table_binding_from_indexed,
+};
+
+/// Bone matrices for skinned meshes, packed per skeleton into one flat array.
+///
+/// Each skeleton owns a contiguous range starting at its palette's `base`, so a shader only
+/// needs that base and a bone index to find a matrix.
+pub struct ReservedBonePalettes {
+    ctx: NonNull<Context>,
+    bones: StagedBuffer,
+    palettes: Vec<BonePalette>,
+    next_bone: u32,
+    dirty: DirtyRange,
+}
+
+impl ReservedBonePalettes {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
+        const START_BONES: usize = 16384;
+
+        let bones = config.staged_buffer(
+            ctx,
+            BufferInfo {
+                debug_name: "[FURIKAKE] Bone Palette Buffer",
+                byte_size: std::mem::size_of::<Mat4>() as u32 * START_BONES as u32,
+                visibility: Default::default(),
+                usage: BufferUsage::ALL,
+                initial_data: None,
+            },
+        );
+
+        Self {
+            ctx: NonNull::new(ctx).expect("NonNull failed check"),
+            bones,
+            palettes: Vec::new(),
+            next_bone: 0,
+            dirty: DirtyRange::default(),
+        }
+    }
+
+    /// Reserves `bone_count` identity matrices for a new skeleton. Returns an invalid handle
+    /// when the palette buffer is full.
+    pub fn add_skeleton(&mut self, bone_count: u32) -> Handle<BonePalette> {
+        let capacity = self.bones.as_slice::<Mat4>().len() as u32;
+        if self.next_bone + bone_count > capacity || self.palettes.len() >= u16::MAX as usize {
+            return Handle::new(u16::MAX, u16::MAX);
+        }
+
+        let palette = BonePalette {
+            base: self.next_bone,
+            bone_count,
+        };
+        self.next_bone += bone_count;
+
+        let start = palette.base as usize;
+        let end = start + bone_count as usize;
+        self.bones.as_slice_mut::<Mat4>()[start..end].fill(Mat4::IDENTITY);
+        self.dirty.mark_elements::<Mat4>(start, bone_count as usize);
+
+        self.palettes.push(palette);
+        Handle::new(self.palettes.len() as u16 - 1, 0)
+    }
+
+    pub fn palette(&self, skeleton: Handle<BonePalette>) -> Option<BonePalette> {
+        self.palettes.get(skeleton.slot as usize).copied()
+    }
+
+    /// Index of the skeleton's first bone in the palette array.
+    pub fn base_offset(&self, skeleton: Handle<BonePalette>) -> Option<u32> {
+        self.palette(skeleton).map(|palette| palette.base)
+    }
+
+    pub fn bones(&self, skeleton: Handle<BonePalette>) -> &[Mat4] {
+        let Some(palette) = self.palette(skeleton) else {
+            return &[];
+        };
+
+        let start = palette.base as usize;
+        &self.bones.as_slice()[start..start + palette.bone_count as usize]
+    }
+
+    /// Writes one bone matrix. Indices past the skeleton's bone count are ignored.
+    pub fn set_bone(&mut self, skeleton: Handle<BonePalette>, index: u32, matrix: Mat4) {
+        let Some(palette) = self.palette(skeleton) else {
+            return;
+        };
+        if index >= palette.bone_count {
+            return;
+        }
+
+        let slot = (palette.base + index) as usize;
+        self.bones.as_slice_mut::<Mat4>()[slot] = matrix;
+        self.dirty.mark_elements::<Mat4>(slot, 1);
+    }
+}
+
+impl ReservedItem for ReservedBonePalettes {
+    fn name(&self) -> String {
+        "meshi_bone_palettes".to_string()
+    }
+
+    fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        let mut cmd = CommandStream::new().begin();
+        if let Some((start, end)) = self.dirty.take() {
+            cmd = cmd.combine(self.bones.sync_up_range(start, end - start).end());
+        }
+        Ok(cmd.end())
+    }
+
+    fn binding(&self) -> ReservedBinding {
+        table_binding_from_indexed(IndexedBindingInfo {
+            resources: &[IndexedResource {
+                resource: ShaderResource::StorageBuffer(self.bones.device().into()),
+                slot: 0,
+            }],
+            binding: 0,
+        })
+    }
+
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashi::ContextInfo;
+    use glam::Vec3;
+
+    #[test]
+    fn packs_skeleton_palettes_back_to_back() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut palettes = ReservedBonePalettes::new(&mut ctx);
+
+        let first = palettes.add_skeleton(3);
+        let second = palettes.add_skeleton(5);
+
+        let lifted = Mat4::from_translation(Vec3::new(0.0, 1.0, 0.0));
+        palettes.set_bone(first, 2, lifted);
+        palettes.set_bone(second, 4, lifted.inverse());
+
+        palettes.update().expect("update bone palettes");
+
+        assert_eq!(palettes.base_offset(first), Some(0));
+        assert_eq!(palettes.base_offset(second), Some(3));
+
+        let all = palettes.bones.as_slice::<Mat4>();
+        assert_eq!(all[2], lifted);
+        assert_eq!(all[3 + 4], lifted.inverse());
+        assert_eq!(palettes.bones(second).len(), 5);
+        assert_eq!(palettes.bones(first)[0], Mat4::IDENTITY);
+    }
+}
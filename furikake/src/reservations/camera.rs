@@ -1,6 +1,9 @@
 #![allow(dead_code)]
 
-use dashi::{cmd::Executable, BindTableVariable, BufferView, CommandStream, Context, IndexedResource, ShaderResource};
+use dashi::{
+    cmd::Executable, BindTableVariable, BindTableVariableType, BufferView, CommandStream, Context,
+    IndexedResource, ShaderResource,
+};
 
 use crate::{error::FurikakeError, types::Camera};
 
@@ -54,6 +57,10 @@ impl ReservedItem for ReservedCamera {
         }
     }
 
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Uniform
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
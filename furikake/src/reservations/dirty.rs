@@ -30,6 +30,10 @@ impl DirtyRange {
         self.mark_bytes(offset, len);
     }
 
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
     pub fn take(&mut self) -> Option<(u32, u32)> {
         if !self.dirty {
             return None;
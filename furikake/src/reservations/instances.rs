@@ -0,0 +1,156 @@
+#![allow(dead_code)]
+
+use std::ptr::NonNull;
+
+use dashi::{
+    BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource, cmd::Executable,
+};
+use tare::utils::StagedBuffer;
+
+use crate::{
+    error::FurikakeError,
+    types::{InstanceData, Material, Transformation},
+};
+
+use super::{
+    DirtyRange, ReservationConfig, ReservedBinding, ReservedItem, table_binding_from_indexed,
+};
+
+/// Per-instance records pairing a transform with a material, so a single instance index is
+/// enough to resolve both on the GPU.
+pub struct ReservedInstanceData {
+    ctx: NonNull<Context>,
+    data: StagedBuffer,
+    available: Vec<u16>,
+    dirty: DirtyRange,
+}
+
+impl ReservedInstanceData {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
+        const START_SIZE: usize = 16384;
+
+        let available: Vec<u16> = (0..START_SIZE as u16).collect();
+        let data = config.staged_buffer(
+            ctx,
+            BufferInfo {
+                debug_name: "[FURIKAKE] Instance Buffer",
+                byte_size: std::mem::size_of::<InstanceData>() as u32 * START_SIZE as u32,
+                visibility: Default::default(),
+                usage: BufferUsage::ALL,
+                initial_data: None,
+            },
+        );
+
+        Self {
+            ctx: NonNull::new(ctx).expect("NonNull failed check"),
+            data,
+            available,
+            dirty: DirtyRange::default(),
+        }
+    }
+
+    pub fn remove_instance(&mut self, instance: Handle<InstanceData>) {
+        let capacity = self.data.as_slice::<InstanceData>().len();
+        if instance.valid() && (instance.slot as usize) < capacity {
+            self.available.push(instance.slot);
+        }
+    }
+
+    pub fn add_instance(
+        &mut self,
+        transform: Handle<Transformation>,
+        material: Handle<Material>,
+    ) -> Handle<InstanceData> {
+        let Some(id) = self.available.pop() else {
+            return Handle::new(u16::MAX, u16::MAX);
+        };
+
+        let handle = Handle::new(id, 0);
+        *self.instance_mut(handle) = InstanceData {
+            transform_index: transform.slot as u32,
+            material_index: material.slot as u32,
+            ..Default::default()
+        };
+        handle
+    }
+
+    pub fn instance(&self, handle: Handle<InstanceData>) -> &InstanceData {
+        &self.data.as_slice()[handle.slot as usize]
+    }
+
+    pub fn instance_mut(&mut self, handle: Handle<InstanceData>) -> &mut InstanceData {
+        self.dirty
+            .mark_elements::<InstanceData>(handle.slot as usize, 1);
+        &mut self.data.as_slice_mut()[handle.slot as usize]
+    }
+}
+
+impl ReservedItem for ReservedInstanceData {
+    fn name(&self) -> String {
+        "meshi_instances".to_string()
+    }
+
+    fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        let mut cmd = CommandStream::new().begin();
+        if let Some((start, end)) = self.dirty.take() {
+            cmd = cmd.combine(self.data.sync_up_range(start, end - start).end());
+        }
+        Ok(cmd.end())
+    }
+
+    fn binding(&self) -> ReservedBinding {
+        table_binding_from_indexed(IndexedBindingInfo {
+            resources: &[IndexedResource {
+                resource: ShaderResource::StorageBuffer(self.data.device().into()),
+                slot: 0,
+            }],
+            binding: 0,
+        })
+    }
+
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashi::ContextInfo;
+
+    #[test]
+    fn instance_references_transform_and_material() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut instances = ReservedInstanceData::new(&mut ctx);
+
+        let transform = Handle::<Transformation>::new(7, 0);
+        let material = Handle::<Material>::new(3, 0);
+        let handle = instances.add_instance(transform, material);
+        instances.instance_mut(handle).flags = 0x2;
+
+        instances.update().expect("update instance data");
+
+        let instance = instances.instance(handle);
+        assert_eq!(instance.transform_index, 7);
+        assert_eq!(instance.material_index, 3);
+        assert_eq!(instance.flags, 0x2);
+        assert_eq!(std::mem::size_of::<InstanceData>(), 16);
+    }
+}
@@ -0,0 +1,229 @@
+#![allow(dead_code)]
+
+use std::ptr::NonNull;
+
+use dashi::{
+    BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, IndexedBindingInfo,
+    IndexedResource, ShaderResource, cmd::Executable,
+};
+use tare::utils::StagedBuffer;
+
+use crate::{error::FurikakeError, types::LightCluster};
+
+use super::{ReservationConfig, ReservedBinding, ReservedItem, table_binding_from_indexed};
+
+/// `uvec4` of grid dimensions and cluster count at the start of the offset buffer.
+const HEADER_WORDS: usize = 4;
+
+/// Per-frame light cluster grid for clustered and forward+ shading.
+///
+/// Bound as two storage buffers in one array, so shaders declare
+/// `buffer LightClusters { uint words[]; } meshi_light_clusters[2];`:
+/// - `[0]` starts with `uvec4(x, y, z, cluster_count)`, followed by one `LightCluster` per
+///   cluster, with `x` varying fastest.
+/// - `[1]` is the light index list each `LightCluster` points into.
+pub struct ReservedLightClusters {
+    ctx: NonNull<Context>,
+    offsets: StagedBuffer,
+    light_indices: StagedBuffer,
+    dims: [u32; 3],
+    clusters: Vec<Vec<u32>>,
+    dirty: bool,
+}
+
+impl ReservedLightClusters {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
+        const MAX_CLUSTERS: usize = 16 * 9 * 32;
+        const MAX_LIGHT_INDICES: usize = 65_536;
+
+        let offsets = config.staged_buffer(
+            ctx,
+            BufferInfo {
+                debug_name: "[FURIKAKE] Light Cluster Offsets",
+                byte_size: (std::mem::size_of::<u32>() * HEADER_WORDS
+                    + std::mem::size_of::<LightCluster>() * MAX_CLUSTERS)
+                    as u32,
+                visibility: Default::default(),
+                usage: BufferUsage::ALL,
+                initial_data: None,
+            },
+        );
+        let light_indices = config.staged_buffer(
+            ctx,
+            BufferInfo {
+                debug_name: "[FURIKAKE] Light Cluster Indices",
+                byte_size: std::mem::size_of::<u32>() as u32 * MAX_LIGHT_INDICES as u32,
+                visibility: Default::default(),
+                usage: BufferUsage::ALL,
+                initial_data: None,
+            },
+        );
+
+        Self {
+            ctx: NonNull::new(ctx).expect("NonNull failed check"),
+            offsets,
+            light_indices,
+            dims: [0; 3],
+            clusters: Vec::new(),
+            dirty: true,
+        }
+    }
+
+    /// Most clusters a grid may have.
+    pub fn max_clusters(&self) -> u32 {
+        let words = self.offsets.as_slice::<u32>().len() - HEADER_WORDS;
+        (words * std::mem::size_of::<u32>() / std::mem::size_of::<LightCluster>()) as u32
+    }
+
+    /// Resizes the grid to `x * y * z` clusters and empties every cluster. Returns `false`,
+    /// leaving the grid unchanged, when it would exceed `max_clusters`.
+    pub fn set_grid_dims(&mut self, x: u32, y: u32, z: u32) -> bool {
+        let count = x as u64 * y as u64 * z as u64;
+        if count > self.max_clusters() as u64 {
+            return false;
+        }
+
+        self.dims = [x, y, z];
+        self.clusters = vec![Vec::new(); count as usize];
+        self.dirty = true;
+        true
+    }
+
+    pub fn grid_dims(&self) -> [u32; 3] {
+        self.dims
+    }
+
+    /// Replaces the lights affecting cluster `index`. Indices past the grid are ignored.
+    pub fn set_cluster(&mut self, index: u32, light_ids: &[u32]) {
+        let Some(cluster) = self.clusters.get_mut(index as usize) else {
+            return;
+        };
+
+        cluster.clear();
+        cluster.extend_from_slice(light_ids);
+        self.dirty = true;
+    }
+
+    pub fn cluster_lights(&self, index: u32) -> &[u32] {
+        self.clusters
+            .get(index as usize)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Packs the clusters back to back into the index list. Lights that don't fit are dropped
+    // from the end of their cluster. Returns the number of light indices written.
+    fn pack(&mut self) -> usize {
+        let [x, y, z] = self.dims;
+        let words = self.offsets.as_slice_mut::<u32>();
+        words[..HEADER_WORDS].copy_from_slice(&[x, y, z, self.clusters.len() as u32]);
+
+        let offsets = &mut words[HEADER_WORDS..];
+        let indices = self.light_indices.as_slice_mut::<u32>();
+        let mut written = 0;
+        for (index, lights) in self.clusters.iter().enumerate() {
+            let count = lights.len().min(indices.len() - written);
+            indices[written..written + count].copy_from_slice(&lights[..count]);
+            offsets[index * 2] = written as u32;
+            offsets[index * 2 + 1] = count as u32;
+            written += count;
+        }
+
+        written
+    }
+}
+
+impl ReservedItem for ReservedLightClusters {
+    fn name(&self) -> String {
+        "meshi_light_clusters".to_string()
+    }
+
+    fn update(&mut self) -> Result<CommandStream<Executable>, FurikakeError> {
+        let mut cmd = CommandStream::new().begin();
+        if !self.dirty {
+            return Ok(cmd.end());
+        }
+
+        let written = self.pack();
+        let offset_bytes = std::mem::size_of::<u32>() * HEADER_WORDS
+            + std::mem::size_of::<LightCluster>() * self.clusters.len();
+        cmd = cmd.combine(self.offsets.sync_up_range(0, offset_bytes as u32).end());
+        let index_bytes = std::mem::size_of::<u32>() * written;
+        cmd = cmd.combine(self.light_indices.sync_up_range(0, index_bytes as u32).end());
+
+        self.dirty = false;
+        Ok(cmd.end())
+    }
+
+    fn binding(&self) -> ReservedBinding {
+        table_binding_from_indexed(IndexedBindingInfo {
+            resources: &[
+                IndexedResource {
+                    resource: ShaderResource::StorageBuffer(self.offsets.device().into()),
+                    slot: 0,
+                },
+                IndexedResource {
+                    resource: ShaderResource::StorageBuffer(self.light_indices.device().into()),
+                    slot: 1,
+                },
+            ],
+            binding: 0,
+        })
+    }
+
+    fn has_pending_update(&self) -> bool {
+        self.dirty
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashi::{ContextInfo, MemoryVisibility};
+
+    #[test]
+    fn packs_cluster_offsets_after_grid_header() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        // Shared buffers make the mapped offsets the ones the GPU reads.
+        let config = ReservationConfig {
+            visibility: MemoryVisibility::CpuAndGpu,
+        };
+        let mut clusters = ReservedLightClusters::with_config(&mut ctx, &config);
+
+        assert!(clusters.set_grid_dims(2, 2, 1));
+        assert!(!clusters.set_grid_dims(u32::MAX, 2, 1));
+        assert_eq!(clusters.grid_dims(), [2, 2, 1]);
+
+        clusters.set_cluster(0, &[3, 7]);
+        clusters.set_cluster(2, &[1, 4, 5]);
+        clusters.set_cluster(9, &[8]);
+        assert!(clusters.has_pending_update());
+
+        clusters.update().expect("update light clusters");
+        assert!(!clusters.has_pending_update());
+
+        let words = clusters.offsets.as_slice::<u32>();
+        assert_eq!(&words[..HEADER_WORDS], &[2, 2, 1, 4]);
+        let offsets: &[u32] = &words[HEADER_WORDS..HEADER_WORDS + 8];
+        assert_eq!(offsets, &[0, 2, 2, 0, 2, 3, 5, 0]);
+
+        let indices = clusters.light_indices.as_slice::<u32>();
+        assert_eq!(&indices[..5], &[3, 7, 1, 4, 5]);
+    }
+}
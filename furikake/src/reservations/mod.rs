@@ -11,33 +11,336 @@ pub mod bindless_skinning;
 pub mod bindless_textures;
 pub mod bindless_transformations;
 pub mod bindless_vertices;
+pub mod bone_palettes;
 pub mod camera;
 mod dirty;
+pub mod instances;
+pub mod light_clusters;
 pub mod per_obj_joints;
 pub mod particles;
 pub mod timing;
 pub use timing::*;
 pub(crate) use dirty::DirtyRange;
 
-use dashi::{cmd::Executable, CommandStream, Context, IndexedBindingInfo, IndexedResource};
+use dashi::{
+    cmd::Executable, BindTable, BindTableLayout, BindTableVariable, BindTableVariableType,
+    BufferInfo, BufferUsage, CommandStream, Context, Handle, IndexedBindingInfo, IndexedResource,
+    MemoryVisibility,
+};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
+use tare::utils::StagedBuffer;
 
 pub enum ReservedBinding {
     TableBinding {
         binding: u32,
         resources: Vec<IndexedResource>,
     },
+    /// Resources split across several `(set, binding)` slots, each bound in its set's table.
+    MultiTableBinding(Vec<ReservedTableBinding>),
+}
+
+/// One `(set, binding)` slot of a `ReservedBinding::MultiTableBinding`.
+///
+/// Shaders declare each slot as its own variable called `name`, and `GPUState::binding` hands
+/// out the owning item for every slot name as well as for the reservation's own name.
+#[derive(Clone)]
+pub struct ReservedTableBinding {
+    pub name: String,
+    pub set: u32,
+    pub binding: u32,
+    pub resources: Vec<IndexedResource>,
+}
+
+impl ReservedBinding {
+    /// Binding and resources of a single-binding reservation, or `None` for a multi-set one,
+    /// which can't be bound as one table.
+    pub fn single(self) -> Option<(u32, Vec<IndexedResource>)> {
+        match self {
+            ReservedBinding::TableBinding { binding, resources } => Some((binding, resources)),
+            ReservedBinding::MultiTableBinding(_) => None,
+        }
+    }
+
+    /// Every table variable the reservation `name` backs, with its resources: `name` itself
+    /// for a single-binding reservation, one per slot for a multi-set one.
+    pub fn table_variables(self, name: &str) -> Vec<(String, Vec<IndexedResource>)> {
+        match self {
+            ReservedBinding::TableBinding { resources, .. } => vec![(name.to_string(), resources)],
+            ReservedBinding::MultiTableBinding(slots) => slots
+                .into_iter()
+                .map(|slot| (slot.name, slot.resources))
+                .collect(),
+        }
+    }
 }
 
 pub trait ReservedItem {
     fn name(&self) -> String;
     fn update(&mut self) -> Result<CommandStream<Executable>, crate::error::FurikakeError>;
     fn binding(&self) -> ReservedBinding;
+    /// Descriptor type shaders must declare for this item's binding.
+    fn variable_type(&self) -> BindTableVariableType;
+
+    /// Counter bumped whenever the item's resource list changes shape (e.g. grows), so
+    /// anything cooked from an earlier `binding()` snapshot knows to rebuild.
+    fn epoch(&self) -> u64 {
+        0
+    }
+
+    /// Whether `update` has anything to upload. Items that can't tell report `true`.
+    fn has_pending_update(&self) -> bool {
+        true
+    }
+
+    /// Number of descriptors the item's binding declares in a layout. Defaults to the live
+    /// resource count; items backed by a fixed-size array report that size instead. Multi-set
+    /// items count every slot; recipes size each slot from its own resources.
+    fn descriptor_count(&self) -> u32 {
+        match self.binding() {
+            ReservedBinding::TableBinding { resources, .. } => resources.len() as u32,
+            ReservedBinding::MultiTableBinding(slots) => {
+                slots.iter().map(|slot| slot.resources.len() as u32).sum()
+            }
+        }
+    }
 
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// Options applied to the buffers backing each reservation.
+#[derive(Clone, Copy, Debug)]
+pub struct ReservationConfig {
+    /// `Gpu` keeps a device-local buffer and uploads from a host staging copy on `update`.
+    /// `CpuAndGpu` uses one host-visible buffer the GPU reads directly, which skips the staging
+    /// copies on unified-memory devices.
+    pub visibility: MemoryVisibility,
+}
+
+impl Default for ReservationConfig {
+    fn default() -> Self {
+        Self {
+            visibility: MemoryVisibility::Gpu,
+        }
+    }
+}
+
+impl ReservationConfig {
+    pub(crate) fn staged_buffer(&self, ctx: &mut Context, info: BufferInfo) -> StagedBuffer {
+        match self.visibility {
+            MemoryVisibility::CpuAndGpu => StagedBuffer::new_shared(ctx, info),
+            _ => StagedBuffer::new(ctx, info),
+        }
+    }
+}
+
+/// Largest slot count a slot-allocated reservation grows to. Slots are `u16`s and
+/// `u16::MAX` is kept free for invalid handles.
+pub(crate) const MAX_RESERVED_SLOTS: usize = u16::MAX as usize;
+
+/// Allocates a buffer like `data` with room for `slots` elements of `T`, copying the existing
+/// host contents over and filling the new tail with `T::default()`.
+///
+/// Nothing is uploaded; the caller marks the whole range dirty so the next `update` fills the
+/// new device buffer.
+pub(crate) fn grow_staged_buffer<T: Copy + Default>(
+    ctx: &mut Context,
+    data: &StagedBuffer,
+    debug_name: &'static str,
+    slots: usize,
+) -> StagedBuffer {
+    let info = BufferInfo {
+        debug_name,
+        byte_size: (std::mem::size_of::<T>() * slots) as u32,
+        visibility: Default::default(),
+        usage: BufferUsage::ALL,
+        initial_data: None,
+    };
+    let mut grown = if data.is_shared() {
+        StagedBuffer::new_shared(ctx, info)
+    } else {
+        StagedBuffer::new(ctx, info)
+    };
+
+    let old = data.as_slice::<T>();
+    let new = grown.as_slice_mut::<T>();
+    new[..old.len()].copy_from_slice(old);
+    new[old.len()..].fill(T::default());
+    grown
+}
+
+/// Grows a slot-allocated reservation to `capacity` elements of `T`, capped at
+/// `MAX_RESERVED_SLOTS`. Never shrinks; returns whether anything changed.
+///
+/// `data` is reallocated through `grow_staged_buffer` and the old buffer retired. The new slots
+/// go below the existing free list so they're handed out last, the whole array is marked dirty
+/// and `epoch` bumps so cooked tables rebind the new buffer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn grow_slots<T: Copy + Default>(
+    ctx: &mut Context,
+    debug_name: &'static str,
+    capacity: usize,
+    data: &mut StagedBuffer,
+    available: &mut Vec<u16>,
+    dirty: &mut DirtyRange,
+    retired: &mut RetiredBuffers,
+    epoch: &mut u64,
+) -> bool {
+    let current = data.as_slice::<T>().len();
+    let capacity = capacity.min(MAX_RESERVED_SLOTS);
+    if capacity <= current {
+        return false;
+    }
+
+    let grown = grow_staged_buffer::<T>(ctx, data, debug_name, capacity);
+    retired.retire(std::mem::replace(data, grown));
+
+    let mut slots: Vec<u16> = (current as u16..capacity as u16).rev().collect();
+    slots.append(available);
+    *available = slots;
+    dirty.mark_elements::<T>(0, capacity);
+    *epoch += 1;
+    true
+}
+
+/// GPU objects that were replaced while frames in flight may still read them. Each is destroyed
+/// only after `RETIRE_AFTER` more calls to `advance`, matching the frames the transient allocator
+/// keeps resources alive for.
+pub(crate) struct Retired<T: RetiredResource> {
+    items: Vec<(T, usize)>,
+}
+
+/// Buffers a grown reservation replaced, aged on every update.
+pub(crate) type RetiredBuffers = Retired<StagedBuffer>;
+
+/// Something `Retired` knows how to destroy.
+pub(crate) trait RetiredResource {
+    fn destroy(self, ctx: &mut Context);
+}
+
+impl RetiredResource for StagedBuffer {
+    fn destroy(self, ctx: &mut Context) {
+        ctx.destroy_buffer(self.device().handle);
+        if !self.is_shared() {
+            ctx.destroy_buffer(self.host().handle);
+        }
+    }
+}
+
+impl RetiredResource for Handle<BindTable> {
+    fn destroy(self, ctx: &mut Context) {
+        ctx.destroy_bind_table(self);
+    }
+}
+
+impl RetiredResource for Handle<BindTableLayout> {
+    fn destroy(self, ctx: &mut Context) {
+        ctx.destroy_bind_table_layout(self);
+    }
+}
+
+impl<T: RetiredResource> Default for Retired<T> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<T: RetiredResource> Retired<T> {
+    const RETIRE_AFTER: usize = 3;
+
+    pub(crate) fn retire(&mut self, item: T) {
+        self.items.push((item, 0));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Ages every retired object by one frame and destroys those old enough.
+    pub(crate) fn advance(&mut self, ctx: &mut Context) {
+        let mut kept = Vec::with_capacity(self.items.len());
+        for (item, age) in self.items.drain(..) {
+            if age + 1 < Self::RETIRE_AFTER {
+                kept.push((item, age + 1));
+            } else {
+                item.destroy(ctx);
+            }
+        }
+        self.items = kept;
+    }
+
+    /// Destroys every retired object right away, for when nothing can be in flight anymore.
+    pub(crate) fn destroy_all(&mut self, ctx: &mut Context) {
+        for (item, _) in self.items.drain(..) {
+            item.destroy(ctx);
+        }
+    }
+}
+
+/// Host-side contents of a slot-allocated reservation: every element plus the free slot list.
+/// Holds no GPU handles, so it can be kept around or serialized independently of the context.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReservationSnapshot<T> {
+    pub data: Vec<T>,
+    pub available: Vec<u16>,
+}
+
+impl<T> Default for ReservationSnapshot<T> {
+    fn default() -> Self {
+        Self {
+            data: Vec::new(),
+            available: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy> ReservationSnapshot<T> {
+    /// Copies `data`'s host-side array of `T` and the free slot list.
+    pub(crate) fn capture(data: &StagedBuffer, available: &[u16]) -> Self {
+        Self {
+            data: data.as_slice::<T>().to_vec(),
+            available: available.to_vec(),
+        }
+    }
+
+    /// Overwrites `data` and `available` with this snapshot, dropping slots beyond the current
+    /// capacity, and marks the restored range dirty.
+    pub(crate) fn restore_into(
+        &self,
+        data: &mut StagedBuffer,
+        available: &mut Vec<u16>,
+        dirty: &mut DirtyRange,
+    ) {
+        let slots = data.as_slice_mut::<T>();
+        let count = self.data.len().min(slots.len());
+        slots[..count].copy_from_slice(&self.data[..count]);
+        *available = self
+            .available
+            .iter()
+            .copied()
+            .filter(|slot| (*slot as usize) < slots.len())
+            .collect();
+        dirty.mark_elements::<T>(0, count);
+    }
+}
+
+/// Layout variable describing `item`'s binding, sized by its `descriptor_count`. Fails for
+/// multi-set items, which have no single binding.
+pub fn reserved_layout_variable(
+    item: &dyn ReservedItem,
+    var_type: BindTableVariableType,
+) -> Result<BindTableVariable, crate::error::FurikakeError> {
+    let (binding, _) = item.binding().single().ok_or_else(|| {
+        crate::error::FurikakeError::MultiSetReservation { name: item.name() }
+    })?;
+    Ok(BindTableVariable {
+        var_type,
+        binding,
+        count: item.descriptor_count(),
+    })
+}
+
 pub(crate) fn table_binding_from_indexed(info: IndexedBindingInfo<'_>) -> ReservedBinding {
     ReservedBinding::TableBinding {
         binding: info.binding,
@@ -1,14 +1,16 @@
 use std::ptr::NonNull;
 
 use dashi::{
-    cmd::Executable, BufferInfo, BufferUsage, CommandStream, Context, Handle, IndexedBindingInfo,
-    IndexedResource, ShaderResource,
+    cmd::Executable, BindTableVariableType, BufferInfo, BufferUsage, CommandStream, Context, Handle,
+    IndexedBindingInfo, IndexedResource, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 
 use crate::{error::FurikakeError, types::ParticleState};
 
-use super::{table_binding_from_indexed, DirtyRange, ReservedBinding, ReservedItem};
+use super::{
+    table_binding_from_indexed, DirtyRange, ReservationConfig, ReservedBinding, ReservedItem,
+};
 
 pub struct ReservedParticles {
     ctx: NonNull<Context>,
@@ -19,11 +21,15 @@ pub struct ReservedParticles {
 
 impl ReservedParticles {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
         const START_SIZE: usize = 16384;
 
         let available: Vec<u16> = (0..START_SIZE as u16).collect();
         let start = vec![ParticleState::default(); START_SIZE];
-        let data = StagedBuffer::new(
+        let data = config.staged_buffer(
             ctx,
             BufferInfo {
                 debug_name: "[FURIKAKE] Particle Buffer",
@@ -100,6 +106,14 @@ impl ReservedItem for ReservedParticles {
         })
     }
 
+    fn has_pending_update(&self) -> bool {
+        self.dirty.is_dirty()
+    }
+
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Storage
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
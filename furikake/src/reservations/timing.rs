@@ -1,12 +1,13 @@
 use dashi::{
-    cmd::Executable, Buffer, BufferInfo, BufferView, CommandStream, Context, Handle, IndexedResource, MemoryVisibility, ShaderResource
+    cmd::Executable, BindTableVariableType, Buffer, BufferInfo, BufferView, CommandStream, Context,
+    Handle, IndexedResource, MemoryVisibility, ShaderResource,
 };
 use tare::utils::StagedBuffer;
 use std::time::Instant;
 
 use crate::error::FurikakeError;
 
-use super::{DirtyRange, ReservedBinding, ReservedItem};
+use super::{DirtyRange, ReservationConfig, ReservedBinding, ReservedItem};
 #[repr(C)]
 struct TimeData {
     current_time_ms: f32,
@@ -21,7 +22,11 @@ pub struct ReservedTiming {
 
 impl ReservedTiming {
     pub fn new(ctx: &mut Context) -> Self {
-        let buffer = StagedBuffer::new(ctx, BufferInfo {
+        Self::with_config(ctx, &ReservationConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: &ReservationConfig) -> Self {
+        let buffer = config.staged_buffer(ctx, BufferInfo {
                 debug_name: "[FURIKAKE] Timing Buffer",
                 byte_size: std::mem::size_of::<TimeData>() as u32,
                 visibility: MemoryVisibility::CpuAndGpu,
@@ -79,6 +84,10 @@ impl ReservedItem for ReservedTiming {
         }
     }
 
+    fn variable_type(&self) -> BindTableVariableType {
+        BindTableVariableType::Uniform
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1,6 +1,4 @@
-use crate::{GPUState, ReservedMetadata};
-
-#[cfg(test)]
+use crate::{GPUState, reservations::ReservedBinding};
 use dashi::BindTableVariableType;
 
 #[derive(Default, Debug)]
@@ -15,15 +13,42 @@ pub struct ResolveResult {
 pub struct Resolver {
     resolved: Vec<ResolveResult>,
 }
+// A variable shaders must declare: a reservation, or one slot of a multi-set reservation.
+struct ExpectedBinding {
+    name: String,
+    kind: BindTableVariableType,
+    set: u32,
+}
+
 impl Resolver {
     pub fn new<T: GPUState>(
-        _state: &T,
+        state: &T,
         result: &bento::CompilationResult,
     ) -> Result<Self, crate::error::FurikakeError> {
-        let names = T::reserved_metadata();
+        // Prefer the live item's own descriptor type, falling back to the static table for
+        // names the state can't hand out. Multi-set reservations expect each slot in its own set.
+        let mut expected = Vec::new();
+        for meta in state.reserved_metadata() {
+            let item = state.binding(meta.name).ok();
+            let kind = item.map(|item| item.variable_type()).unwrap_or(meta.kind);
+            match item.map(|item| item.binding()) {
+                Some(ReservedBinding::MultiTableBinding(slots)) => {
+                    expected.extend(slots.into_iter().map(|slot| ExpectedBinding {
+                        name: slot.name,
+                        kind,
+                        set: slot.set,
+                    }));
+                }
+                _ => expected.push(ExpectedBinding {
+                    name: meta.name.to_string(),
+                    kind,
+                    set: meta.set,
+                }),
+            }
+        }
 
         Ok(Self {
-            resolved: Self::reflect_bindings(names, result)?,
+            resolved: Self::reflect_bindings(&expected, result)?,
         })
     }
 
@@ -32,7 +57,7 @@ impl Resolver {
     }
 
     fn reflect_bindings(
-        names: &[ReservedMetadata],
+        names: &[ExpectedBinding],
         res: &bento::CompilationResult,
     ) -> Result<Vec<ResolveResult>, crate::error::FurikakeError> {
         let mut results = Vec::new();
@@ -46,6 +71,13 @@ impl Resolver {
                         ),
                     });
                 }
+                if found.set != meta.set {
+                    return Err(crate::error::FurikakeError::ReservedSetMismatch {
+                        name: meta.name.clone(),
+                        expected: meta.set,
+                        found: found.set,
+                    });
+                }
 
                 results.push(ResolveResult {
                     name: found.name.clone(),
@@ -55,7 +87,7 @@ impl Resolver {
                 });
             } else {
                 return Err(crate::error::FurikakeError::MissingReservedBinding {
-                    name: meta.name.to_string(),
+                    name: meta.name.clone(),
                 });
             }
         }
@@ -63,22 +95,42 @@ impl Resolver {
     }
 }
 
+/// Resolves every shader against `state`, collecting one error per shader that fails instead of
+/// stopping at the first. Meant as a pre-flight check before building pipelines.
+pub fn validate_shaders<T: GPUState>(
+    state: &T,
+    shaders: &[bento::CompilationResult],
+) -> Result<(), Vec<crate::error::FurikakeError>> {
+    let errors: Vec<_> = shaders
+        .iter()
+        .filter_map(|shader| Resolver::new(state, shader).err())
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::error::FurikakeError;
+    use crate::ReservedMetadata;
 
     struct TestState;
 
     impl GPUState for TestState {
-        fn reserved_names() -> &'static [&'static str] {
+        fn reserved_names(&self) -> &[&'static str] {
             &["meshi_timing"]
         }
 
-        fn reserved_metadata() -> &'static [ReservedMetadata] {
+        fn reserved_metadata(&self) -> &[ReservedMetadata] {
             &[ReservedMetadata {
                 name: "meshi_timing",
                 kind: BindTableVariableType::Uniform,
+                set: 0,
             }]
         }
 
@@ -104,9 +156,14 @@ mod tests {
                 inputs: Vec::new(),
                 outputs: Vec::new(),
                 workgroup_size: None,
+                workgroup_size_specializable: false,
                 vertex: Default::default(),
+                may_discard: false,
+                uses_atomics: false,
+                push_constants: None,
             },
             spirv: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -133,6 +190,9 @@ mod tests {
                 binding: 0,
                 count: 1,
             },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
         }]);
 
         let err = Resolver::new(&TestState, &res).unwrap_err();
@@ -144,4 +204,60 @@ mod tests {
             other => panic!("unexpected error {other:?}", other = other),
         }
     }
+
+    #[test]
+    fn reports_set_mismatch() {
+        let res = make_result(vec![bento::ShaderVariable {
+            name: "meshi_timing".to_string(),
+            set: 2,
+            kind: dashi::BindTableVariable {
+                var_type: BindTableVariableType::Uniform,
+                binding: 0,
+                count: 1,
+            },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
+        }]);
+
+        let err = Resolver::new(&TestState, &res).unwrap_err();
+        match err {
+            FurikakeError::ReservedSetMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                assert_eq!(name, "meshi_timing");
+                assert_eq!(expected, 0);
+                assert_eq!(found, 2);
+            }
+            other => panic!("unexpected error {other:?}", other = other),
+        }
+    }
+
+    #[test]
+    fn validate_shaders_collects_every_failure() {
+        let good = make_result(vec![bento::ShaderVariable {
+            name: "meshi_timing".to_string(),
+            set: 0,
+            kind: dashi::BindTableVariable {
+                var_type: BindTableVariableType::Uniform,
+                binding: 0,
+                count: 1,
+            },
+            spec_count: None,
+            unbounded: false,
+            texel_buffer: None,
+        }]);
+        let missing = make_result(vec![]);
+
+        let errors = validate_shaders(&TestState, &[good.clone(), missing]).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            FurikakeError::MissingReservedBinding { name } if name == "meshi_timing"
+        ));
+
+        assert!(validate_shaders(&TestState, &[good]).is_ok());
+    }
 }
@@ -1,8 +1,9 @@
 use dashi::{Handle, ImageView, Sampler};
 use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum ProjectionKind {
     Perspective = 0,
     Orthographic = 1,
@@ -15,7 +16,7 @@ impl Default for ProjectionKind {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Camera {
     /// Camera → world transform.
     pub world_from_camera: Mat4,
@@ -199,11 +200,42 @@ impl Camera {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Transformation {
     pub transform: Mat4,
 }
 
+/// Per-instance record resolving both the transform and material of a draw from one index.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InstanceData {
+    /// Slot of the instance's transform in `meshi_bindless_transformations`.
+    pub transform_index: u32,
+    /// Slot of the instance's material in `meshi_bindless_materials`.
+    pub material_index: u32,
+    /// Application-defined per-instance flags.
+    pub flags: u32,
+    /// Padding to keep the struct 16 bytes when used in buffers.
+    pub _padding: u32,
+}
+
+/// Range of `meshi_bone_palettes` holding one skeleton's bone matrices; shaders read bone `i`
+/// at `palette[base + i]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BonePalette {
+    pub base: u32,
+    pub bone_count: u32,
+}
+
+/// Range of `meshi_light_clusters`' light index list holding the lights that affect one
+/// cluster.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LightCluster {
+    pub offset: u32,
+    pub count: u32,
+}
+
 /// GPU-facing handle identifier for bindless resources.
 pub type GpuHandle = u32;
 
@@ -452,7 +484,7 @@ impl Default for Light {
     }
 }
 #[repr(C)]
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Material {
     pub base_color_texture_id: u32,
     pub normal_texture_id: u32,
@@ -474,6 +506,7 @@ pub const MATERIAL_FLAG_EMISSIVE_ONLY: u16 = 1 << 4;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::mem::offset_of;
 
     fn assert_matrix_close(a: Mat4, b: Mat4) {
         for (lhs, rhs) in a.to_cols_array().iter().zip(b.to_cols_array().iter()) {
@@ -481,6 +514,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn camera_matches_reflected_shader_header() {
+        let header = r#"
+            struct Camera {
+                mat4 world_from_camera;
+                mat4 projection;
+                vec2 viewport;
+                float near;
+                float far;
+                float fov_y_radians;
+                uint projection_kind;
+                float _padding;
+            };
+        "#;
+
+        let compiler = bento::Compiler::new().expect("create compiler");
+        let layouts = compiler
+            .reflect_header(header, bento::ShaderLang::Glsl)
+            .expect("reflect header");
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts[0].name, "Camera");
+
+        let reflected: Vec<(&str, u32)> = layouts[0]
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.offset))
+            .collect();
+        let expected = [
+            ("world_from_camera", offset_of!(Camera, world_from_camera)),
+            ("projection", offset_of!(Camera, projection)),
+            ("viewport", offset_of!(Camera, viewport)),
+            ("near", offset_of!(Camera, near)),
+            ("far", offset_of!(Camera, far)),
+            ("fov_y_radians", offset_of!(Camera, fov_y_radians)),
+            ("projection_kind", offset_of!(Camera, projection_kind)),
+            ("_padding", offset_of!(Camera, _padding)),
+        ]
+        .map(|(name, offset)| (name, offset as u32));
+        assert_eq!(reflected, expected);
+    }
+
     #[test]
     fn defaults_to_perspective_projection() {
         let camera = Camera::default();
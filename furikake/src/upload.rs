@@ -0,0 +1,206 @@
+use dashi::{
+    BufferInfo, BufferUsage, CommandQueueInfo2, CommandStream, Context, Handle, Image, ImageInfo,
+    ImageView, QueueType, SubmitInfo, SubresourceRange, driver::command::CopyBufferImage,
+};
+use tare::utils::StagedBuffer;
+
+use crate::{error::FurikakeError, reservations::bindless_textures::ReservedBindlessTextures};
+
+// Keeps every image's data at an offset valid for any texel size.
+const STAGING_ALIGNMENT: usize = 16;
+
+/// Creates every image in `images`, uploads all of their data through one staging buffer in a
+/// single submission, and registers each into `textures`.
+///
+/// Returns the texture ids in the same order as `images`. Any `initial_data` on the infos is
+/// ignored in favour of the paired bytes. Blocks until the upload has finished. Fails without
+/// registering anything when `textures` can't hold every image.
+pub fn upload_textures(
+    ctx: &mut Context,
+    textures: &mut ReservedBindlessTextures,
+    images: &[(ImageInfo<'_>, &[u8])],
+) -> Result<Vec<u16>, FurikakeError> {
+    if images.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut offsets = Vec::with_capacity(images.len());
+    let mut staging_size = 0;
+    for (_, data) in images {
+        offsets.push(staging_size);
+        staging_size += data.len().next_multiple_of(STAGING_ALIGNMENT);
+    }
+
+    let mut staging = StagedBuffer::new_shared(
+        ctx,
+        BufferInfo {
+            debug_name: "[FURIKAKE] Texture Upload Staging",
+            byte_size: staging_size as u32,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        },
+    );
+    let mapped = staging.as_slice_mut::<u8>();
+    for ((_, data), offset) in images.iter().zip(&offsets) {
+        mapped[*offset..*offset + data.len()].copy_from_slice(data);
+    }
+
+    // Both paths out of the upload release the staging buffer, and a failed upload also
+    // releases the images it already created.
+    let mut created = Vec::with_capacity(images.len());
+    let uploaded = copy_from_staging(ctx, &staging, images, &offsets, &mut created);
+    ctx.destroy_buffer(staging.host().handle);
+    if let Err(err) = uploaded {
+        for img in created {
+            ctx.destroy_image(img);
+        }
+        return Err(err);
+    }
+
+    let ids: Vec<u16> = created
+        .iter()
+        .map_while(|img| {
+            textures.add_texture(ImageView {
+                img: *img,
+                ..Default::default()
+            })
+        })
+        .collect();
+    if ids.len() < created.len() {
+        for id in ids {
+            textures.remove_texture(id);
+        }
+        for img in created {
+            ctx.destroy_image(img);
+        }
+        return Err(FurikakeError::BindlessTexturesFull);
+    }
+
+    Ok(ids)
+}
+
+// Creates each image into `created` and copies its data out of `staging`, blocking until the
+// copies have finished.
+fn copy_from_staging(
+    ctx: &mut Context,
+    staging: &StagedBuffer,
+    images: &[(ImageInfo<'_>, &[u8])],
+    offsets: &[usize],
+    created: &mut Vec<Handle<Image>>,
+) -> Result<(), FurikakeError> {
+    for (info, _) in images {
+        created.push(ctx.make_image(&ImageInfo {
+            initial_data: None,
+            ..info.clone()
+        })?);
+    }
+
+    let mut stream = CommandStream::new().begin();
+    for (image, offset) in created.iter().zip(offsets) {
+        stream = stream.copy_buffer_to_image(&CopyBufferImage {
+            src: staging.host().handle,
+            dst: *image,
+            range: SubresourceRange::default(),
+            src_offset: *offset as u32,
+        });
+    }
+    let stream = stream.end();
+
+    let mut ring = ctx.make_command_ring(&CommandQueueInfo2 {
+        debug_name: "[FURIKAKE] Texture Upload",
+        parent: None,
+        queue_type: QueueType::Graphics,
+    })?;
+    ring.record(|cmd| {
+        stream.append(cmd);
+    })?;
+    ring.submit(&SubmitInfo::default())?;
+    ring.wait_all()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashi::{
+        ContextInfo, Format, IndexedResource, MemoryVisibility, ShaderResource,
+        driver::command::CopyImageBuffer,
+    };
+
+    #[test]
+    fn uploads_several_textures_in_one_call() {
+        let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+        let mut textures = ReservedBindlessTextures::new(&mut ctx);
+
+        const COLORS: [[u8; 4]; 3] = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        let info = ImageInfo {
+            debug_name: "uploaded",
+            dim: [1, 1, 1],
+            format: Format::RGBA8,
+            ..Default::default()
+        };
+        let images: Vec<_> = COLORS
+            .iter()
+            .map(|color| (info.clone(), &color[..]))
+            .collect();
+
+        let ids = upload_textures(&mut ctx, &mut textures, &images).expect("upload textures");
+
+        assert_eq!(ids.len(), 3);
+        assert!(ids[0] != ids[1] && ids[1] != ids[2] && ids[0] != ids[2]);
+
+        let readback = ctx
+            .make_buffer(&BufferInfo {
+                debug_name: "uploaded readback",
+                byte_size: 4 * COLORS.len() as u32,
+                visibility: MemoryVisibility::CpuAndGpu,
+                usage: BufferUsage::ALL,
+                ..Default::default()
+            })
+            .expect("create readback buffer");
+
+        let mut stream = CommandStream::new().begin();
+        for (index, id) in ids.iter().enumerate() {
+            let Some(IndexedResource {
+                resource: ShaderResource::Image(view),
+                ..
+            }) = textures.image_resource(*id)
+            else {
+                panic!("texture {id} has no image");
+            };
+            stream = stream.copy_image_to_buffer(&CopyImageBuffer {
+                src: view.img,
+                dst: readback,
+                range: SubresourceRange::default(),
+                dst_offset: 4 * index as u32,
+            });
+        }
+        let stream = stream.end();
+
+        let mut ring = ctx
+            .make_command_ring(&CommandQueueInfo2 {
+                debug_name: "uploaded readback",
+                parent: None,
+                queue_type: QueueType::Graphics,
+            })
+            .expect("create command ring");
+        ring.record(|cmd| {
+            stream.append(cmd);
+        })
+        .expect("record readback");
+        ring.submit(&SubmitInfo::default())
+            .expect("submit readback");
+        ring.wait_all().expect("wait for readback");
+
+        let data = ctx
+            .map_buffer::<u8>(readback.into())
+            .expect("map readback buffer")
+            .to_vec();
+        ctx.unmap_buffer(readback).expect("unmap readback buffer");
+
+        for (chunk, color) in data.chunks_exact(4).zip(COLORS) {
+            assert_eq!(chunk, color);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use bento::builder::PSOBuilder;
+use dashi::driver::command::DrawIndexed;
+use dashi::*;
+use furikake::reservations::ReservationConfig;
+use furikake::reservations::bindless_materials::ReservedBindlessMaterials;
+use furikake::{BindlessState, PSOBuilderFurikakeExt};
+use tare::graph::*;
+
+const WIDTH: u32 = 4;
+const HEIGHT: u32 = 4;
+// First slot past the material buffer's starting capacity.
+const GROWN_SLOT: u16 = 4096;
+
+const FULLSCREEN_VERTEX: &str = r#"
+#version 450
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+const MATERIAL_FRAGMENT: &str = r#"
+#version 450
+struct Material {
+    uint base_color_texture_id;
+    uint normal_texture_id;
+    uint metallic_roughness_texture_id;
+    uint occlusion_texture_id;
+    uint emissive_texture_id;
+    uint material_flags;
+    uint render_mask;
+    uint _padding;
+};
+layout(set = 0, binding = 0) buffer Materials {
+    Material materials[];
+} meshi_bindless_materials;
+layout(location = 0) out vec4 color;
+void main() {
+    uint flags = meshi_bindless_materials.materials[4096].material_flags;
+    color = vec4(float(flags) / 255.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+fn compile_shader(stage: ShaderType, source: &str) -> bento::CompilationResult {
+    let compiler = bento::Compiler::new().expect("compiler should initialize");
+    let request = bento::Request {
+        name: None,
+        lang: bento::ShaderLang::Glsl,
+        stage,
+        ..Default::default()
+    };
+
+    compiler
+        .compile(source.as_bytes(), &request)
+        .expect("shader should compile")
+}
+
+// Draws one fullscreen triangle with `pso` and returns the target's texels.
+fn draw_texels(ctx: &mut Context, pso: &bento::builder::PSO) -> Vec<u8> {
+    let mut graph = RenderGraph::new(ctx);
+    graph.set_global_bind_tables(pso.tables());
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[GROWN MATERIAL TARGET]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+    let geometry = graph.make_buffer(&BufferInfo {
+        debug_name: "[GROWN MATERIAL INDICES]",
+        byte_size: 12,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(bytemuck::cast_slice(&[0u32, 1, 2])),
+    });
+    let viewport = Viewport {
+        area: FRect2D {
+            w: WIDTH as f32,
+            h: HEIGHT as f32,
+            ..Default::default()
+        },
+        scissor: Rect2D {
+            w: WIDTH,
+            h: HEIGHT,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pipeline = pso.handle;
+
+    graph.add_subpass_with_context(
+        &SubpassInfo {
+            viewport,
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            clear_values: [
+                Some(ClearValue::Color([0.0, 0.0, 0.0, 1.0])),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            ..Default::default()
+        },
+        &[],
+        move |stream, pass| {
+            let stream = stream
+                .bind_graphics_pipeline(pipeline)
+                .update_viewport(&viewport);
+            pass.draw_indexed(
+                stream,
+                &DrawIndexed {
+                    vertices: geometry.handle,
+                    indices: geometry.handle,
+                    index_count: 3,
+                    ..Default::default()
+                },
+            )
+            .unbind_graphics_pipeline()
+        },
+    );
+    graph.execute();
+
+    let readback = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: "[READBACK]",
+            byte_size: WIDTH * HEIGHT * 4,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        })
+        .expect("create readback buffer");
+    graph.read_image(target.view, readback);
+
+    let data = ctx
+        .map_buffer::<u8>(readback.into())
+        .expect("map readback buffer")
+        .to_vec();
+    ctx.unmap_buffer(readback).expect("unmap readback buffer");
+    ctx.destroy_buffer(readback);
+    data
+}
+
+#[test]
+fn registered_pso_tables_follow_grown_reservations() {
+    let mut ctx = Context::headless(&ContextInfo::default()).expect("create context");
+    let mut state = BindlessState::with_config(
+        &mut ctx,
+        ReservationConfig {
+            visibility: MemoryVisibility::CpuAndGpu,
+        },
+    );
+
+    let pso = PSOBuilder::new()
+        .vertex_compiled(Some(compile_shader(ShaderType::Vertex, FULLSCREEN_VERTEX)))
+        .fragment_compiled(Some(compile_shader(ShaderType::Fragment, MATERIAL_FRAGMENT)))
+        .add_reserved_table_variable(&state, "meshi_bindless_materials")
+        .expect("reserve material table")
+        .set_attachment_format(0, Format::RGBA8)
+        .build(&mut ctx)
+        .expect("material pipeline");
+    state.register_pso_tables(&pso);
+
+    state
+        .reserved_mut::<ReservedBindlessMaterials, _>("meshi_bindless_materials", |materials| {
+            assert_eq!(materials.capacity(), GROWN_SLOT as usize);
+            materials.grow(materials.capacity() * 2);
+            materials.material_mut(Handle::new(GROWN_SLOT, 0)).material_flags = 255;
+        })
+        .expect("grow materials");
+
+    // Age the replaced buffer past the frames in flight so it is destroyed before the draw.
+    for _ in 0..4 {
+        state.update().expect("advance bindless state");
+    }
+
+    let texels = draw_texels(&mut ctx, &pso);
+    assert!(texels.chunks_exact(4).all(|texel| texel == [255, 0, 0, 255]));
+}
@@ -81,11 +81,11 @@ fn mutates_all_bindless_reservations_across_frames() {
         let mut ids = [0u32; 5];
         state
             .reserved_mut::<ReservedBindlessTextures, _>("meshi_bindless_textures", |textures| {
-                ids[0] = textures.add_texture(base_view) as u32;
-                ids[1] = textures.add_texture(normal_view) as u32;
-                ids[2] = textures.add_texture(roughness_view) as u32;
-                ids[3] = textures.add_texture(occlusion_view) as u32;
-                ids[4] = textures.add_texture(emissive_view) as u32;
+                ids[0] = textures.add_texture(base_view).expect("texture slot") as u32;
+                ids[1] = textures.add_texture(normal_view).expect("texture slot") as u32;
+                ids[2] = textures.add_texture(roughness_view).expect("texture slot") as u32;
+                ids[3] = textures.add_texture(occlusion_view).expect("texture slot") as u32;
+                ids[4] = textures.add_texture(emissive_view).expect("texture slot") as u32;
             })
             .expect("allocate bindless textures");
 
@@ -177,8 +177,8 @@ fn mutates_all_bindless_reservations_across_frames() {
         let mut ids = (0u32, 0u32);
         state
             .reserved_mut::<ReservedBindlessTextures, _>("meshi_bindless_textures", |textures| {
-                ids.0 = textures.add_texture(updated_base_view) as u32;
-                ids.1 = textures.add_texture(updated_normal_view) as u32;
+                ids.0 = textures.add_texture(updated_base_view).expect("texture slot") as u32;
+                ids.1 = textures.add_texture(updated_normal_view).expect("texture slot") as u32;
              })
             .expect("allocate updated textures");
         ids
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -19,61 +19,20 @@ pub(crate) fn build_define_map(defines: &[String]) -> HashMap<String, Option<Str
     define_map
 }
 
-fn resolve_imports(
-    path: &Path,
-    include_dir: &Path,
-    visited: &mut HashSet<PathBuf>,
-) -> std::io::Result<String> {
-    let source = fs::read_to_string(path)?;
-    let display_path = path.display().to_string();
-    let mut resolved = String::new();
-
-    resolved.push_str(&format!("#line 1 \"{display_path}\"\n"));
-
-    for (line_index, line) in source.lines().enumerate() {
-        let trimmed = line.trim();
-        if let Some(rest) = trimmed.strip_prefix("import ") {
-            let module_name = rest.trim_end_matches(';').trim();
-            let module_path = include_dir.join(format!("{module_name}.slang"));
-
-            if visited.insert(module_path.clone()) {
-                let inlined = resolve_imports(&module_path, include_dir, visited)?;
-                resolved.push_str(&format!("\n// begin include {module_name}\n"));
-                resolved.push_str(&inlined);
-                resolved.push_str(&format!("\n// end include {module_name}\n"));
-            }
-
-            let next_line = line_index + 2;
-            resolved.push_str(&format!("#line {next_line} \"{display_path}\"\n"));
-            continue;
-        }
-
-        resolved.push_str(line);
-        resolved.push('\n');
-    }
-
-    Ok(resolved)
-}
-
-fn resolve_with_includes_impl(source: &str, include: &str) -> String {
-    let include_dir = include.trim().trim_start_matches("-I");
-    let include_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(include_dir);
-    let source_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(source);
-    let mut visited = HashSet::new();
-
-    resolve_imports(&source_path, &include_dir, &mut visited)
-        .unwrap_or_else(|err| panic!("Failed to resolve includes for {source}: {err}"))
+/// Reads one of miso's bundled shaders; its imports are resolved by bento from `include_dirs`.
+fn shader_source(path: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()))
 }
 
-macro_rules! resolve_with_includes {
-    ($a:expr, $b:expr) => {
-        crate::resolve_with_includes_impl($a, $b)
-    };
+fn include_dirs() -> Vec<PathBuf> {
+    vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("src/slang/include")]
 }
 
 pub fn stddeferred(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!("src/slang/src/stdvert.slang", "-Isrc/slang/include/");
-    let fshader = resolve_with_includes!("src/slang/src/stdfrag.slang", "-Isrc/slang/include/");
+    let vshader = shader_source("src/slang/src/stdvert.slang");
+    let fshader = shader_source("src/slang/src/stdfrag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -84,6 +43,8 @@ pub fn stddeferred(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -110,10 +71,8 @@ pub fn stddeferred(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn gpudeferred(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader =
-        resolve_with_includes!("src/slang/src/gpudeferred_vert.slang", "-Isrc/slang/include/");
-    let fshader =
-        resolve_with_includes!("src/slang/src/gpudeferred_frag.slang", "-Isrc/slang/include/");
+    let vshader = shader_source("src/slang/src/gpudeferred_vert.slang");
+    let fshader = shader_source("src/slang/src/gpudeferred_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -124,6 +83,8 @@ pub fn gpudeferred(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -150,10 +111,8 @@ pub fn gpudeferred(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn gpuforward(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader =
-        resolve_with_includes!("src/slang/src/gpuforward_vert.slang", "-Isrc/slang/include/");
-    let fshader =
-        resolve_with_includes!("src/slang/src/gpuforward_frag.slang", "-Isrc/slang/include/");
+    let vshader = shader_source("src/slang/src/gpuforward_vert.slang");
+    let fshader = shader_source("src/slang/src/gpuforward_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -164,6 +123,8 @@ pub fn gpuforward(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -190,14 +151,8 @@ pub fn gpuforward(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stdforward(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!(
-        "src/slang/src/stdforward_vert.slang",
-        "-Isrc/slang/include/"
-    );
-    let fshader = resolve_with_includes!(
-        "src/slang/src/stdforward_frag.slang",
-        "-Isrc/slang/include/"
-    );
+    let vshader = shader_source("src/slang/src/stdforward_vert.slang");
+    let fshader = shader_source("src/slang/src/stdforward_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -208,6 +163,8 @@ pub fn stdforward(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -234,14 +191,8 @@ pub fn stdforward(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stddeferred_combine(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!(
-        "src/slang/src/stddeferred_combine_vert.slang",
-        "-Isrc/slang/include/"
-    );
-    let fshader = resolve_with_includes!(
-        "src/slang/src/stddeferred_combine_frag.slang",
-        "-Isrc/slang/include/"
-    );
+    let vshader = shader_source("src/slang/src/stddeferred_combine_vert.slang");
+    let fshader = shader_source("src/slang/src/stddeferred_combine_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -252,6 +203,8 @@ pub fn stddeferred_combine(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -278,8 +231,8 @@ pub fn stddeferred_combine(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stdsky(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!("src/slang/src/stdsky_vert.slang", "-Isrc/slang/include/");
-    let fshader = resolve_with_includes!("src/slang/src/stdsky_frag.slang", "-Isrc/slang/include/");
+    let vshader = shader_source("src/slang/src/stdsky_vert.slang");
+    let fshader = shader_source("src/slang/src/stdsky_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -290,6 +243,8 @@ pub fn stdsky(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -316,10 +271,8 @@ pub fn stdsky(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stdocean(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader =
-        resolve_with_includes!("src/slang/src/stdocean_vert.slang", "-Isrc/slang/include/");
-    let fshader =
-        resolve_with_includes!("src/slang/src/stdocean_frag.slang", "-Isrc/slang/include/");
+    let vshader = shader_source("src/slang/src/stdocean_vert.slang");
+    let fshader = shader_source("src/slang/src/stdocean_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -330,6 +283,8 @@ pub fn stdocean(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -356,14 +311,8 @@ pub fn stdocean(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stdbillboard(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!(
-        "src/slang/src/stdbillboard_vert.slang",
-        "-Isrc/slang/include/"
-    );
-    let fshader = resolve_with_includes!(
-        "src/slang/src/stdbillboard_frag.slang",
-        "-Isrc/slang/include/"
-    );
+    let vshader = shader_source("src/slang/src/stdbillboard_vert.slang");
+    let fshader = shader_source("src/slang/src/stdbillboard_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -374,6 +323,8 @@ pub fn stdbillboard(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -400,14 +351,8 @@ pub fn stdbillboard(defines: &[String]) -> Vec<CompilationResult> {
 }
 
 pub fn stdparticle(defines: &[String]) -> Vec<CompilationResult> {
-    let vshader = resolve_with_includes!(
-        "src/slang/src/stdparticle_vert.slang",
-        "-Isrc/slang/include/"
-    );
-    let fshader = resolve_with_includes!(
-        "src/slang/src/stdparticle_frag.slang",
-        "-Isrc/slang/include/"
-    );
+    let vshader = shader_source("src/slang/src/stdparticle_vert.slang");
+    let fshader = shader_source("src/slang/src/stdparticle_frag.slang");
     let define_map = build_define_map(defines);
 
     let compiler = Compiler::new().expect("Failed to create shader compiler");
@@ -418,6 +363,8 @@ pub fn stdparticle(defines: &[String]) -> Vec<CompilationResult> {
         optimization: OptimizationLevel::Performance,
         debug_symbols: true,
         defines: define_map,
+        include_dirs: include_dirs(),
+        ..Default::default()
     };
 
     let vertex = compiler
@@ -450,15 +397,6 @@ mod tests {
     use dashi::Context;
     use std::collections::{HashMap, HashSet};
 
-    #[test]
-    fn resolve_with_includes_inlines_imports() {
-        let resolved =
-            resolve_with_includes_impl("src/slang/src/stdvert.slang", "-Isrc/slang/include/");
-
-        assert!(resolved.contains("// begin include bindless"));
-        assert!(!resolved.contains("import bindless;"));
-    }
-
     #[test]
     fn build_define_map_parses_values_and_flags() {
         let defines = vec!["FOO=bar".to_string(), "BAZ".to_string()];
@@ -481,6 +419,24 @@ mod tests {
         assert!(results.iter().all(|r| !r.spirv.is_empty()));
     }
 
+    #[test]
+    fn stddeferred_vertex_layout_compatibility() {
+        let results = stddeferred(&[]);
+        let layout = results
+            .iter()
+            .find(|r| r.stage == dashi::ShaderType::Vertex)
+            .and_then(|r| r.metadata.vertex.clone())
+            .expect("std vertex layout");
+        assert!(layout.compatible_with(&layout));
+
+        let mut dropped = layout.clone();
+        let removed = dropped.entries.pop().expect("std layout has attributes");
+        assert!(!layout.compatible_with(&dropped));
+        assert!(layout.diff(&dropped).contains(&bento::VertexLayoutDifference::Removed {
+            location: removed.location,
+        }));
+    }
+
     #[test]
     fn gpudeferred_compiles_vertex_and_fragment_shaders() {
         let results = gpudeferred(&[]);
@@ -620,22 +576,67 @@ mod tests {
         let locations: Vec<_> = layout
             .entries
             .iter()
-            .map(|e| (e.location, &e.format))
+            .map(|e| (e.location, e.format))
             .collect();
         let offsets: Vec<_> = layout.entries.iter().map(|e| e.offset).collect();
 
         assert_eq!(layout.stride, 96);
         assert!(matches!(layout.rate, dashi::VertexRate::Vertex));
         assert_eq!(locations.len(), 7);
-        assert_eq!(locations[0], (0, &dashi::ShaderPrimitiveType::Vec3));
-        assert_eq!(locations[1], (1, &dashi::ShaderPrimitiveType::Vec3));
-        assert_eq!(locations[2], (2, &dashi::ShaderPrimitiveType::Vec4));
-        assert_eq!(locations[3], (3, &dashi::ShaderPrimitiveType::Vec2));
-        assert_eq!(locations[4], (4, &dashi::ShaderPrimitiveType::Vec4));
+        assert_eq!(locations[0], (0, Some(dashi::ShaderPrimitiveType::Vec3)));
+        assert_eq!(locations[1], (1, Some(dashi::ShaderPrimitiveType::Vec3)));
+        assert_eq!(locations[2], (2, Some(dashi::ShaderPrimitiveType::Vec4)));
+        assert_eq!(locations[3], (3, Some(dashi::ShaderPrimitiveType::Vec2)));
+        assert_eq!(locations[4], (4, Some(dashi::ShaderPrimitiveType::Vec4)));
 
         assert_eq!(offsets, vec![0, 12, 24, 40, 48, 64, 80]);
     }
 
+    #[test]
+    fn stdforward_bindings_match_stddeferred_layout() {
+        let forward = stdforward(&[]);
+        let deferred = stddeferred(&[]);
+
+        let bindings = |results: &[CompilationResult]| {
+            results
+                .iter()
+                .flat_map(|r| r.variables.iter())
+                .map(|v| (v.name.clone(), (v.set, v.kind.binding, v.kind.var_type)))
+                .collect::<HashMap<_, _>>()
+        };
+        let forward_bindings = bindings(&forward);
+        let deferred_bindings = bindings(&deferred);
+
+        assert!(!forward_bindings.is_empty());
+        let deferred_sets: HashSet<u32> = deferred_bindings.values().map(|b| b.0).collect();
+        for (name, binding) in &forward_bindings {
+            assert!(
+                deferred_sets.contains(&binding.0),
+                "{name} uses set {} outside the bindless layout",
+                binding.0
+            );
+            if let Some(expected) = deferred_bindings.get(name) {
+                assert_eq!(binding, expected, "{name} differs from the deferred layout");
+            }
+        }
+    }
+
+    #[test]
+    fn stdforward_vertex_layout_matches_stddeferred() {
+        let vertex_layout = |results: Vec<CompilationResult>| {
+            results
+                .into_iter()
+                .find(|r| r.stage == dashi::ShaderType::Vertex)
+                .and_then(|r| r.metadata.vertex)
+                .expect("vertex layout missing")
+        };
+
+        assert_eq!(
+            vertex_layout(stdforward(&[])),
+            vertex_layout(stddeferred(&[]))
+        );
+    }
+
     fn expected_binding_count(var: &dashi::BindTableVariable) -> u32 {
         if var.count == 0 { 256 } else { var.count }
     }
@@ -699,6 +700,7 @@ mod tests {
             }
         }
 
+        bento::builder::retire_context(&ctx);
         ctx.destroy();
     }
 }
@@ -86,6 +86,13 @@ pub struct ISlangBlob {
     _marker: PhantomData<()>,
 }
 
+/// Whether the linked Slang library answers API calls, i.e. it was found at build time and
+/// loaded at run time.
+pub fn is_available() -> bool {
+    // Safety: `spGetBuildTagString` takes no arguments and returns a static string or null.
+    unsafe { !spGetBuildTagString().is_null() }
+}
+
 #[link(name = "slang")]
 unsafe extern "C" {
     /// Returns the build tag string for the linked Slang library.
@@ -7,6 +7,7 @@ use winit::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEve
 use winit::event_loop::ControlFlow;
 use winit::platform::run_return::EventLoopExtRunReturn;
 #[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct Vertex {
     position: [f32; 2],
     color: [f32; 3],
@@ -83,27 +84,18 @@ fn main() {
 
     let indices: [u32; 3] = [0, 1, 2];
 
-    let vertex = context
-        .make_buffer(&BufferInfo {
-            debug_name: "render-graph-vertices",
-            byte_size: (std::mem::size_of::<Vertex>() * vertices.len()) as u32,
-            visibility: MemoryVisibility::Gpu,
-            usage: BufferUsage::VERTEX,
-            initial_data: unsafe { Some(vertices.align_to::<u8>().1) },
-            ..Default::default()
-        })
-        .unwrap();
-
-    let indices = context
-        .make_buffer(&BufferInfo {
-            debug_name: "render-graph-indices",
-            byte_size: (std::mem::size_of::<u32>() * indices.len()) as u32,
-            visibility: MemoryVisibility::Gpu,
-            usage: BufferUsage::INDEX,
-            initial_data: unsafe { Some(indices.align_to::<u8>().1) },
-            ..Default::default()
-        })
-        .unwrap();
+    let vertex = tare::make_typed_buffer(
+        &mut context,
+        "render-graph-vertices",
+        &vertices,
+        BufferUsage::VERTEX,
+    );
+    let indices = tare::make_typed_buffer(
+        &mut context,
+        "render-graph-indices",
+        &indices,
+        BufferUsage::INDEX,
+    );
 
     let pso = bento::builder::PSOBuilder::new()
         .vertex_compiled(Some(vert_shader))
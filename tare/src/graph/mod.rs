@@ -1,13 +1,18 @@
+use std::collections::HashMap;
 use std::mem;
 use std::panic;
 use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
 
 use crate::transient::{BindlessTextureRegistry, TransientAllocator, TransientImage};
 use crate::utils::ThreadPool;
 use cmd::{CommandStream, Executable, PendingGraphics, Recording};
 use dashi::gpu::cmd::{Scope, SyncPoint};
 use dashi::{execution::CommandRing, *};
-use driver::command::BeginRenderPass;
+use driver::command::{
+    BeginRenderPass, CopyBuffer, CopyBufferImage, CopyImageBuffer, DrawIndexed,
+    DrawIndexedIndirect, ResolveImage,
+};
 
 #[derive(Default, Debug, Clone)]
 pub struct SubpassInfo {
@@ -17,17 +22,259 @@ pub struct SubpassInfo {
     pub depth_attachment: Option<ImageView>,
     pub clear_values: [Option<ClearValue>; 8],
     pub depth_clear: Option<ClearValue>,
+    /// Stencil value to clear the depth attachment's stencil aspect to. Overrides the stencil in
+    /// `depth_clear`, and clears the stencil alone, keeping depth, when `depth_clear` is `None`.
+    pub stencil_clear: Option<u32>,
+    /// Sample count to render the color attachments at. Above `S1` the graph renders into
+    /// transient multisampled images and resolves them once the subpass ends; every color
+    /// attachment then needs a clear value, and any depth attachment must already use this
+    /// sample count.
+    pub samples: SampleCount,
+    /// Single-sample targets each color attachment resolves into after a multisampled subpass.
+    /// Empty slots resolve back into the original color attachment. Ignored at `S1`.
+    pub resolve_attachments: [Option<ImageView>; 8],
+}
+
+impl SubpassInfo {
+    /// The clear value the depth attachment begins with, combining `depth_clear` and
+    /// `stencil_clear`.
+    pub fn depth_stencil_clear(&self) -> Option<ClearValue> {
+        match (self.depth_clear, self.stencil_clear) {
+            (Some(ClearValue::DepthStencil { depth, .. }), Some(stencil)) => {
+                Some(ClearValue::depth_stencil(depth, stencil))
+            }
+            (None, Some(stencil)) => Some(ClearValue::depth_stencil(1.0, stencil)),
+            (depth, _) => depth,
+        }
+    }
+}
+
+/// Format-aware constructors for [`ClearValue`].
+///
+/// Bring this trait into scope to write `ClearValue::color_u32(..)` and friends. The graph checks
+/// every clear against its attachment format when solving and records a [`ClearValueWarning`]
+/// when, for example, a float color is used to clear an integer target.
+pub trait ClearValueExt: Sized {
+    fn color_f32(color: [f32; 4]) -> Self;
+    fn color_u32(color: [u32; 4]) -> Self;
+    fn depth_stencil(depth: f32, stencil: u32) -> Self;
+    fn matches_format(&self, format: Format) -> bool;
+}
+
+impl ClearValueExt for ClearValue {
+    fn color_f32(color: [f32; 4]) -> Self {
+        ClearValue::Color(color)
+    }
+
+    fn color_u32(color: [u32; 4]) -> Self {
+        ClearValue::UintColor(color)
+    }
+
+    fn depth_stencil(depth: f32, stencil: u32) -> Self {
+        ClearValue::DepthStencil { depth, stencil }
+    }
+
+    fn matches_format(&self, format: Format) -> bool {
+        match self {
+            ClearValue::Color(_) => {
+                !is_signed_format(format) && !is_unsigned_format(format) && !is_depth_format(format)
+            }
+            ClearValue::IntColor(_) => is_signed_format(format),
+            ClearValue::UintColor(_) => is_unsigned_format(format),
+            ClearValue::DepthStencil { .. } => is_depth_format(format),
+        }
+    }
+}
+
+// dashi has no format queries, so the integer and depth variants are listed here. Any other
+// format is treated as a float color target.
+fn is_signed_format(format: Format) -> bool {
+    matches!(format, Format::R32Sint | Format::RGBA32Sint)
+}
+
+fn is_unsigned_format(format: Format) -> bool {
+    matches!(format, Format::R32Uint | Format::RGBA32Uint)
+}
+
+fn is_depth_format(format: Format) -> bool {
+    matches!(format, Format::D24S8)
+}
+
+/// A clear value that does not match the format of the attachment it clears.
+#[derive(Debug, Clone)]
+pub struct ClearValueWarning {
+    pub pass: Option<String>,
+    /// Color attachment index, or `None` for the depth attachment.
+    pub attachment: Option<usize>,
+    pub format: Format,
+    pub value: ClearValue,
+}
+
+/// A transient image declared on the graph whose backing allocation is deferred until execution.
+///
+/// Scratch images whose pass usage does not overlap may be served from the same physical image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScratchImage(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ScratchImageDesc {
+    debug_name: String,
+    dim: [u32; 3],
+    layers: u32,
+    format: Format,
+    mip_levels: u32,
+    samples: SampleCount,
+}
+
+impl ScratchImageDesc {
+    fn compatible(&self, other: &Self) -> bool {
+        self.dim == other.dim
+            && self.layers == other.layers
+            && self.format == other.format
+            && self.mip_levels == other.mip_levels
+            && self.samples == other.samples
+    }
+}
+
+/// Outcome of the scratch image lifetime analysis from the last execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AliasingStats {
+    /// Scratch images declared on the graph.
+    pub scratch_images: usize,
+    /// Physical images allocated to back them.
+    pub physical_images: usize,
+    /// Passes that waited for the previous user of an aliased image before touching it.
+    pub alias_barriers: usize,
+}
+
+impl AliasingStats {
+    /// Whether any two scratch images shared a physical image.
+    pub fn aliased(&self) -> bool {
+        self.physical_images < self.scratch_images
+    }
+}
+
+/// Byte stride of one `VkDrawIndexedIndirectCommand` in an indirect argument buffer.
+pub const INDEXED_INDIRECT_STRIDE: u32 = 20;
+
+/// An indexed draw whose parameters live in a GPU buffer, typically written by a culling
+/// compute pass earlier in the graph.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IndirectDraw {
+    pub vertices: Handle<Buffer>,
+    pub indices: Handle<Buffer>,
+    /// Tightly packed indexed-indirect commands, `INDEXED_INDIRECT_STRIDE` bytes apart.
+    pub args: BufferView,
+    pub draw_count: u32,
+    pub bind_tables: [Option<Handle<BindTable>>; 4],
+}
+
+pub trait IndirectDrawExt {
+    /// Records `draw.draw_count` indexed draws sourced from `draw.args`. A zero count records
+    /// nothing, so culling passes that reject everything need no special casing.
+    fn draw_indirect(self, draw: &IndirectDraw) -> Self;
+}
+
+impl IndirectDrawExt for CommandStream<PendingGraphics> {
+    fn draw_indirect(self, draw: &IndirectDraw) -> Self {
+        if draw.draw_count == 0 {
+            return self;
+        }
+
+        self.draw_indexed_indirect(&DrawIndexedIndirect {
+            vertices: draw.vertices,
+            indices: draw.indices,
+            indirect: draw.args.handle,
+            offset: draw.args.offset as u32,
+            draw_count: draw.draw_count,
+            stride: INDEXED_INDIRECT_STRIDE,
+            bind_tables: draw.bind_tables,
+            ..Default::default()
+        })
+    }
+}
+
+/// What a render pass callback gets alongside its command stream.
+///
+/// dashi binds tables per draw, so the global tables only reach draws recorded through
+/// `draw_indexed` and `draw_indirect` here, which fill every set the draw leaves empty.
+#[derive(Clone, Copy)]
+pub struct PassContext<'a> {
+    /// Tables set with `RenderGraph::set_global_bind_tables`, indexed by descriptor set.
+    pub bind_tables: [Option<Handle<BindTable>>; 4],
+    /// Resolved views of the pass's scratch images, in the order they were requested.
+    pub scratch: &'a [ImageView],
+}
+
+impl PassContext<'_> {
+    /// The global tables with every `Some` entry of `tables` taking its set's slot, for draws
+    /// that add per-draw tables on top of the frame's reserved ones.
+    pub fn bind_tables_with(
+        &self,
+        tables: [Option<Handle<BindTable>>; 4],
+    ) -> [Option<Handle<BindTable>>; 4] {
+        let mut merged = self.bind_tables;
+        for (slot, table) in merged.iter_mut().zip(tables) {
+            if table.is_some() {
+                *slot = table;
+            }
+        }
+        merged
+    }
+
+    /// Records `draw` with the global tables bound under its own.
+    pub fn draw_indexed(
+        &self,
+        stream: CommandStream<PendingGraphics>,
+        draw: &DrawIndexed,
+    ) -> CommandStream<PendingGraphics> {
+        let mut draw = draw.clone();
+        draw.bind_tables = self.bind_tables_with(draw.bind_tables);
+        stream.draw_indexed(&draw)
+    }
+
+    /// Records `draw` with the global tables bound under its own.
+    pub fn draw_indirect(
+        &self,
+        stream: CommandStream<PendingGraphics>,
+        draw: &IndirectDraw,
+    ) -> CommandStream<PendingGraphics> {
+        stream.draw_indirect(&IndirectDraw {
+            bind_tables: self.bind_tables_with(draw.bind_tables),
+            ..*draw
+        })
+    }
 }
 
 pub struct RenderGraph {
     alloc: TransientAllocatorOwner,
     ring: CommandRing,
+    // Transfer ring for `read_image`/`write_image`, once `use_transfer_queue` created one.
+    transfer_ring: Option<CommandRing>,
     passes: Vec<GraphPass>,
+    scratch_images: Vec<ScratchImageDesc>,
+    aliasing_stats: AliasingStats,
     cached_render_passes: Vec<Handle<RenderPass>>,
     cached_begins: Vec<BeginRenderPass>,
+    clear_warnings: Vec<ClearValueWarning>,
+    history: HashMap<String, HistoryImages>,
+    global_bind_tables: [Option<Handle<BindTable>>; 4],
     thread_pool: ThreadPool,
 }
 
+// Two global images that trade the current/previous roles every `execute`.
+struct HistoryImages {
+    images: [TransientImage; 2],
+    current: usize,
+    key: ([u32; 3], u32, Format, u32, SampleCount),
+}
+
+impl HistoryImages {
+    fn key(info: &ImageInfo) -> ([u32; 3], u32, Format, u32, SampleCount) {
+        (info.dim, info.layers, info.format, info.mip_levels, info.samples)
+    }
+}
+
 enum TransientAllocatorOwner {
     Owned(TransientAllocator),
     Borrowed(NonNull<TransientAllocator>),
@@ -50,18 +297,41 @@ impl TransientAllocatorOwner {
     }
 }
 
+type SubpassCallback = Box<
+    dyn FnMut(CommandStream<PendingGraphics>, &PassContext) -> CommandStream<PendingGraphics>
+        + Send,
+>;
+type ComputeCallback =
+    Box<dyn FnMut(CommandStream<Recording>, &[ImageView]) -> CommandStream<Executable> + Send>;
+
 struct StoredSubpass {
     info: SubpassInfo,
-    cb: Box<dyn FnMut(CommandStream<PendingGraphics>) -> CommandStream<PendingGraphics> + Send>,
+    scratch: Vec<ScratchImage>,
+    // Views recorded by one `add_multiview_subpass` call share a group and may share a render
+    // pass.
+    multiview_group: Option<usize>,
+    cb: SubpassCallback,
 }
 
 struct StoredComputePass {
-    cb: Box<dyn FnMut(CommandStream<Recording>) -> CommandStream<Executable> + Send>,
+    scratch: Vec<ScratchImage>,
+    cb: ComputeCallback,
 }
 
 enum GraphPass {
     Render(StoredSubpass),
     Compute(StoredComputePass),
+    Transfer(CopyBuffer),
+}
+
+impl GraphPass {
+    fn scratch(&self) -> &[ScratchImage] {
+        match self {
+            GraphPass::Render(subpass) => &subpass.scratch,
+            GraphPass::Compute(compute) => &compute.scratch,
+            GraphPass::Transfer(_) => &[],
+        }
+    }
 }
 
 impl RenderGraph {
@@ -101,13 +371,152 @@ impl RenderGraph {
                 .map(TransientAllocatorOwner::borrowed)
                 .unwrap_or_else(|| TransientAllocatorOwner::owned(ctx)),
             ring,
+            transfer_ring: None,
             passes: Vec::new(),
+            scratch_images: Vec::new(),
+            aliasing_stats: AliasingStats::default(),
             cached_render_passes: Vec::new(),
             cached_begins: Vec::new(),
+            clear_warnings: Vec::new(),
+            history: HashMap::new(),
+            global_bind_tables: [None; 4],
             thread_pool: ThreadPool::new(),
         }
     }
 
+    /// Routes `read_image` and `write_image` copies through a transfer queue so they don't
+    /// serialize behind rendering. Returns `false`, keeping copies on the graphics queue, when
+    /// the context can't create a transfer ring. dashi picks the queue, so on devices without a
+    /// separate transfer family it may share hardware with graphics.
+    pub fn use_transfer_queue(&mut self, ctx: &mut Context) -> bool {
+        if self.transfer_ring.is_none() {
+            self.transfer_ring = ctx
+                .make_command_ring(&CommandQueueInfo2 {
+                    debug_name: "tare-render-graph-transfer",
+                    parent: None,
+                    queue_type: QueueType::Transfer,
+                })
+                .ok();
+        }
+        self.transfer_ring.is_some()
+    }
+
+    /// Whether `read_image` and `write_image` copies run on a transfer queue.
+    pub fn uses_transfer_queue(&self) -> bool {
+        self.transfer_ring.is_some()
+    }
+
+    /// Copies `src` into `dst` and blocks until the bytes are available on the host. The copy
+    /// waits on the GPU for rendering already submitted by `execute`.
+    pub fn read_image(&mut self, src: ImageView, dst: Handle<Buffer>) {
+        let copy = CopyImageBuffer {
+            src: src.img,
+            dst,
+            range: SubresourceRange::default(),
+            dst_offset: 0,
+        };
+        self.submit_copy(
+            CommandStream::new()
+                .begin()
+                .copy_image_to_buffer(&copy)
+                .end(),
+            true,
+        );
+    }
+
+    /// Uploads `src` into `dst` without blocking the host. The copy waits on the GPU for
+    /// rendering already submitted by `execute`, and the next `execute` waits for the copy.
+    /// `src` must stay alive until the graph's frames in flight finish.
+    pub fn write_image(&mut self, src: Handle<Buffer>, dst: ImageView) {
+        let copy = CopyBufferImage {
+            src,
+            dst: dst.img,
+            range: SubresourceRange::default(),
+            src_offset: 0,
+        };
+        self.submit_copy(
+            CommandStream::new()
+                .begin()
+                .copy_buffer_to_image(&copy)
+                .end(),
+            false,
+        );
+    }
+
+    // Runs a copy after the rendering submitted so far, on the transfer ring when there is one.
+    // The queues hand the image over on the GPU: a release batch on the graphics ring signals a
+    // semaphore the transfer copy waits on, and the copy signals one an acquire batch on the
+    // graphics ring waits on before later rendering. Only `wait_on_host` blocks the CPU.
+    fn submit_copy(&mut self, stream: CommandStream<Executable>, wait_on_host: bool) {
+        let release = CommandStream::new()
+            .begin()
+            .sync(SyncPoint::GraphicsToTransfer, Scope::All)
+            .end();
+        // Transfer writes chain through the compute stage into graphics, as after transfer passes.
+        let acquire = CommandStream::new()
+            .begin()
+            .sync(SyncPoint::TransferToCompute, Scope::All)
+            .sync(SyncPoint::ComputeToGraphics, Scope::All)
+            .end();
+
+        let Some(transfer) = self.transfer_ring.as_mut() else {
+            self.ring
+                .record(|cmd| {
+                    release.append(cmd).unwrap();
+                    stream.append(cmd).unwrap();
+                    acquire.append(cmd).unwrap();
+                })
+                .expect("Failed to record render graph copy");
+            self.ring
+                .submit(&Default::default())
+                .expect("Failed to submit render graph copy");
+            if wait_on_host {
+                self.ring
+                    .wait_all()
+                    .expect("Failed to wait for render graph copy");
+            }
+            return;
+        };
+
+        let rendered = self.alloc.as_mut().make_semaphore();
+        let copied = self.alloc.as_mut().make_semaphore();
+        self.ring
+            .record(|cmd| release.append(cmd).unwrap())
+            .expect("Failed to record render graph copy release");
+        self.ring
+            .submit(&SubmitInfo {
+                wait_sems: &[],
+                signal_sems: &[rendered],
+            })
+            .expect("Failed to submit render graph copy release");
+
+        transfer
+            .record(|cmd| stream.append(cmd).unwrap())
+            .expect("Failed to record render graph copy");
+        transfer
+            .submit(&SubmitInfo {
+                wait_sems: &[rendered],
+                signal_sems: &[copied],
+            })
+            .expect("Failed to submit render graph copy");
+
+        self.ring
+            .record(|cmd| acquire.append(cmd).unwrap())
+            .expect("Failed to record render graph copy acquire");
+        self.ring
+            .submit(&SubmitInfo {
+                wait_sems: &[copied],
+                signal_sems: &[],
+            })
+            .expect("Failed to submit render graph copy acquire");
+
+        if wait_on_host {
+            transfer
+                .wait_all()
+                .expect("Failed to wait for render graph copy");
+        }
+    }
+
     pub fn make_semaphore(&mut self) -> Handle<Semaphore> {
         self.alloc.as_mut().make_semaphore()
     }
@@ -125,6 +534,25 @@ impl RenderGraph {
         self.alloc.as_mut().make_cubemap(info)
     }
 
+    // Declare a transient image for this frame. The backing image is chosen when the graph
+    // executes, and may be shared with other scratch images whose passes do not overlap.
+    pub fn make_scratch_image(&mut self, info: &ImageInfo) -> ScratchImage {
+        self.scratch_images.push(ScratchImageDesc {
+            debug_name: info.debug_name.to_string(),
+            dim: info.dim,
+            layers: info.layers,
+            format: info.format,
+            mip_levels: info.mip_levels,
+            samples: info.samples,
+        });
+        ScratchImage(self.scratch_images.len() - 1)
+    }
+
+    /// Scratch image aliasing results from the last call to `execute`.
+    pub fn aliasing_stats(&self) -> AliasingStats {
+        self.aliasing_stats
+    }
+
     // Make a non-transient image that stays valid until explicitly destroyed.
     pub fn make_global_image(&mut self, info: &ImageInfo) -> TransientImage {
         self.alloc.as_mut().make_global_image(info)
@@ -134,6 +562,49 @@ impl RenderGraph {
         self.alloc.as_mut().make_global_cubemap(info)
     }
 
+    /// Returns `(current, previous)` images of a two-slot history named `name`, for passes that
+    /// read last frame's output while writing this frame's.
+    ///
+    /// The images are global, so they survive `advance`, and swap roles after every `execute`:
+    /// what was written to `current` is `previous` on the next frame. Asking for a name with a
+    /// different size or format recreates both images; the old ones are destroyed once frames in
+    /// flight are done with them.
+    pub fn persistent_history(
+        &mut self,
+        name: &str,
+        info: &ImageInfo,
+    ) -> (TransientImage, TransientImage) {
+        let key = HistoryImages::key(info);
+        let stale = self.history.get(name).filter(|history| history.key != key);
+        if let Some(images) = stale.map(|history| history.images) {
+            for image in images {
+                self.alloc.as_mut().retire_global_image(image.view.img);
+            }
+            self.history.remove(name);
+        }
+
+        if !self.history.contains_key(name) {
+            let images = [
+                self.alloc.as_mut().make_global_image(info),
+                self.alloc.as_mut().make_global_image(info),
+            ];
+            self.history.insert(
+                name.to_string(),
+                HistoryImages {
+                    images,
+                    current: 0,
+                    key,
+                },
+            );
+        }
+
+        let history = &self.history[name];
+        (
+            history.images[history.current],
+            history.images[history.current ^ 1],
+        )
+    }
+
     pub fn destroy_global_image(&mut self, image: Handle<Image>) {
         self.alloc.as_mut().destroy_global_image(image);
     }
@@ -147,48 +618,343 @@ impl RenderGraph {
         self.alloc.as_mut().make_buffer(info)
     }
 
+    /// Tables handed to every render pass through its `PassContext`, e.g. the frame's reserved
+    /// timing and bindless tables. Passes recorded before the call see the new tables too.
+    pub fn set_global_bind_tables(&mut self, tables: [Option<Handle<BindTable>>; 4]) {
+        self.global_bind_tables = tables;
+    }
+
+    pub fn global_bind_tables(&self) -> [Option<Handle<BindTable>>; 4] {
+        self.global_bind_tables
+    }
+
     pub fn set_bindless_registry(&mut self, registry: &mut impl BindlessTextureRegistry) {
         self.alloc.as_mut().set_bindless_registry(registry);
     }
 
     // Append a potential subpass
-    pub fn add_subpass<F>(&mut self, info: &SubpassInfo, cb: F)
+    pub fn add_subpass<F>(&mut self, info: &SubpassInfo, mut cb: F)
     where
         F: FnMut(CommandStream<PendingGraphics>) -> CommandStream<PendingGraphics>,
     {
-        let cb: Box<dyn FnMut(CommandStream<PendingGraphics>) -> CommandStream<PendingGraphics>> =
-            Box::new(cb);
+        self.add_subpass_with_scratch(info, &[], move |stream, _| cb(stream));
+    }
+
+    // Append a subpass that uses the given scratch images. The callback receives their resolved
+    // views in the same order.
+    pub fn add_subpass_with_scratch<F>(
+        &mut self,
+        info: &SubpassInfo,
+        scratch: &[ScratchImage],
+        mut cb: F,
+    ) where
+        F: FnMut(CommandStream<PendingGraphics>, &[ImageView]) -> CommandStream<PendingGraphics>,
+    {
+        self.add_subpass_with_context(info, scratch, move |stream, pass| {
+            cb(stream, pass.scratch)
+        });
+    }
+
+    // Append a subpass whose callback receives a `PassContext` with the graph's global bind
+    // tables and the resolved views of the given scratch images.
+    pub fn add_subpass_with_context<F>(
+        &mut self,
+        info: &SubpassInfo,
+        scratch: &[ScratchImage],
+        cb: F,
+    ) where
+        F: FnMut(CommandStream<PendingGraphics>, &PassContext) -> CommandStream<PendingGraphics>,
+    {
+        let cb: Box<
+            dyn FnMut(
+                CommandStream<PendingGraphics>,
+                &PassContext,
+            ) -> CommandStream<PendingGraphics>,
+        > = Box::new(cb);
         let cb = unsafe {
             mem::transmute::<
-                Box<dyn FnMut(CommandStream<PendingGraphics>) -> CommandStream<PendingGraphics>>,
                 Box<
-                    dyn FnMut(CommandStream<PendingGraphics>) -> CommandStream<PendingGraphics>
-                        + Send,
+                    dyn FnMut(
+                        CommandStream<PendingGraphics>,
+                        &PassContext,
+                    ) -> CommandStream<PendingGraphics>,
                 >,
+                SubpassCallback,
             >(cb)
         };
+        let info = self.multisampled(info);
         self.passes.push(GraphPass::Render(StoredSubpass {
-            info: info.clone(),
+            info,
+            scratch: scratch.to_vec(),
+            multiview_group: None,
             cb,
         }));
         self.cached_render_passes.clear();
         self.cached_begins.clear();
     }
 
-    pub fn add_compute_pass<F>(&mut self, cb: F)
+    // Swap the color attachments of a multisampled subpass for transient images at its sample
+    // count, resolving each into its original attachment unless a resolve target was given.
+    // The transient images start undefined, so each one needs a clear value.
+    fn multisampled(&mut self, info: &SubpassInfo) -> SubpassInfo {
+        let mut info = info.clone();
+        if info.samples == SampleCount::S1 {
+            return info;
+        }
+
+        for (slot, (color, resolve)) in info
+            .color_attachments
+            .iter_mut()
+            .zip(info.resolve_attachments.iter_mut())
+            .enumerate()
+        {
+            let Some(target) = *color else {
+                continue;
+            };
+            assert!(
+                info.clear_values[slot].is_some(),
+                "multisampled color attachment {slot} needs a clear value"
+            );
+            let target_info = self.alloc.as_mut().context().image_info(target.img);
+            let msaa = self.alloc.as_mut().make_image(&ImageInfo {
+                debug_name: "tare-msaa-color",
+                dim: target_info.dim,
+                format: target_info.format,
+                samples: info.samples,
+                ..Default::default()
+            });
+            *color = Some(msaa.view);
+            resolve.get_or_insert(target);
+        }
+        info
+    }
+
+    // Append a pass that only clears `target` to `color`, with no draws. Later passes that leave
+    // the attachment's clear value unset load the cleared contents.
+    pub fn add_clear(&mut self, target: ImageView, color: ClearValue) {
+        let mut info = SubpassInfo {
+            name: Some("clear".to_string()),
+            ..Default::default()
+        };
+        info.color_attachments[0] = Some(target);
+        info.clear_values[0] = Some(color);
+
+        self.add_subpass(&info, |stream| stream);
+    }
+
+    // Append one subpass per view, recording the callback once for each with the view's index.
+    // Views whose attachment formats match share a single render pass.
+    pub fn add_multiview_subpass<F>(&mut self, views: &[SubpassInfo], cb: F)
+    where
+        F: FnMut(CommandStream<PendingGraphics>, usize) -> CommandStream<PendingGraphics>,
+    {
+        let cb: Box<
+            dyn FnMut(CommandStream<PendingGraphics>, usize) -> CommandStream<PendingGraphics>,
+        > = Box::new(cb);
+        let cb = unsafe {
+            mem::transmute::<
+                Box<
+                    dyn FnMut(
+                        CommandStream<PendingGraphics>,
+                        usize,
+                    ) -> CommandStream<PendingGraphics>,
+                >,
+                Box<
+                    dyn FnMut(
+                            CommandStream<PendingGraphics>,
+                            usize,
+                        ) -> CommandStream<PendingGraphics>
+                        + Send,
+                >,
+            >(cb)
+        };
+        // Views may record on different threads, so they take turns with the callback.
+        let cb = Arc::new(Mutex::new(cb));
+        let group = self.passes.len();
+
+        for (index, info) in views.iter().enumerate() {
+            let cb = Arc::clone(&cb);
+            let info = self.multisampled(info);
+            self.passes.push(GraphPass::Render(StoredSubpass {
+                info,
+                scratch: Vec::new(),
+                multiview_group: Some(group),
+                cb: Box::new(move |stream, _| {
+                    let mut cb = cb.lock().expect("multiview callback poisoned");
+                    (*cb)(stream, index)
+                }),
+            }));
+        }
+        self.cached_render_passes.clear();
+        self.cached_begins.clear();
+    }
+
+    pub fn add_compute_pass<F>(&mut self, mut cb: F)
     where
         F: FnMut(CommandStream<Recording>) -> CommandStream<Executable>,
     {
-        let cb: Box<dyn FnMut(CommandStream<Recording>) -> CommandStream<Executable>> =
-            Box::new(cb);
+        self.add_compute_pass_with_scratch(&[], move |stream, _| cb(stream));
+    }
+
+    // Append a compute pass that uses the given scratch images. The callback receives their
+    // resolved views in the same order.
+    pub fn add_compute_pass_with_scratch<F>(&mut self, scratch: &[ScratchImage], cb: F)
+    where
+        F: FnMut(CommandStream<Recording>, &[ImageView]) -> CommandStream<Executable>,
+    {
+        let cb: Box<
+            dyn FnMut(CommandStream<Recording>, &[ImageView]) -> CommandStream<Executable>,
+        > = Box::new(cb);
         let cb = unsafe {
             mem::transmute::<
-                Box<dyn FnMut(CommandStream<Recording>) -> CommandStream<Executable>>,
-                Box<dyn FnMut(CommandStream<Recording>) -> CommandStream<Executable> + Send>,
+                Box<dyn FnMut(CommandStream<Recording>, &[ImageView]) -> CommandStream<Executable>>,
+                ComputeCallback,
             >(cb)
         };
-        self.passes
-            .push(GraphPass::Compute(StoredComputePass { cb }));
+        self.passes.push(GraphPass::Compute(StoredComputePass {
+            scratch: scratch.to_vec(),
+            cb,
+        }));
+    }
+
+    /// Copies `size` bytes from `src` into `dst` between the surrounding passes. The copy waits
+    /// for writes from the pass before it, and compute or render passes after it see the copied
+    /// bytes. Offsets and `size` must fit in a `u32`.
+    pub fn add_copy_pass(&mut self, src: BufferView, dst: BufferView, size: u64) {
+        assert!(
+            (src.size == 0 || size <= src.size) && (dst.size == 0 || size <= dst.size),
+            "copy of {size} bytes exceeds its source or destination view"
+        );
+        let narrow = |value: u64, what: &str| {
+            u32::try_from(value).unwrap_or_else(|_| panic!("copy {what} {value} exceeds u32::MAX"))
+        };
+
+        self.passes.push(GraphPass::Transfer(CopyBuffer {
+            src: src.handle,
+            dst: dst.handle,
+            src_offset: narrow(src.offset, "source offset"),
+            dst_offset: narrow(dst.offset, "destination offset"),
+            amount: narrow(size, "size"),
+        }));
+    }
+
+    /// Fills `buf` with repeated copies of `value`, e.g. to reset a counter between dispatches.
+    /// A zero `buf.size` fills from `buf.offset` to the end of the buffer, which must then be one
+    /// this graph made. Ordered like `add_copy_pass`.
+    pub fn add_fill_pass(&mut self, buf: BufferView, value: u32) {
+        let size = if buf.size == 0 {
+            let whole = self.alloc.as_mut().buffer_size(buf.handle).unwrap_or_else(|| {
+                panic!("whole-buffer fill needs a buffer made by this graph, or a sized view")
+            });
+            whole.saturating_sub(buf.offset)
+        } else {
+            buf.size
+        };
+        assert!(size > 0, "fill pass needs a non-zero size");
+        let byte_size = u32::try_from(size)
+            .unwrap_or_else(|_| panic!("fill of {size} bytes exceeds u32::MAX"));
+
+        let (staging, ptr, _) = self.alloc.as_mut().make_buffer_mapped(&BufferInfo {
+            debug_name: "tare-fill-pass",
+            byte_size,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            initial_data: None,
+        });
+        let bytes = unsafe { std::slice::from_raw_parts_mut(ptr, byte_size as usize) };
+        for (byte, fill) in bytes.iter_mut().zip(value.to_le_bytes().into_iter().cycle()) {
+            *byte = fill;
+        }
+
+        self.add_copy_pass(staging, buf, size);
+    }
+
+    // Assign physical images to the declared scratch images. Each scratch image is live from the
+    // first to the last pass that uses it; compatible images whose live ranges are disjoint share
+    // one backing image. Also returns, per pass, the sync it needs before touching an image that
+    // an earlier pass used under another scratch image.
+    fn resolve_scratch_images(&mut self) -> (Vec<ImageView>, Vec<Option<SyncPoint>>) {
+        let mut ranges: Vec<Option<(usize, usize)>> = vec![None; self.scratch_images.len()];
+        for (pass_index, pass) in self.passes.iter().enumerate() {
+            for scratch in pass.scratch() {
+                let range = &mut ranges[scratch.0];
+                *range = match *range {
+                    Some((first, _)) => Some((first, pass_index)),
+                    None => Some((pass_index, pass_index)),
+                };
+            }
+        }
+
+        let mut order: Vec<usize> = (0..self.scratch_images.len()).collect();
+        order.sort_by_key(|index| ranges[*index].map(|(first, _)| first));
+
+        // (descriptor index, view, last pass using it)
+        let mut physical: Vec<(usize, ImageView, Option<usize>)> = Vec::new();
+        let mut views = vec![ImageView::default(); self.scratch_images.len()];
+        let mut handoffs: Vec<Option<usize>> = vec![None; self.passes.len()];
+
+        for index in order {
+            let desc = &self.scratch_images[index];
+            let range = ranges[index];
+            let reusable = range.and_then(|(first, _)| {
+                physical.iter().position(|(owner, _, last)| {
+                    self.scratch_images[*owner].compatible(desc)
+                        && last.is_some_and(|last| last < first)
+                })
+            });
+
+            match reusable {
+                Some(slot) => {
+                    if let (Some((first, _)), Some(previous)) = (range, physical[slot].2) {
+                        handoffs[first] = handoffs[first].max(Some(previous));
+                    }
+                    physical[slot].2 = range.map(|(_, last)| last);
+                    views[index] = physical[slot].1;
+                }
+                None => {
+                    let image = self.alloc.as_mut().make_image(&ImageInfo {
+                        debug_name: desc.debug_name.as_str(),
+                        dim: desc.dim,
+                        layers: desc.layers,
+                        format: desc.format,
+                        mip_levels: desc.mip_levels,
+                        samples: desc.samples,
+                        ..Default::default()
+                    });
+                    physical.push((index, image.view, range.map(|(_, last)| last)));
+                    views[index] = image.view;
+                }
+            }
+        }
+
+        let waits: Vec<Option<SyncPoint>> = handoffs
+            .iter()
+            .enumerate()
+            .map(|(index, previous)| {
+                let from_compute = matches!(self.passes[(*previous)?], GraphPass::Compute(_));
+                Some(match (&self.passes[index], from_compute) {
+                    (GraphPass::Render(_), true) => SyncPoint::ComputeToGraphics,
+                    (GraphPass::Render(_), false) => SyncPoint::GraphicsToGraphics,
+                    (_, true) => SyncPoint::ComputeToCompute,
+                    (_, false) => SyncPoint::GraphicsToCompute,
+                })
+            })
+            .collect();
+
+        self.aliasing_stats = AliasingStats {
+            scratch_images: self.scratch_images.len(),
+            physical_images: physical.len(),
+            alias_barriers: waits.iter().flatten().count(),
+        };
+        self.scratch_images.clear();
+
+        (views, waits)
+    }
+
+    /// Clear values that did not match their attachment format the last time the graph was
+    /// solved.
+    pub fn clear_warnings(&self) -> &[ClearValueWarning] {
+        &self.clear_warnings
     }
 
     pub fn render_pass_handle(&mut self) -> Option<Handle<RenderPass>> {
@@ -196,6 +962,13 @@ impl RenderGraph {
             .and_then(|(rps, _)| rps.into_iter().next())
     }
 
+    // Render pass used by each recorded subpass, in submission order.
+    pub fn render_pass_handles(&mut self) -> Vec<Handle<RenderPass>> {
+        self.solve_and_cache()
+            .map(|(rps, _)| rps)
+            .unwrap_or_default()
+    }
+
     fn solve_and_cache(&mut self) -> Option<(Vec<Handle<RenderPass>>, Vec<BeginRenderPass>)> {
         if !(self.cached_render_passes.is_empty() || self.cached_begins.is_empty()) {
             return Some((
@@ -210,6 +983,14 @@ impl RenderGraph {
 
         self.cached_render_passes.clear();
         self.cached_begins.clear();
+        self.clear_warnings.clear();
+
+        // Last multiview render pass, keyed by group and attachment layout.
+        let mut previous_view: Option<(
+            usize,
+            Vec<(Format, SampleCount, bool)>,
+            Handle<RenderPass>,
+        )> = None;
 
         for pass in &self.passes {
             let GraphPass::Render(subpass) = pass else {
@@ -220,8 +1001,19 @@ impl RenderGraph {
             for attachment in subpass.info.color_attachments.iter().flatten().take(4) {
                 let mut desc = AttachmentDescription::default();
                 // Keep load_op aligned with whether we intend to clear the attachment.
-                let clear = subpass.info.clear_values[colors.len()].is_some();
+                let clear_value = subpass.info.clear_values[colors.len()];
+                let clear = clear_value.is_some();
                 let info = self.alloc.as_mut().context().image_info(attachment.img);
+                if let Some(value) = clear_value {
+                    if !value.matches_format(info.format) {
+                        self.clear_warnings.push(ClearValueWarning {
+                            pass: subpass.info.name.clone(),
+                            attachment: Some(colors.len()),
+                            format: info.format,
+                            value,
+                        });
+                    }
+                }
                 desc.samples = info.samples;
                 desc.format = info.format;
                 desc.load_op = if clear { LoadOp::Clear } else { LoadOp::Load };
@@ -231,6 +1023,16 @@ impl RenderGraph {
             let depth_desc = subpass.info.depth_attachment.map(|attach| {
                 let mut desc = AttachmentDescription::default();
                 let info = self.alloc.as_mut().context().image_info(attach.img);
+                if let Some(value) = subpass.info.depth_clear {
+                    if !value.matches_format(info.format) {
+                        self.clear_warnings.push(ClearValueWarning {
+                            pass: subpass.info.name.clone(),
+                            attachment: None,
+                            format: info.format,
+                            value,
+                        });
+                    }
+                }
                 desc.samples = info.samples;
                 desc.format = info.format;
                 desc.load_op = if subpass.info.depth_clear.is_some() {
@@ -238,6 +1040,11 @@ impl RenderGraph {
                 } else {
                     LoadOp::Load
                 };
+                desc.stencil_load_op = if subpass.info.depth_stencil_clear().is_some() {
+                    LoadOp::Clear
+                } else {
+                    LoadOp::Load
+                };
                 desc
             });
 
@@ -253,7 +1060,30 @@ impl RenderGraph {
                 subpasses: std::slice::from_ref(&subpass_description),
             };
 
-            let render_pass = self.alloc.as_mut().make_render_pass(&rp_info);
+            let attachment_key: Vec<(Format, SampleCount, bool)> = colors
+                .iter()
+                .chain(depth_desc.iter())
+                .map(|desc| {
+                    (
+                        desc.format,
+                        desc.samples,
+                        matches!(desc.load_op, LoadOp::Clear),
+                    )
+                })
+                .collect();
+            let reused = match (&previous_view, subpass.multiview_group) {
+                (Some((group, key, handle)), Some(current))
+                    if *group == current && *key == attachment_key =>
+                {
+                    Some(*handle)
+                }
+                _ => None,
+            };
+            let render_pass =
+                reused.unwrap_or_else(|| self.alloc.as_mut().make_render_pass(&rp_info));
+            previous_view = subpass
+                .multiview_group
+                .map(|group| (group, attachment_key, render_pass));
 
             let mut begin = BeginRenderPass {
                 viewport: subpass.info.viewport,
@@ -261,7 +1091,7 @@ impl RenderGraph {
                 color_attachments: [None; 4],
                 depth_attachment: subpass.info.depth_attachment,
                 clear_values: [None; 4],
-                depth_clear: subpass.info.depth_clear,
+                depth_clear: subpass.info.depth_stencil_clear(),
             };
 
             for i in 0..4 {
@@ -279,6 +1109,27 @@ impl RenderGraph {
         ))
     }
 
+    /// Indices of passes that wait on compute or transfer work recorded before them. Each one is
+    /// preceded by a compute-to-graphics barrier so indirect arguments and storage writes are
+    /// visible.
+    pub fn compute_barriers(&self) -> Vec<usize> {
+        let mut pending_compute = false;
+        let mut barriers = Vec::new();
+        for (index, pass) in self.passes.iter().enumerate() {
+            match pass {
+                // Transfer passes end with a transfer-to-compute barrier, which the
+                // compute-to-graphics barrier chains onto.
+                GraphPass::Compute(_) | GraphPass::Transfer(_) => pending_compute = true,
+                GraphPass::Render(_) if pending_compute => {
+                    barriers.push(index);
+                    pending_compute = false;
+                }
+                GraphPass::Render(_) => {}
+            }
+        }
+        barriers
+    }
+
     pub fn execute(&mut self) {
         self.execute_with(&Default::default());
     }
@@ -288,12 +1139,24 @@ impl RenderGraph {
             return;
         };
 
+        let (scratch_views, alias_waits) = self.resolve_scratch_images();
+        let barriers = self.compute_barriers();
+        let bind_tables = self.global_bind_tables;
         let mut render_index = 0;
+        let mut after_render = false;
         let passes = std::mem::take(&mut self.passes);
         let mut handles = Vec::with_capacity(passes.len());
         self.thread_pool.ensure_workers(passes.len());
 
-        for pass in passes {
+        for ((pass_index, pass), alias_wait) in passes.into_iter().enumerate().zip(alias_waits) {
+            let wait_for_compute = barriers.contains(&pass_index);
+            let views: Vec<ImageView> = pass
+                .scratch()
+                .iter()
+                .map(|scratch| scratch_views[scratch.0])
+                .collect();
+            let follows_render =
+                mem::replace(&mut after_render, matches!(pass, GraphPass::Render(_)));
             match pass {
                 GraphPass::Render(mut subpass) => {
                     let subpass_index = render_index;
@@ -309,13 +1172,43 @@ impl RenderGraph {
                         .unwrap_or_else(|| format!("subpass-{subpass_index}"));
                     let start_label = format!("subpass start: {label}");
                     let end_label = format!("subpass end: {label}");
+                    let resolves: Vec<ResolveImage> = if subpass.info.samples == SampleCount::S1 {
+                        Vec::new()
+                    } else {
+                        subpass
+                            .info
+                            .color_attachments
+                            .iter()
+                            .zip(subpass.info.resolve_attachments.iter())
+                            .filter_map(|(color, resolve)| {
+                                Some(ResolveImage {
+                                    src: (*color)?.img,
+                                    dst: (*resolve)?.img,
+                                    range: SubresourceRange::default(),
+                                })
+                            })
+                            .collect()
+                    };
                     handles.push(self.thread_pool.execute(move || {
                         let mut stream = CommandStream::new().begin();
+                        if let Some(wait) = alias_wait {
+                            stream = stream.sync(wait, Scope::All);
+                        }
+                        if wait_for_compute {
+                            stream = stream.sync(SyncPoint::ComputeToGraphics, Scope::All);
+                        }
                         stream = stream.debug_label(&start_label);
                         let mut subpass_stream = stream.begin_render_pass(&begin);
-                        subpass_stream = (subpass.cb)(subpass_stream);
-                        stream = subpass_stream
-                            .stop_drawing()
+                        let pass = PassContext {
+                            bind_tables,
+                            scratch: &views,
+                        };
+                        subpass_stream = (subpass.cb)(subpass_stream, &pass);
+                        stream = subpass_stream.stop_drawing();
+                        for resolve in &resolves {
+                            stream = stream.resolve_image(resolve);
+                        }
+                        stream = stream
                             .sync(SyncPoint::GraphicsToGraphics, Scope::All)
                             .debug_label(&end_label);
                         stream.end()
@@ -323,8 +1216,26 @@ impl RenderGraph {
                 }
                 GraphPass::Compute(mut compute) => {
                     handles.push(self.thread_pool.execute(move || {
-                        let stream = CommandStream::new().begin();
-                        (compute.cb)(stream)
+                        let mut stream = CommandStream::new().begin();
+                        if let Some(wait) = alias_wait {
+                            stream = stream.sync(wait, Scope::All);
+                        }
+                        (compute.cb)(stream, &views)
+                    }));
+                }
+                GraphPass::Transfer(copy) => {
+                    let wait = if follows_render {
+                        SyncPoint::GraphicsToTransfer
+                    } else {
+                        SyncPoint::ComputeToTransfer
+                    };
+                    handles.push(self.thread_pool.execute(move || {
+                        CommandStream::new()
+                            .begin()
+                            .sync(wait, Scope::All)
+                            .copy_buffers(&copy)
+                            .sync(SyncPoint::TransferToCompute, Scope::All)
+                            .end()
                     }));
                 }
             }
@@ -353,6 +1264,10 @@ impl RenderGraph {
             .submit(info)
             .expect("Failed to submit render graph commands");
 
+        for history in self.history.values_mut() {
+            history.current ^= 1;
+        }
+
         // Advance transient allocator
         self.alloc.as_mut().advance();
         self.passes.clear();
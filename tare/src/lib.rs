@@ -1,3 +1,5 @@
 pub mod graph;
 pub mod transient;
 pub mod utils;
+
+pub use utils::make_typed_buffer;
@@ -1,17 +1,34 @@
 use std::collections::HashSet;
-use std::{
-    collections::{HashMap, hash_map::DefaultHasher},
-    hash::{Hash, Hasher},
-    ptr::NonNull,
-};
+use std::{collections::HashMap, hash::Hasher, ptr::NonNull};
 
 use dashi::*;
+use fnv::FnvHasher;
 
 pub struct Ring<T, const N: usize> {
     current: usize,
     data: [T; N],
 }
 
+/// Errors from constructing a [`Ring`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RingError {
+    /// Fewer elements were supplied than the ring has slots.
+    InsufficientData { required: usize, provided: usize },
+}
+
+impl std::fmt::Display for RingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RingError::InsufficientData { required, provided } => write!(
+                f,
+                "ring needs {required} elements but only {provided} were provided"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RingError {}
+
 #[derive(Clone, Copy, Debug)]
 pub struct TransientImage {
     pub view: ImageView,
@@ -19,22 +36,27 @@ pub struct TransientImage {
 }
 
 pub trait BindlessTextureRegistry {
-    fn add_texture(&mut self, view: ImageView) -> u16;
+    /// Registers `view` and returns its bindless id, or `None` when the registry is full. The
+    /// image is then left without a bindless id.
+    fn add_texture(&mut self, view: ImageView) -> Option<u16>;
     fn remove_texture(&mut self, id: u16);
-    fn add_cubemap(&mut self, view: ImageView) -> u16;
+    fn add_cubemap(&mut self, view: ImageView) -> Option<u16>;
     fn remove_cubemap(&mut self, id: u16);
 }
 
 #[derive(Clone, Copy)]
 struct BindlessRegistry {
     ctx: *mut (),
-    add: unsafe fn(*mut (), ImageView) -> u16,
+    add: unsafe fn(*mut (), ImageView) -> Option<u16>,
     remove: unsafe fn(*mut (), u16),
-    add_cubemap: unsafe fn(*mut (), ImageView) -> u16,
+    add_cubemap: unsafe fn(*mut (), ImageView) -> Option<u16>,
     remove_cubemap: unsafe fn(*mut (), u16),
 }
 
-unsafe fn add_texture_impl<T: BindlessTextureRegistry>(ctx: *mut (), view: ImageView) -> u16 {
+unsafe fn add_texture_impl<T: BindlessTextureRegistry>(
+    ctx: *mut (),
+    view: ImageView,
+) -> Option<u16> {
     let registry = unsafe { &mut *(ctx as *mut T) };
     registry.add_texture(view)
 }
@@ -44,7 +66,10 @@ unsafe fn remove_texture_impl<T: BindlessTextureRegistry>(ctx: *mut (), id: u16)
     registry.remove_texture(id);
 }
 
-unsafe fn add_cubemap_impl<T: BindlessTextureRegistry>(ctx: *mut (), view: ImageView) -> u16 {
+unsafe fn add_cubemap_impl<T: BindlessTextureRegistry>(
+    ctx: *mut (),
+    view: ImageView,
+) -> Option<u16> {
     let registry = unsafe { &mut *(ctx as *mut T) };
     registry.add_cubemap(view)
 }
@@ -66,12 +91,26 @@ impl<T: Default, const N: usize> Ring<T, N> {
     where
         T: Clone,
     {
-        assert!(data.len() >= N, "not enough data to fill ring");
+        Self::try_new_with(data).expect("not enough data to fill ring")
+    }
 
-        Self {
+    /// Fills the ring from the first `N` elements of `data`, failing instead of panicking when
+    /// there are fewer than `N`.
+    pub fn try_new_with(data: &[T]) -> Result<Self, RingError>
+    where
+        T: Clone,
+    {
+        if data.len() < N {
+            return Err(RingError::InsufficientData {
+                required: N,
+                provided: data.len(),
+            });
+        }
+
+        Ok(Self {
             current: 0,
             data: std::array::from_fn(|idx| data[idx].clone()),
-        }
+        })
     }
 
     pub fn set(&mut self, data: T, idx: usize) {
@@ -138,9 +177,47 @@ impl From<&BufferInfo<'_>> for BufferKey {
     }
 }
 
-fn hash_value<T: Hash>(value: &T) -> u64 {
-    let mut hasher = DefaultHasher::new();
-    value.hash(&mut hasher);
+/// Render pass reuse key: FNV-1a over a fixed little-endian encoding of `info`'s viewport and
+/// subpasses, so keys are the same on every machine and toolchain. The debug name is ignored.
+pub fn render_pass_key(info: &RenderPassInfo) -> u64 {
+    let mut hasher = FnvHasher::default();
+    let viewport = &info.viewport;
+    for value in [viewport.area.x, viewport.area.y, viewport.area.w, viewport.area.h] {
+        hasher.write(&value.to_bits().to_le_bytes());
+    }
+    for value in [viewport.scissor.x, viewport.scissor.y] {
+        hasher.write(&value.to_le_bytes());
+    }
+    for value in [viewport.scissor.w, viewport.scissor.h] {
+        hasher.write(&value.to_le_bytes());
+    }
+
+    for subpass in info.subpasses {
+        hasher.write(&(subpass.color_attachments.len() as u32).to_le_bytes());
+        for desc in subpass.color_attachments.iter().chain(subpass.depth_stencil_attachment) {
+            for value in [
+                desc.format as u32,
+                desc.samples as u32,
+                desc.load_op as u32,
+                desc.store_op as u32,
+                desc.stencil_load_op as u32,
+                desc.stencil_store_op as u32,
+            ] {
+                hasher.write(&value.to_le_bytes());
+            }
+        }
+        hasher.write(&[subpass.depth_stencil_attachment.is_some() as u8]);
+        hasher.write(&(subpass.subpass_dependencies.len() as u32).to_le_bytes());
+        for dependency in subpass.subpass_dependencies {
+            for value in [
+                dependency.subpass_id,
+                dependency.attachment_id,
+                dependency.depth_id,
+            ] {
+                hasher.write(&value.to_le_bytes());
+            }
+        }
+    }
     hasher.finish()
 }
 
@@ -158,10 +235,41 @@ struct ImageReuseEntry {
     is_cubemap: bool,
 }
 
+/// How many `make_*` requests were served from the reuse pools versus newly allocated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransientStats {
+    pub images_reused: usize,
+    pub images_allocated: usize,
+    pub buffers_reused: usize,
+    pub buffers_allocated: usize,
+    pub semaphores_reused: usize,
+    pub semaphores_allocated: usize,
+}
+
 const MAX_FRAMES: usize = 3;
-const UNUSED_RETIRE_THRESHOLD: usize = MAX_FRAMES * 12;
+
+/// How many frames pooled resources may sit unused before the allocator destroys them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransientConfig {
+    /// Applies to images, buffers and render passes.
+    pub retire_threshold: usize,
+    /// Applies to semaphores, which are cheap to keep and reused often enough that retiring
+    /// them on the image schedule only churns.
+    pub semaphore_retire_threshold: usize,
+}
+
+impl Default for TransientConfig {
+    fn default() -> Self {
+        Self {
+            retire_threshold: MAX_FRAMES * 12,
+            semaphore_retire_threshold: MAX_FRAMES * 48,
+        }
+    }
+}
+
 pub struct TransientAllocator {
     ctx: NonNull<Context>,
+    config: TransientConfig,
     images: Ring<Vec<(ImageKey, Handle<Image>, Option<u16>, bool)>, MAX_FRAMES>,
     buffers: Ring<Vec<(BufferKey, Handle<Buffer>)>, MAX_FRAMES>,
     renderpasses: Ring<Vec<(u64, Handle<RenderPass>)>, MAX_FRAMES>,
@@ -175,14 +283,22 @@ pub struct TransientAllocator {
     free_renderpasses: Vec<Handle<RenderPass>>,
     free_semaphores: Vec<Handle<Semaphore>>,
     global_images: HashMap<Handle<Image>, (Option<u16>, bool)>,
+    // Global images retired in each frame, destroyed once that frame can no longer be in flight.
+    retired_globals: Ring<Vec<(Handle<Image>, bool)>, MAX_FRAMES>,
     bindless_registry: Option<BindlessRegistry>,
     bindless_image_ids: HashMap<Handle<Image>, u16>,
+    stats: TransientStats,
 }
 
 impl TransientAllocator {
     pub fn new(ctx: &mut Context) -> Self {
+        Self::with_config(ctx, TransientConfig::default())
+    }
+
+    pub fn with_config(ctx: &mut Context, config: TransientConfig) -> Self {
         Self {
             ctx: NonNull::from(ctx),
+            config,
             images: Ring::new(),
             buffers: Ring::new(),
             renderpasses: Ring::new(),
@@ -196,8 +312,10 @@ impl TransientAllocator {
             free_renderpasses: Vec::new(),
             free_semaphores: Vec::new(),
             global_images: HashMap::new(),
+            retired_globals: Ring::new(),
             bindless_registry: None,
             bindless_image_ids: HashMap::new(),
+            stats: TransientStats::default(),
         }
     }
 
@@ -205,6 +323,48 @@ impl TransientAllocator {
         unsafe { self.ctx.as_ref() }
     }
 
+    /// Reuse counters for every image, buffer and semaphore made since the allocator was created.
+    pub fn stats(&self) -> TransientStats {
+        self.stats
+    }
+
+    /// Creates `copies` images and buffers for each of the given shapes up front and parks them
+    /// in the reuse pools, so the first frames reuse them instead of allocating.
+    ///
+    /// Prewarmed resources age out like any other unused pool entry. They don't count towards
+    /// [`TransientStats`] until a `make_*` call picks them up.
+    pub fn prewarm(&mut self, images: &[ImageInfo], buffers: &[BufferInfo], copies: usize) {
+        for info in images {
+            for _ in 0..copies {
+                let handle = unsafe { self.ctx.as_mut() }
+                    .make_image(info)
+                    .expect("Make prewarmed image");
+                let bindless_id = self.register_bindless_image(handle, false);
+                self.available_images
+                    .entry(ImageKey::from(info))
+                    .or_default()
+                    .push(ImageReuseEntry {
+                        handle,
+                        age: 0,
+                        bindless_id,
+                        is_cubemap: false,
+                    });
+            }
+        }
+
+        for info in buffers {
+            for _ in 0..copies {
+                let handle = unsafe { self.ctx.as_mut() }
+                    .make_buffer(info)
+                    .expect("Make prewarmed buffer");
+                self.available_buffers
+                    .entry(BufferKey::from(info))
+                    .or_default()
+                    .push(ReuseEntry { handle, age: 0 });
+            }
+        }
+    }
+
     pub fn new_with_bindless_registry(
         ctx: &mut Context,
         registry: &mut impl BindlessTextureRegistry,
@@ -243,7 +403,7 @@ impl TransientAllocator {
             unsafe { (registry.add_cubemap)(registry.ctx, view) }
         } else {
             unsafe { (registry.add)(registry.ctx, view) }
-        };
+        }?;
 
         self.bindless_image_ids.insert(handle, id);
         Some(id)
@@ -302,9 +462,16 @@ impl TransientAllocator {
                 age: 0,
             });
         }
+
+        let retired = self.retired_globals.get_mut(stale_index);
+        self.free_images.extend(retired.drain(..));
     }
 
     fn collect_unused(&mut self) {
+        let TransientConfig {
+            retire_threshold,
+            semaphore_retire_threshold,
+        } = self.config;
         let mut free_images = Vec::new();
         let mut free_buffers = Vec::new();
         let mut free_renderpasses = Vec::new();
@@ -313,7 +480,7 @@ impl TransientAllocator {
         self.available_images.retain(|_, list| {
             list.retain_mut(|entry| {
                 entry.age += 1;
-                if entry.age >= UNUSED_RETIRE_THRESHOLD {
+                if entry.age >= retire_threshold {
                     free_images.push((entry.handle, entry.is_cubemap));
                     false
                 } else {
@@ -326,7 +493,7 @@ impl TransientAllocator {
         self.available_buffers.retain(|_, list| {
             list.retain_mut(|entry| {
                 entry.age += 1;
-                if entry.age >= UNUSED_RETIRE_THRESHOLD {
+                if entry.age >= retire_threshold {
                     free_buffers.push(entry.handle);
                     false
                 } else {
@@ -339,7 +506,7 @@ impl TransientAllocator {
         self.available_renderpasses.retain(|_, list| {
             list.retain_mut(|entry| {
                 entry.age += 1;
-                if entry.age >= UNUSED_RETIRE_THRESHOLD {
+                if entry.age >= retire_threshold {
                     free_renderpasses.push(entry.handle);
                     false
                 } else {
@@ -351,7 +518,7 @@ impl TransientAllocator {
 
         self.available_semaphores.retain_mut(|entry| {
             entry.age += 1;
-            if entry.age >= UNUSED_RETIRE_THRESHOLD {
+            if entry.age >= semaphore_retire_threshold {
                 free_semaphores.push(entry.handle);
                 false
             } else {
@@ -389,6 +556,7 @@ impl TransientAllocator {
         self.buffers.advance();
         self.renderpasses.advance();
         self.semaphores.advance();
+        self.retired_globals.advance();
     }
 
     // Make a transient image matching the parameters input from this frame.
@@ -408,7 +576,7 @@ impl TransientAllocator {
             .iter()
             .map(|(_, handle, _, _)| *handle)
             .collect();
-        let (handle, bindless_id) = self
+        let reused = self
             .available_images
             .get_mut(&key)
             .and_then(|list| {
@@ -420,14 +588,25 @@ impl TransientAllocator {
                         let entry = list.swap_remove(index);
                         (entry.handle, entry.bindless_id)
                     })
-            })
-            .unwrap_or_else(|| {
-                let handle = unsafe { self.ctx.as_mut() }
-                    .make_image(info)
-                    .expect("Make transient image");
-                let bindless_id = self.register_bindless_image(handle, cubemap);
-                (handle, bindless_id)
             });
+        #[cfg(feature = "log")]
+        log::debug!(
+            target: "tare",
+            "transient image {key:?}: {}",
+            if reused.is_some() { "reused" } else { "allocated" }
+        );
+        if reused.is_some() {
+            self.stats.images_reused += 1;
+        } else {
+            self.stats.images_allocated += 1;
+        }
+        let (handle, bindless_id) = reused.unwrap_or_else(|| {
+            let handle = unsafe { self.ctx.as_mut() }
+                .make_image(info)
+                .expect("Make transient image");
+            let bindless_id = self.register_bindless_image(handle, cubemap);
+            (handle, bindless_id)
+        });
 
         self.images
             .data_mut()
@@ -440,6 +619,35 @@ impl TransientAllocator {
         TransientImage { view, bindless_id }
     }
 
+    // Pops a pooled buffer for `key` that this frame isn't already using, updating the stats.
+    fn take_available_buffer(
+        &mut self,
+        key: BufferKey,
+        in_use: &HashSet<Handle<Buffer>>,
+    ) -> Option<Handle<Buffer>> {
+        let reused = self.available_buffers.get_mut(&key).and_then(|list| {
+            list.iter()
+                .rposition(|entry| !in_use.contains(&entry.handle))
+                .map(|index| list.swap_remove(index).handle)
+        });
+        if reused.is_some() {
+            self.stats.buffers_reused += 1;
+        } else {
+            self.stats.buffers_allocated += 1;
+        }
+        reused
+    }
+
+    /// Byte size of `handle` if it is one of this frame's transient buffers. dashi can't be
+    /// asked for a buffer's size, so only buffers made here are known.
+    pub fn buffer_size(&self, handle: Handle<Buffer>) -> Option<u64> {
+        self.buffers
+            .data()
+            .iter()
+            .find(|(_, buffer)| *buffer == handle)
+            .map(|(key, _)| key.byte_size as u64)
+    }
+
     // Make a transient buffer matching the parameters input
     pub fn make_buffer(&mut self, info: &BufferInfo) -> BufferView {
         let key = BufferKey::from(info);
@@ -449,19 +657,11 @@ impl TransientAllocator {
             .iter()
             .map(|(_, handle)| *handle)
             .collect();
-        let handle = self
-            .available_buffers
-            .get_mut(&key)
-            .and_then(|list| {
-                list.iter()
-                    .rposition(|entry| !in_use.contains(&entry.handle))
-                    .map(|index| list.swap_remove(index).handle)
-            })
-            .unwrap_or_else(|| {
-                unsafe { self.ctx.as_mut() }
-                    .make_buffer(info)
-                    .expect("Make transient buffer")
-            });
+        let handle = self.take_available_buffer(key, &in_use).unwrap_or_else(|| {
+            unsafe { self.ctx.as_mut() }
+                .make_buffer(info)
+                .expect("Make transient buffer")
+        });
 
         self.buffers.data_mut().push((key, handle));
 
@@ -477,19 +677,11 @@ impl TransientAllocator {
             .iter()
             .map(|(_, handle)| *handle)
             .collect();
-        let handle = self
-            .available_buffers
-            .get_mut(&key)
-            .and_then(|list| {
-                list.iter()
-                    .rposition(|entry| !in_use.contains(&entry.handle))
-                    .map(|index| list.swap_remove(index).handle)
-            })
-            .unwrap_or_else(|| {
-                unsafe { self.ctx.as_mut() }
-                    .make_buffer(info)
-                    .expect("Make transient buffer")
-            });
+        let handle = self.take_available_buffer(key, &in_use).unwrap_or_else(|| {
+            unsafe { self.ctx.as_mut() }
+                .make_buffer(info)
+                .expect("Make transient buffer")
+        });
 
         self.buffers.data_mut().push((key, handle));
 
@@ -504,7 +696,7 @@ impl TransientAllocator {
     }
 
     pub fn make_render_pass(&mut self, info: &RenderPassInfo) -> Handle<RenderPass> {
-        let hash = hash_value(info);
+        let hash = render_pass_key(info);
         let handle = self
             .available_renderpasses
             .get_mut(&hash)
@@ -526,16 +718,21 @@ impl TransientAllocator {
 
     pub fn make_semaphore(&mut self) -> Handle<Semaphore> {
         let in_use: HashSet<Handle<Semaphore>> = self.semaphores.data().iter().copied().collect();
-        let handle = self
+        let reused = self
             .available_semaphores
             .iter()
             .rposition(|entry| !in_use.contains(&entry.handle))
-            .map(|index| self.available_semaphores.swap_remove(index).handle)
-            .unwrap_or_else(|| {
-                unsafe { self.ctx.as_mut() }
-                    .make_semaphore()
-                    .expect("Make transient semaphore")
-            });
+            .map(|index| self.available_semaphores.swap_remove(index).handle);
+        if reused.is_some() {
+            self.stats.semaphores_reused += 1;
+        } else {
+            self.stats.semaphores_allocated += 1;
+        }
+        let handle = reused.unwrap_or_else(|| {
+            unsafe { self.ctx.as_mut() }
+                .make_semaphore()
+                .expect("Make transient semaphore")
+        });
 
         self.semaphores.data_mut().push(handle);
 
@@ -590,6 +787,14 @@ impl TransientAllocator {
             unsafe { self.ctx.as_mut() }.destroy_image(image);
         }
     }
+
+    /// Destroys a global image or cubemap once frames in flight are done with it, on the same
+    /// schedule transient images are recycled on.
+    pub fn retire_global_image(&mut self, image: Handle<Image>) {
+        if let Some((_, is_cubemap)) = self.global_images.remove(&image) {
+            self.retired_globals.data_mut().push((image, is_cubemap));
+        }
+    }
 }
 
 impl Drop for TransientAllocator {
@@ -650,6 +855,9 @@ impl Drop for TransientAllocator {
             ctx.destroy_semaphore(sem.handle);
         }
 
+        for retired in self.retired_globals.data.iter_mut() {
+            self.free_images.append(retired);
+        }
         let handles: Vec<_> = self.free_images.drain(..).collect();
         for (handle, is_cubemap) in handles {
             self.unregister_bindless_image(handle, is_cubemap);
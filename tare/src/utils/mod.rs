@@ -1,5 +1,7 @@
 pub mod error;
 pub mod staged_buffer;
 pub mod thread_pool;
+pub mod typed_buffer;
 pub use staged_buffer::*;
 pub use thread_pool::*;
+pub use typed_buffer::*;
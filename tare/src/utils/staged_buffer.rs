@@ -1,4 +1,4 @@
-use cmd::Recording;
+use cmd::{Executable, Recording};
 use dashi::*;
 use driver::command::CopyBuffer;
 
@@ -9,11 +9,12 @@ pub struct StagedBuffer {
     device: BufferView,
     host: BufferView,
     mapped: (*mut u8, usize),
+    shared: bool,
 }
 
 impl Default for StagedBuffer {
     fn default() -> Self {
-        Self { device: BufferView::new(Default::default()), host: BufferView::new(Default::default()), mapped: Default::default() }
+        Self { device: BufferView::new(Default::default()), host: BufferView::new(Default::default()), mapped: Default::default(), shared: false }
     }
 }
 impl StagedBuffer {
@@ -42,6 +43,32 @@ impl StagedBuffer {
             device: BufferView::new(device),
             host: BufferView::new(host),
             mapped,
+            shared: false,
+        };
+    }
+
+    /// Creates a single host-visible buffer the GPU reads directly, for unified-memory devices.
+    /// `device()` and `host()` return the same buffer and the sync methods record nothing.
+    pub fn new_shared(ctx: &mut Context, info: BufferInfo) -> Self {
+        let mut info = info.clone();
+
+        info.visibility = MemoryVisibility::CpuAndGpu;
+
+        let buffer = ctx
+            .make_buffer(&info)
+            .expect("Unable to make shared buffer!");
+
+        let mapped = (
+            ctx.map_buffer_mut::<u8>(BufferView::new(buffer))
+                .expect("Unable to map shared buffer")
+                .as_mut_ptr(),
+            info.byte_size as usize,
+        );
+        return Self {
+            device: BufferView::new(buffer),
+            host: BufferView::new(buffer),
+            mapped,
+            shared: true,
         };
     }
 
@@ -60,6 +87,7 @@ impl StagedBuffer {
             device,
             host,
             mapped: (ptr, len as usize),
+            shared: false,
         };
     }
 
@@ -71,6 +99,11 @@ impl StagedBuffer {
         self.host
     }
 
+    /// Whether host and device share one buffer, making uploads unnecessary.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
     pub fn as_slice<T>(&self) -> &'static [T] {
         let (ptr, len) = self.mapped;
         let ptr = ptr as *const T;
@@ -86,6 +119,10 @@ impl StagedBuffer {
     }
 
     pub fn sync_up(&self) -> CommandStream<Recording> {
+        if self.shared {
+            return CommandStream::new().begin();
+        }
+
         CommandStream::new().begin().copy_buffers(&CopyBuffer {
             src: self.host.handle,
             dst: self.device.handle,
@@ -94,7 +131,7 @@ impl StagedBuffer {
     }
 
     pub fn sync_up_range(&self, offset: u32, amount: u32) -> CommandStream<Recording> {
-        if amount == 0 {
+        if amount == 0 || self.shared {
             return CommandStream::new().begin();
         }
 
@@ -107,7 +144,40 @@ impl StagedBuffer {
         })
     }
 
+    /// Writes `data` into the staging memory and returns a recorded copy of it into `into`,
+    /// for the caller to submit alongside their frame instead of blocking on an upload. The
+    /// staging memory must not be written again until that submission has finished.
+    pub fn record_upload<T: bytemuck::Pod>(
+        &mut self,
+        data: &[T],
+        into: Handle<Buffer>,
+    ) -> CommandStream<Executable> {
+        let amount = std::mem::size_of_val(data);
+        assert!(
+            amount <= self.mapped.1,
+            "upload of {amount} bytes exceeds the {} byte staging buffer",
+            self.mapped.1
+        );
+
+        self.as_slice_mut::<u8>()[..amount].copy_from_slice(bytemuck::cast_slice(data));
+
+        CommandStream::new()
+            .begin()
+            .copy_buffers(&CopyBuffer {
+                src: self.host.handle,
+                dst: into,
+                src_offset: 0,
+                dst_offset: 0,
+                amount: amount as u32,
+            })
+            .end()
+    }
+
     pub fn sync_down(&self) -> CommandStream<Recording> {
+        if self.shared {
+            return CommandStream::new().begin();
+        }
+
         CommandStream::new().begin().copy_buffers(&CopyBuffer {
             src: self.device.handle,
             dst: self.host.handle,
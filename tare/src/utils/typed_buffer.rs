@@ -0,0 +1,21 @@
+use dashi::*;
+
+/// Creates a device-local buffer sized for `data` and initialized with its bytes.
+///
+/// Vulkan has no zero-sized buffers, so an empty `data` gets a one-word, uninitialized buffer.
+pub fn make_typed_buffer<T: bytemuck::Pod>(
+    ctx: &mut Context,
+    name: &str,
+    data: &[T],
+    usage: BufferUsage,
+) -> Handle<Buffer> {
+    let bytes: &[u8] = bytemuck::cast_slice(data);
+    ctx.make_buffer(&BufferInfo {
+        debug_name: name,
+        byte_size: bytes.len().max(4) as u32,
+        visibility: MemoryVisibility::Gpu,
+        usage,
+        initial_data: (!bytes.is_empty()).then_some(bytes),
+    })
+    .expect("Unable to make typed buffer!")
+}
@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+
+use dashi::gpu::vulkan::GPUError;
+use dashi::{
+    Context, ContextInfo, DebugMessageSeverity, DebugMessageType, DebugMessenger,
+    DebugMessengerCreateInfo,
+};
+use std::ffi::{CStr, c_void};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Serializes the `DASHI_VALIDATION` switch around context creation.
+static VALIDATION_ENV: Mutex<()> = Mutex::new(());
+
+/// Headless context with validation layers enabled. Dropping it fails the test if the layers
+/// reported an API usage error.
+pub struct ValidationContext {
+    ctx: Option<Context>,
+    guard: Option<ValidationGuard>,
+}
+
+impl ValidationContext {
+    pub fn headless(info: &ContextInfo) -> Result<Self, GPUError> {
+        let ctx = {
+            let _env = VALIDATION_ENV.lock().unwrap_or_else(|err| err.into_inner());
+            unsafe {
+                std::env::set_var("DASHI_VALIDATION", "1");
+            }
+            let ctx = Context::headless(info);
+            unsafe {
+                std::env::set_var("DASHI_VALIDATION", "0");
+            }
+            ctx?
+        };
+
+        let guard = ValidationGuard::new(&ctx)?;
+
+        Ok(Self {
+            ctx: Some(ctx),
+            guard: Some(guard),
+        })
+    }
+}
+
+impl std::ops::Deref for ValidationContext {
+    type Target = Context;
+
+    fn deref(&self) -> &Self::Target {
+        self.ctx.as_ref().expect("context should exist")
+    }
+}
+
+impl std::ops::DerefMut for ValidationContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ctx.as_mut().expect("context should exist")
+    }
+}
+
+impl Drop for ValidationContext {
+    fn drop(&mut self) {
+        // Pipelines point at the context where it lives now, so retire it before `take` moves it.
+        if let Some(ctx) = &self.ctx {
+            bento::builder::retire_context(ctx);
+        }
+        if let Some(ctx) = self.ctx.take() {
+            if let Some(mut guard) = self.guard.take() {
+                guard.teardown(&ctx);
+            }
+
+            ctx.destroy();
+        }
+    }
+}
+
+struct ValidationGuard {
+    validation_flag: Arc<AtomicBool>,
+    validation_ptr: Option<*const AtomicBool>,
+    debug_messenger: Option<DebugMessenger>,
+}
+
+impl ValidationGuard {
+    fn new(ctx: &Context) -> Result<Self, GPUError> {
+        let validation_flag = Arc::new(AtomicBool::new(false));
+        let validation_ptr = Arc::into_raw(Arc::clone(&validation_flag));
+
+        let messenger_info = DebugMessengerCreateInfo {
+            message_severity: DebugMessageSeverity::ERROR,
+            message_type: DebugMessageType::VALIDATION,
+            user_callback: validation_error_callback,
+            user_data: validation_ptr as *mut c_void,
+        };
+
+        let debug_messenger = ctx.create_debug_messenger(&messenger_info)?;
+
+        Ok(Self {
+            validation_flag,
+            validation_ptr: Some(validation_ptr),
+            debug_messenger: Some(debug_messenger),
+        })
+    }
+
+    fn teardown(&mut self, ctx: &Context) {
+        if let Some(messenger) = self.debug_messenger.take() {
+            ctx.destroy_debug_messenger(messenger);
+        }
+
+        if let Some(ptr) = self.validation_ptr.take() {
+            unsafe {
+                let _ = Arc::from_raw(ptr);
+            }
+        }
+
+        if !std::thread::panicking() {
+            assert!(
+                !self.validation_flag.load(Ordering::SeqCst),
+                "Vulkan validation layers reported an API usage error"
+            );
+        }
+    }
+}
+
+unsafe extern "system" fn validation_error_callback(
+    message_severity: DebugMessageSeverity,
+    message_type: DebugMessageType,
+    _p_callback_data: &CStr,
+    user_data: *mut c_void,
+) -> bool {
+    if message_severity.contains(DebugMessageSeverity::ERROR)
+        && message_type.contains(DebugMessageType::VALIDATION)
+    {
+        if let Some(flag) =
+            (!user_data.is_null()).then(|| unsafe { &*(user_data as *const AtomicBool) })
+        {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    false
+}
@@ -1,6 +1,42 @@
+mod common;
+
+use bento::builder::PSOBuilder;
+use common::ValidationContext;
 use dashi::driver::command::CopyBuffer;
 use dashi::*;
-use tare::graph::RenderGraph;
+use tare::graph::{
+    INDEXED_INDIRECT_STRIDE, IndirectDraw, IndirectDrawExt, RenderGraph, SubpassInfo,
+};
+
+const FULLSCREEN_VERTEX: &str = r#"
+#version 450
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+const GREEN_FRAGMENT: &str = r#"
+#version 450
+layout(location = 0) out vec4 color;
+void main() {
+    color = vec4(0.0, 1.0, 0.0, 1.0);
+}
+"#;
+
+fn compile_shader(stage: ShaderType, source: &str) -> bento::CompilationResult {
+    let compiler = bento::Compiler::new().expect("compiler should initialize");
+    let request = bento::Request {
+        name: None,
+        lang: bento::ShaderLang::Glsl,
+        stage,
+        ..Default::default()
+    };
+
+    compiler
+        .compile(source.as_bytes(), &request)
+        .expect("shader should compile")
+}
 
 #[test]
 fn compute_pass_dispatches_and_writes_buffer() {
@@ -57,3 +93,213 @@ fn compute_pass_dispatches_and_writes_buffer() {
 
     assert_eq!(data, [7]);
 }
+
+#[test]
+fn sequential_compute_passes_alias_scratch_images() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let info = ImageInfo {
+        debug_name: "[SCRATCH]",
+        dim: [16, 16, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    };
+    let first = graph.make_scratch_image(&info);
+    let second = graph.make_scratch_image(&info);
+
+    let used = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    for scratch in [first, second] {
+        let used = used.clone();
+        graph.add_compute_pass_with_scratch(&[scratch], move |stream, views| {
+            used.lock().unwrap().push(views[0].img);
+            stream.end()
+        });
+    }
+    graph.execute();
+    context.sync_current_device();
+
+    let stats = graph.aliasing_stats();
+    assert_eq!(stats.scratch_images, 2);
+    assert_eq!(stats.physical_images, 1);
+    assert!(stats.aliased());
+    // The second pass waits for the first to finish with the shared image.
+    assert_eq!(stats.alias_barriers, 1);
+
+    let used = used.lock().unwrap();
+    assert_eq!(used.len(), 2);
+    assert_eq!(used[0], used[1]);
+}
+
+#[test]
+fn indirect_draw_waits_on_compute_written_args() {
+    const WIDTH: u32 = 16;
+    const HEIGHT: u32 = 16;
+
+    let mut context = ValidationContext::headless(&Default::default()).expect("headless context");
+    let pso = PSOBuilder::new()
+        .vertex_compiled(Some(compile_shader(ShaderType::Vertex, FULLSCREEN_VERTEX)))
+        .fragment_compiled(Some(compile_shader(ShaderType::Fragment, GREEN_FRAGMENT)))
+        .set_attachment_format(0, Format::RGBA8)
+        .build(&mut context)
+        .expect("indirect pipeline");
+    let readback = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[INDIRECT READBACK]",
+            byte_size: WIDTH * HEIGHT * 4,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        })
+        .expect("readback buffer");
+
+    let mut graph = RenderGraph::new(&mut context);
+
+    let args_size = INDEXED_INDIRECT_STRIDE;
+    // One fullscreen triangle: index count, instance count, first index, vertex offset and
+    // first instance. The draw only covers the target if it reads what the copy wrote.
+    let culled_args = [3u32, 1, 0, 0, 0];
+
+    let source = graph.make_buffer(&BufferInfo {
+        debug_name: "[CULLED ARGS]",
+        byte_size: args_size,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(bytemuck::cast_slice(&culled_args)),
+    });
+    let args = graph.make_buffer(&BufferInfo {
+        debug_name: "[INDIRECT ARGS]",
+        byte_size: args_size,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(&[0u8; INDEXED_INDIRECT_STRIDE as usize]),
+    });
+    let geometry = graph.make_buffer(&BufferInfo {
+        debug_name: "[INDIRECT INDICES]",
+        byte_size: 12,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(bytemuck::cast_slice(&[0u32, 1, 2])),
+    });
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[INDIRECT TARGET]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+    let viewport = Viewport {
+        area: FRect2D {
+            w: WIDTH as f32,
+            h: HEIGHT as f32,
+            ..Default::default()
+        },
+        scissor: Rect2D {
+            w: WIDTH,
+            h: HEIGHT,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pipeline = pso.handle;
+
+    graph.add_compute_pass(move |stream| {
+        stream
+            .copy_buffers(&CopyBuffer {
+                src: source.handle,
+                dst: args.handle,
+                src_offset: 0,
+                dst_offset: 0,
+                amount: args_size,
+            })
+            .end()
+    });
+    graph.add_subpass(
+        &SubpassInfo {
+            viewport,
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            clear_values: [
+                Some(ClearValue::Color([0.0, 0.0, 0.0, 1.0])),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            ..Default::default()
+        },
+        move |stream| {
+            stream
+                .bind_graphics_pipeline(pipeline)
+                .update_viewport(&viewport)
+                .draw_indirect(&IndirectDraw {
+                    vertices: geometry.handle,
+                    indices: geometry.handle,
+                    args,
+                    draw_count: 1,
+                    ..Default::default()
+                })
+                .unbind_graphics_pipeline()
+        },
+    );
+
+    assert_eq!(graph.compute_barriers(), vec![1]);
+    graph.execute();
+    graph.read_image(target.view, readback);
+
+    let data = context
+        .map_buffer::<u8>(readback.into())
+        .expect("map readback buffer")
+        .to_vec();
+    context.unmap_buffer(readback).expect("unmap readback buffer");
+
+    assert!(data.chunks_exact(4).all(|texel| texel == [0, 255, 0, 255]));
+}
+
+#[test]
+fn fill_pass_writes_value_to_every_element() {
+    let mut context = ValidationContext::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    const ELEMENTS: usize = 16;
+    let byte_size = (ELEMENTS * std::mem::size_of::<u32>()) as u32;
+
+    let counters = graph.make_buffer(&BufferInfo {
+        debug_name: "[COUNTERS]",
+        byte_size,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(&[0u8; ELEMENTS * std::mem::size_of::<u32>()]),
+    });
+    let readback = graph.make_buffer(&BufferInfo {
+        debug_name: "[READBACK]",
+        byte_size,
+        visibility: MemoryVisibility::CpuAndGpu,
+        usage: BufferUsage::ALL,
+        initial_data: None,
+    });
+
+    graph.add_fill_pass(counters, 0xDEAD_BEEF);
+    graph.add_fill_pass(BufferView { size: 8, ..counters }, 7);
+    graph.add_copy_pass(counters, readback, byte_size as u64);
+    graph.execute();
+
+    context.sync_current_device();
+    let data = context
+        .map_buffer::<u32>(readback.handle.into())
+        .expect("map readback buffer")
+        .to_vec();
+    context
+        .unmap_buffer(readback.handle)
+        .expect("unmap readback buffer");
+
+    assert_eq!(data.len(), ELEMENTS);
+    assert_eq!(data[..2], [7, 7]);
+    assert!(data[2..].iter().all(|value| *value == 0xDEAD_BEEF));
+}
@@ -1,8 +1,76 @@
-use dashi::driver::command::CopyImageBuffer;
+mod common;
+
+use bento::builder::PSOBuilder;
+use common::ValidationContext;
+use dashi::builders::BindTableLayoutBuilder;
+use dashi::driver::command::{CopyImageBuffer, DrawIndexed};
 use dashi::*;
+use std::sync::{Arc, Mutex};
 use tare::graph::*;
 use tare::transient::TransientAllocator;
 
+const FULLSCREEN_VERTEX: &str = r#"
+#version 450
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+const TIMING_FRAGMENT: &str = r#"
+#version 450
+layout(set = 0, binding = 0) uniform Timing {
+    float value;
+} timing;
+layout(location = 0) out vec4 color;
+void main() {
+    color = vec4(timing.value, 0.0, 0.0, 1.0);
+}
+"#;
+
+fn compile_shader(stage: ShaderType, source: &str) -> bento::CompilationResult {
+    let compiler = bento::Compiler::new().expect("compiler should initialize");
+    let request = bento::Request {
+        name: None,
+        lang: bento::ShaderLang::Glsl,
+        stage,
+        ..Default::default()
+    };
+
+    compiler
+        .compile(source.as_bytes(), &request)
+        .expect("shader should compile")
+}
+
+// Copies `image` into a fresh host-visible buffer through `graph` and returns its contents.
+fn read_texels<T: Copy>(
+    context: &mut Context,
+    graph: &mut RenderGraph,
+    image: ImageView,
+    byte_size: u32,
+) -> Vec<T> {
+    let readback = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[READBACK]",
+            byte_size,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        })
+        .expect("create readback buffer");
+    graph.read_image(image, readback);
+
+    let data = context
+        .map_buffer::<T>(readback.into())
+        .expect("map readback buffer")
+        .to_vec();
+    context
+        .unmap_buffer(readback)
+        .expect("unmap readback buffer");
+    context.destroy_buffer(readback);
+    data
+}
+
 #[test]
 fn headless_render_graph_executes_without_validation_noise() {
     // Ensure validation layers stay disabled so the test output remains quiet.
@@ -151,6 +219,155 @@ fn headless_render_graph_outputs_readable_image() {
     }
 }
 
+#[test]
+fn clear_pass_fills_target_for_later_loads() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 4;
+    const HEIGHT: u32 = 4;
+    const EXPECTED_COLOR: [u8; 4] = [0, 255, 0, 255];
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[CLEAR ATTACHMENT]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+
+    graph.add_clear(
+        target.view,
+        ClearValue::color_f32(EXPECTED_COLOR.map(|c| c as f32 / 255.0)),
+    );
+    graph.add_subpass(
+        &SubpassInfo {
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            ..Default::default()
+        },
+        |stream| stream,
+    );
+
+    graph.execute();
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+    assert_eq!(data.len() as u32, WIDTH * HEIGHT * 4);
+    for chunk in data.chunks_exact(4) {
+        assert_eq!(chunk, EXPECTED_COLOR);
+    }
+}
+
+#[test]
+fn headless_render_graph_clears_uint_attachment() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 4;
+    const HEIGHT: u32 = 4;
+    const EXPECTED_COLOR: [u32; 4] = [7, 42, 1024, 65535];
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[UINT ATTACHMENT]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA32Uint,
+        ..Default::default()
+    });
+
+    graph.add_subpass(
+        &SubpassInfo {
+            viewport: Viewport::default(),
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            depth_attachment: None,
+            clear_values: [
+                Some(ClearValue::color_u32(EXPECTED_COLOR)),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            depth_clear: None,
+            ..Default::default()
+        },
+        |stream| stream,
+    );
+
+    graph.render_pass_handle().expect("render pass for uint target");
+    assert!(graph.clear_warnings().is_empty());
+
+    graph.execute();
+
+    let data: Vec<u32> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 16);
+    assert_eq!(data.len() as u32, WIDTH * HEIGHT * 4);
+    for chunk in data.chunks_exact(4) {
+        assert_eq!(chunk, EXPECTED_COLOR);
+    }
+}
+
+#[test]
+fn render_graph_warns_on_float_clear_for_uint_attachment() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[UINT ATTACHMENT]",
+        dim: [4, 4, 1],
+        format: Format::RGBA32Uint,
+        ..Default::default()
+    });
+
+    graph.add_subpass(
+        &SubpassInfo {
+            name: Some("float-clear".to_string()),
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            clear_values: [
+                Some(ClearValue::color_f32([1.0, 0.0, 0.0, 1.0])),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            ..Default::default()
+        },
+        |stream| stream,
+    );
+
+    graph.render_pass_handle().expect("render pass for uint target");
+
+    let warnings = graph.clear_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].pass.as_deref(), Some("float-clear"));
+    assert_eq!(warnings[0].attachment, Some(0));
+}
+
+#[test]
+fn clear_values_match_signed_unsigned_and_depth_formats() {
+    assert!(ClearValue::color_u32([1, 2, 3, 4]).matches_format(Format::R32Uint));
+    assert!(!ClearValue::color_u32([1, 2, 3, 4]).matches_format(Format::R32Sint));
+    assert!(ClearValue::IntColor([1, 2, 3, 4]).matches_format(Format::RGBA32Sint));
+    assert!(!ClearValue::IntColor([1, 2, 3, 4]).matches_format(Format::RGBA32Uint));
+    assert!(!ClearValue::color_f32([0.0; 4]).matches_format(Format::R32Sint));
+    assert!(ClearValue::color_f32([0.0; 4]).matches_format(Format::RGBA16F));
+    assert!(ClearValue::depth_stencil(1.0, 0).matches_format(Format::D24S8));
+    assert!(!ClearValue::color_f32([0.0; 4]).matches_format(Format::D24S8));
+}
+
 #[test]
 fn render_graph_can_reuse_allocator() {
     unsafe {
@@ -218,3 +435,493 @@ fn render_graph_exposes_cubemap_allocators() {
 
     graph.destroy_global_cubemap(global.view.img);
 }
+
+#[test]
+fn multiview_subpass_clears_side_by_side_viewports() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 4;
+    const LEFT_COLOR: [u8; 4] = [255, 0, 0, 255];
+    const RIGHT_COLOR: [u8; 4] = [0, 0, 255, 255];
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[MULTIVIEW ATTACHMENT]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+
+    let half_view = |x: u32, color: [u8; 4]| {
+        let half = WIDTH / 2;
+        SubpassInfo {
+            viewport: Viewport {
+                area: FRect2D {
+                    x: x as f32,
+                    w: half as f32,
+                    h: HEIGHT as f32,
+                    ..Default::default()
+                },
+                scissor: Rect2D {
+                    x,
+                    w: half,
+                    h: HEIGHT,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            clear_values: [
+                Some(ClearValue::Color(color.map(|c| c as f32 / 255.0))),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            ..Default::default()
+        }
+    };
+
+    let recorded = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let views = [half_view(0, LEFT_COLOR), half_view(WIDTH / 2, RIGHT_COLOR)];
+    {
+        let recorded = recorded.clone();
+        graph.add_multiview_subpass(&views, move |stream, view| {
+            recorded.lock().unwrap().push(view);
+            stream
+        });
+    }
+
+    let render_passes = graph.render_pass_handles();
+    assert_eq!(render_passes.len(), 2);
+    assert_eq!(render_passes[0], render_passes[1]);
+
+    graph.execute();
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+
+    let mut recorded = recorded.lock().unwrap().clone();
+    recorded.sort();
+    assert_eq!(recorded, vec![0, 1]);
+
+    for (index, pixel) in data.chunks_exact(4).enumerate() {
+        let x = index as u32 % WIDTH;
+        let expected = if x < WIDTH / 2 { LEFT_COLOR } else { RIGHT_COLOR };
+        assert_eq!(pixel, expected, "pixel at x = {x}");
+    }
+}
+
+#[test]
+fn transfer_queue_round_trips_image_contents() {
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 8;
+    const EXPECTED_COLOR: [u8; 4] = [0, 255, 0, 255];
+
+    let mut context = ValidationContext::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+    if !graph.use_transfer_queue(&mut context) {
+        // No transfer queue to test; the graphics path is covered by the other read-backs.
+        return;
+    }
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[TRANSFER ATTACHMENT]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+    graph.add_clear(target.view, ClearValue::Color([0.0, 1.0, 0.0, 1.0]));
+    graph.execute();
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+    assert_eq!(data.len() as u32, WIDTH * HEIGHT * 4);
+    for chunk in data.chunks_exact(4) {
+        assert_eq!(chunk, EXPECTED_COLOR);
+    }
+
+    let pattern: Vec<u8> = (0..WIDTH * HEIGHT * 4).map(|byte| byte as u8).collect();
+    let upload = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[TRANSFER UPLOAD]",
+            byte_size: WIDTH * HEIGHT * 4,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            initial_data: Some(&pattern),
+        })
+        .expect("create upload buffer");
+
+    graph.write_image(upload, target.view);
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+    assert_eq!(data, pattern);
+}
+
+#[test]
+fn multisampled_subpass_resolves_into_single_sample_target() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 8;
+    const EXPECTED_COLOR: [u8; 4] = [0, 0, 255, 255];
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[RESOLVE TARGET]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+
+    let mut subpass = SubpassInfo {
+        name: Some("msaa".to_string()),
+        samples: SampleCount::S4,
+        ..Default::default()
+    };
+    subpass.color_attachments[0] = Some(target.view);
+    subpass.clear_values[0] = Some(ClearValue::Color([0.0, 0.0, 1.0, 1.0]));
+    graph.add_subpass(&subpass, |stream| stream);
+    graph.execute();
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+    assert_eq!(data.len() as u32, WIDTH * HEIGHT * 4);
+    for chunk in data.chunks_exact(4) {
+        assert_eq!(chunk, EXPECTED_COLOR);
+    }
+}
+
+#[test]
+#[should_panic(expected = "needs a clear value")]
+fn multisampled_subpass_requires_color_clear_values() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[UNCLEARED RESOLVE TARGET]",
+        dim: [8, 8, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+
+    let mut subpass = SubpassInfo {
+        name: Some("msaa".to_string()),
+        samples: SampleCount::S4,
+        ..Default::default()
+    };
+    subpass.color_attachments[0] = Some(target.view);
+    graph.add_subpass(&subpass, |stream| stream);
+}
+
+#[test]
+fn persistent_history_keeps_previous_frame_output() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 8;
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+    let info = ImageInfo {
+        debug_name: "[HISTORY]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    };
+
+    let (first, _) = graph.persistent_history("history", &info);
+    graph.add_clear(first.view, ClearValue::Color([1.0, 0.0, 0.0, 1.0]));
+    graph.execute();
+
+    let (current, previous) = graph.persistent_history("history", &info);
+    assert_eq!(previous.view.img, first.view.img);
+    assert_ne!(current.view.img, first.view.img);
+    graph.add_clear(current.view, ClearValue::Color([0.0, 0.0, 1.0, 1.0]));
+    graph.execute();
+
+    for (image, expected) in [(previous, [255, 0, 0, 255]), (current, [0, 0, 255, 255])] {
+        let data: Vec<u8> = read_texels(&mut context, &mut graph, image.view, WIDTH * HEIGHT * 4);
+        for chunk in data.chunks_exact(4) {
+            assert_eq!(chunk, expected);
+        }
+    }
+}
+
+#[test]
+fn persistent_history_retires_resized_images_after_frames_in_flight() {
+    let mut context = ValidationContext::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+    let info = |size: u32| ImageInfo {
+        debug_name: "[HISTORY]",
+        dim: [size, size, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    };
+
+    let (first, _) = graph.persistent_history("history", &info(8));
+    graph.add_clear(first.view, ClearValue::Color([1.0, 0.0, 0.0, 1.0]));
+    graph.execute();
+
+    // Resizing while the first frame may still be in flight must not destroy its images.
+    for _ in 0..4 {
+        let (current, _) = graph.persistent_history("history", &info(16));
+        assert_ne!(current.view.img, first.view.img);
+        graph.add_clear(current.view, ClearValue::Color([0.0, 0.0, 1.0, 1.0]));
+        graph.execute();
+    }
+}
+
+#[test]
+fn clears_depth_and_stencil_separately() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 8;
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut graph = RenderGraph::new(&mut context);
+
+    let depth = graph.make_image(&ImageInfo {
+        debug_name: "[DEPTH STENCIL]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::D24S8,
+        ..Default::default()
+    });
+    let subpass = SubpassInfo {
+        viewport: Viewport {
+            area: FRect2D {
+                w: WIDTH as f32,
+                h: HEIGHT as f32,
+                ..Default::default()
+            },
+            scissor: Rect2D {
+                w: WIDTH,
+                h: HEIGHT,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        depth_attachment: Some(depth.view),
+        depth_clear: Some(ClearValue::depth_stencil(1.0, 0)),
+        stencil_clear: Some(5),
+        ..Default::default()
+    };
+    assert!(matches!(
+        subpass.depth_stencil_clear(),
+        Some(ClearValue::DepthStencil { depth, stencil: 5 }) if depth == 1.0
+    ));
+
+    graph.add_subpass(&subpass, |stream| stream);
+    graph.execute();
+
+    let data: Vec<u32> = read_texels(&mut context, &mut graph, depth.view, WIDTH * HEIGHT * 4);
+
+    // D24S8 depth copies out as 24-bit UNORM in the low bits of each texel.
+    assert!(data.iter().all(|texel| texel & 0x00FF_FFFF == 0x00FF_FFFF));
+}
+
+#[test]
+fn subpasses_receive_global_bind_tables() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let timing = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[GLOBAL TIMING]",
+            byte_size: 8,
+            visibility: MemoryVisibility::CpuAndGpu,
+            ..Default::default()
+        })
+        .expect("timing buffer");
+    let variables = [BindTableVariable {
+        var_type: BindTableVariableType::Uniform,
+        binding: 0,
+        count: 1,
+    }];
+    let layout = BindTableLayoutBuilder::new("[GLOBAL TIMING BTL]")
+        .shader(ShaderInfo {
+            shader_type: ShaderType::All,
+            variables: &variables,
+        })
+        .build(&mut context)
+        .expect("timing layout");
+    let table = context
+        .make_bind_table(&BindTableInfo {
+            debug_name: "[GLOBAL TIMING TABLE]",
+            layout,
+            bindings: &[IndexedBindingInfo {
+                resources: &[IndexedResource {
+                    resource: ShaderResource::ConstBuffer(BufferView::new(timing)),
+                    slot: 0,
+                }],
+                binding: 0,
+            }],
+            set: 0,
+        })
+        .expect("timing table");
+
+    let mut graph = RenderGraph::new(&mut context);
+    graph.set_global_bind_tables([Some(table), None, None, None]);
+    assert_eq!(graph.global_bind_tables()[0], Some(table));
+
+    let vertices = graph.make_buffer(&BufferInfo::default());
+    let indices = graph.make_buffer(&BufferInfo::default());
+    let args = graph.make_buffer(&BufferInfo {
+        debug_name: "[GLOBAL TABLE ARGS]",
+        byte_size: INDEXED_INDIRECT_STRIDE,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: None,
+    });
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[GLOBAL TABLE TARGET]",
+        dim: [16, 16, 1],
+        ..Default::default()
+    });
+
+    let seen = Arc::new(Mutex::new(None));
+    let recorded = Arc::clone(&seen);
+    graph.add_subpass_with_context(
+        &SubpassInfo {
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            ..Default::default()
+        },
+        &[],
+        move |stream, pass| {
+            *recorded.lock().unwrap() = Some(pass.bind_tables);
+            stream.draw_indirect(&IndirectDraw {
+                vertices: vertices.handle,
+                indices: indices.handle,
+                args,
+                draw_count: 0,
+                bind_tables: pass.bind_tables_with([None; 4]),
+            })
+        },
+    );
+
+    graph.execute();
+    context.sync_current_device();
+
+    let seen = seen.lock().unwrap().expect("subpass callback ran");
+    assert_eq!(seen, [Some(table), None, None, None]);
+}
+
+#[test]
+fn global_bind_tables_reach_subpass_draws() {
+    const WIDTH: u32 = 16;
+    const HEIGHT: u32 = 16;
+
+    let mut context = ValidationContext::headless(&Default::default()).expect("headless context");
+    let mut pso = PSOBuilder::new()
+        .vertex_compiled(Some(compile_shader(ShaderType::Vertex, FULLSCREEN_VERTEX)))
+        .fragment_compiled(Some(compile_shader(ShaderType::Fragment, TIMING_FRAGMENT)))
+        .set_attachment_format(0, Format::RGBA8)
+        .build(&mut context)
+        .expect("timing pipeline");
+    let timing = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[GLOBAL TIMING]",
+            byte_size: 16,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::UNIFORM,
+            initial_data: Some(bytemuck::cast_slice(&[1.0f32, 0.0, 0.0, 0.0])),
+        })
+        .expect("timing buffer");
+    pso.update_table(
+        "timing",
+        IndexedResource {
+            resource: ShaderResource::ConstBuffer(BufferView::new(timing)),
+            slot: 0,
+        },
+    );
+    let mut graph = RenderGraph::new(&mut context);
+    graph.set_global_bind_tables(pso.tables());
+
+    let target = graph.make_image(&ImageInfo {
+        debug_name: "[GLOBAL TABLE TARGET]",
+        dim: [WIDTH, HEIGHT, 1],
+        format: Format::RGBA8,
+        ..Default::default()
+    });
+    let geometry = graph.make_buffer(&BufferInfo {
+        debug_name: "[GLOBAL TABLE INDICES]",
+        byte_size: 12,
+        visibility: MemoryVisibility::Gpu,
+        usage: BufferUsage::ALL,
+        initial_data: Some(bytemuck::cast_slice(&[0u32, 1, 2])),
+    });
+    let viewport = Viewport {
+        area: FRect2D {
+            w: WIDTH as f32,
+            h: HEIGHT as f32,
+            ..Default::default()
+        },
+        scissor: Rect2D {
+            w: WIDTH,
+            h: HEIGHT,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pipeline = pso.handle;
+
+    graph.add_subpass_with_context(
+        &SubpassInfo {
+            viewport,
+            color_attachments: [Some(target.view), None, None, None, None, None, None, None],
+            clear_values: [
+                Some(ClearValue::Color([0.0, 0.0, 0.0, 1.0])),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            ..Default::default()
+        },
+        &[],
+        move |stream, pass| {
+            let stream = stream
+                .bind_graphics_pipeline(pipeline)
+                .update_viewport(&viewport);
+            // The draw names no tables of its own; set 0 comes from the graph.
+            pass.draw_indexed(
+                stream,
+                &DrawIndexed {
+                    vertices: geometry.handle,
+                    indices: geometry.handle,
+                    index_count: 3,
+                    ..Default::default()
+                },
+            )
+            .unbind_graphics_pipeline()
+        },
+    );
+
+    graph.execute();
+
+    let data: Vec<u8> = read_texels(&mut context, &mut graph, target.view, WIDTH * HEIGHT * 4);
+    assert!(data.chunks_exact(4).all(|texel| texel == [255, 0, 0, 255]));
+}
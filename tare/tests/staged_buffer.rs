@@ -0,0 +1,59 @@
+use dashi::*;
+use tare::utils::StagedBuffer;
+
+#[test]
+fn recorded_upload_lands_in_destination_buffer() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let values: Vec<u32> = (0..64).map(|i| i * i).collect();
+    let byte_size = (values.len() * std::mem::size_of::<u32>()) as u32;
+
+    let mut staging = StagedBuffer::new(
+        &mut context,
+        BufferInfo {
+            debug_name: "[UPLOAD STAGING]",
+            byte_size,
+            visibility: MemoryVisibility::Gpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        },
+    );
+    let destination = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[UPLOAD DESTINATION]",
+            byte_size,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        })
+        .expect("create destination buffer");
+
+    let mut ring = context
+        .make_command_ring(&CommandQueueInfo2 {
+            debug_name: "staged-buffer-upload",
+            parent: None,
+            queue_type: QueueType::Graphics,
+        })
+        .expect("create command ring for upload");
+    ring.record(|cmd| {
+        let upload = staging.record_upload(&values, destination);
+        upload.append(cmd).unwrap();
+    })
+    .expect("record upload commands");
+    ring.submit(&SubmitInfo::default())
+        .expect("submit upload commands");
+    ring.wait_all().expect("wait for upload");
+
+    let data = context
+        .map_buffer::<u32>(destination.into())
+        .expect("map destination buffer")
+        .to_vec();
+    context
+        .unmap_buffer(destination)
+        .expect("unmap destination buffer");
+
+    assert_eq!(data, values);
+}
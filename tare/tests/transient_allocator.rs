@@ -1,5 +1,7 @@
 use dashi::*;
-use tare::transient::TransientAllocator;
+use tare::transient::{
+    Ring, RingError, TransientAllocator, TransientConfig, TransientStats, render_pass_key,
+};
 
 #[test]
 fn transient_allocator_avoids_in_frame_reuse() {
@@ -112,3 +114,143 @@ fn transient_cubemap_lifetime_and_reuse() {
         "transient cubemap resources should be recycled across frames"
     );
 }
+
+#[test]
+fn prewarmed_images_are_reused_on_first_frame() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let mut allocator = TransientAllocator::new(&mut context);
+
+    let image_info = ImageInfo {
+        debug_name: "[PREWARMED IMAGE]",
+        dim: [16, 16, 1],
+        ..Default::default()
+    };
+
+    allocator.prewarm(std::slice::from_ref(&image_info), &[], 2);
+    assert_eq!(allocator.stats(), TransientStats::default());
+
+    let images: Vec<_> = (0..3).map(|_| allocator.make_image(&image_info)).collect();
+    assert_ne!(images[0].view.img, images[1].view.img);
+    assert_ne!(images[1].view.img, images[2].view.img);
+    assert_eq!(
+        allocator.stats(),
+        TransientStats {
+            images_reused: 2,
+            images_allocated: 1,
+            ..Default::default()
+        }
+    );
+
+    allocator.advance();
+}
+
+#[test]
+fn semaphores_outlive_images_with_a_higher_retire_threshold() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let config = TransientConfig {
+        retire_threshold: 1,
+        semaphore_retire_threshold: 1_000,
+    };
+    let mut allocator = TransientAllocator::with_config(&mut context, config);
+
+    let image_info = ImageInfo {
+        debug_name: "[RETIRED IMAGE]",
+        dim: [4, 4, 1],
+        ..Default::default()
+    };
+    allocator.make_image(&image_info);
+    let semaphore = allocator.make_semaphore();
+
+    // Long enough for both to return to the pools and the image to age out and be destroyed.
+    for _ in 0..8 {
+        allocator.advance();
+    }
+
+    allocator.make_image(&image_info);
+    assert_eq!(allocator.make_semaphore(), semaphore);
+    assert_eq!(
+        allocator.stats(),
+        TransientStats {
+            images_allocated: 2,
+            semaphores_reused: 1,
+            semaphores_allocated: 1,
+            ..Default::default()
+        }
+    );
+
+    allocator.advance();
+}
+
+#[test]
+fn ring_try_new_with_reports_insufficient_data() {
+    let short = Ring::<u32, 3>::try_new_with(&[1, 2]);
+    assert_eq!(
+        short.err(),
+        Some(RingError::InsufficientData {
+            required: 3,
+            provided: 2,
+        })
+    );
+
+    let mut ring = Ring::<u32, 3>::try_new_with(&[1, 2, 3, 4]).expect("enough data");
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        seen.push(*ring.data());
+        ring.advance();
+    }
+    assert_eq!(seen, [1, 2, 3]);
+}
+
+#[test]
+fn render_pass_key_is_locked() {
+    let viewport = Viewport {
+        area: FRect2D {
+            w: 64.0,
+            h: 32.0,
+            ..Default::default()
+        },
+        scissor: Rect2D {
+            w: 64,
+            h: 32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let key = |colors: &[AttachmentDescription]| {
+        render_pass_key(&RenderPassInfo {
+            debug_name: "locked",
+            viewport,
+            subpasses: &[SubpassDescription {
+                color_attachments: colors,
+                depth_stencil_attachment: None,
+                subpass_dependencies: &[],
+            }],
+        })
+    };
+
+    assert_eq!(key(&[]), 0x080f_28e4_b787_867f);
+
+    let rgba8 = AttachmentDescription {
+        format: Format::RGBA8,
+        ..Default::default()
+    };
+    let rgba32 = AttachmentDescription {
+        format: Format::RGBA32Uint,
+        ..Default::default()
+    };
+    assert_ne!(key(&[rgba8]), key(&[rgba32]));
+
+    let discarded = AttachmentDescription {
+        store_op: StoreOp::DontCare,
+        ..rgba8
+    };
+    assert_ne!(key(&[rgba8]), key(&[discarded]));
+}
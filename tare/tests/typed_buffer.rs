@@ -0,0 +1,73 @@
+use dashi::driver::command::CopyBuffer;
+use dashi::*;
+
+#[test]
+fn typed_buffer_round_trips_slice_contents() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let values: Vec<u32> = (0..16).map(|i| i * 3 + 1).collect();
+    let byte_size = (values.len() * std::mem::size_of::<u32>()) as u32;
+
+    let buffer = tare::make_typed_buffer(&mut context, "[TYPED]", &values, BufferUsage::ALL);
+
+    let readback = context
+        .make_buffer(&BufferInfo {
+            debug_name: "[TYPED READBACK]",
+            byte_size,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            ..Default::default()
+        })
+        .expect("create readback buffer");
+
+    let mut ring = context
+        .make_command_ring(&CommandQueueInfo2 {
+            debug_name: "typed-buffer-readback",
+            parent: None,
+            queue_type: QueueType::Graphics,
+        })
+        .expect("create command ring for readback");
+    ring.record(|cmd| {
+        CommandStream::new()
+            .begin()
+            .copy_buffers(&CopyBuffer {
+                src: buffer,
+                dst: readback,
+                src_offset: 0,
+                dst_offset: 0,
+                amount: byte_size,
+            })
+            .end()
+            .append(cmd)
+            .unwrap();
+    })
+    .expect("record readback commands");
+    ring.submit(&SubmitInfo::default())
+        .expect("submit readback commands");
+    ring.wait_all().expect("wait for readback");
+
+    let data = context
+        .map_buffer::<u32>(readback.into())
+        .expect("map readback buffer")
+        .to_vec();
+    context
+        .unmap_buffer(readback)
+        .expect("unmap readback buffer");
+
+    assert_eq!(data, values);
+}
+
+#[test]
+fn typed_buffer_accepts_empty_slices() {
+    unsafe {
+        std::env::set_var("DASHI_VALIDATION", "0");
+    }
+
+    let mut context = Context::headless(&Default::default()).expect("headless context");
+    let buffer = tare::make_typed_buffer::<u32>(&mut context, "[EMPTY]", &[], BufferUsage::ALL);
+
+    context.destroy_buffer(buffer);
+}